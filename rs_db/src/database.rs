@@ -1,49 +1,96 @@
 use crate::error_factory::create_error;
-use dirs_next;
 use rs_response::DataResponse;
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-const ERR_SRC: &str = "rs_db::connection::connect()";
+const ERR_SRC: &str = "database";
 
-pub fn connect(db_display_name: &str, db_name: &str) -> DataResponse<Connection> {
-    let db_dir = match dirs_next::data_dir() {
-        None => {
-            return Err(create_error(
-                "Could not access the database directory",
-                "The data directory path was not found",
-                ERR_SRC,
-            ));
-        }
-        Some(data_dir) => {
-            let db_dir = data_dir.join("com.renamed.app/db/");
-
-            fs::create_dir_all(db_dir.clone()).map_err(|e| {
-                create_error(
-                    "Could not access the database directory",
-                    e.to_string(),
-                    ERR_SRC,
-                )
-            })?;
-
-            db_dir
-        }
-    };
+/// How long a connection waits for another one to release a lock before failing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where the data of a single database is kept
+///
+/// # Variants:
+/// - `File` - A database file on disk
+/// - `Memory` - A named in-memory database, shared by every connection that
+///   opens the same name and dropped with the last of them
+pub enum DbFile {
+    File(PathBuf),
+    Memory(String),
+}
+
+/// Returns the default folder that holds every database
+pub fn default_db_dir() -> DataResponse<PathBuf> {
+    match dirs_next::data_dir() {
+        None => Err(create_error(
+            "Could not access the database directory",
+            "The data directory path was not found",
+            ERR_SRC,
+        )),
+        Some(data_dir) => Ok(data_dir.join("com.renamed.app/db/")),
+    }
+}
 
+/// Creates the folder that holds every database if needed
+pub fn create_db_dir(db_dir: &Path) -> DataResponse<()> {
+    fs::create_dir_all(db_dir).map_err(|e| {
+        create_error(
+            "Could not access the database directory",
+            format!("'{}': {}", db_dir.display(), e),
+            ERR_SRC,
+        )
+    })
+}
+
+/// Returns the path of a database file inside `db_dir`
+pub fn db_path(db_dir: &Path, db_name: &str) -> PathBuf {
     let mut db_filename = db_name.to_string();
     if !db_filename.ends_with(".db") {
         db_filename += ".db"
     }
 
-    let db_path = db_dir.join(db_filename);
+    db_dir.join(db_filename)
+}
 
-    let db = Connection::open(db_path).map_err(|e| {
+/// Opens a database and prepares the connection to be shared
+///
+/// **NOTE:** Database files are switched to WAL mode, so readers never block
+/// the writer and the writer never blocks readers. A connection that finds
+/// the database locked retries for up to `BUSY_TIMEOUT` before failing
+pub fn open(db_display_name: &str, db_file: &DbFile) -> DataResponse<Connection> {
+    let open_error = |e: rusqlite::Error| {
         create_error(
             format!("Unable to open the '{}' database", db_display_name),
             e.to_string(),
             ERR_SRC,
         )
-    })?;
+    };
+
+    let db = match db_file {
+        DbFile::File(db_path) => {
+            let db = Connection::open(db_path).map_err(open_error)?;
+
+            db.pragma_update_and_check(None, "journal_mode", "WAL", |row| {
+                row.get::<usize, String>(0)
+            })
+            .map_err(open_error)?;
+            db.pragma_update(None, "synchronous", "NORMAL")
+                .map_err(open_error)?;
+
+            db
+        }
+        DbFile::Memory(name) => Connection::open_with_flags(
+            format!("file:/{}?vfs=memdb", name),
+            OpenFlags::default() | OpenFlags::SQLITE_OPEN_URI,
+        )
+        .map_err(open_error)?,
+    };
+
+    db.busy_timeout(BUSY_TIMEOUT).map_err(open_error)?;
+    db.pragma_update(None, "foreign_keys", "ON")
+        .map_err(open_error)?;
 
     Ok(db)
 }
@@ -1,4 +1,4 @@
-use rs_response::ErrorRepsonse;
+use rs_response::{ErrorRepsonse, OkResponse};
 
 pub fn create_error(
     message: impl Into<String>,
@@ -12,3 +12,16 @@ pub fn create_error(
         String::from("rs_db::") + source,
     )
 }
+
+pub fn create_warning(
+    message: impl Into<String>,
+    details: impl Into<String>,
+    source: &str,
+) -> OkResponse {
+    OkResponse::new_warning(
+        "Database",
+        message,
+        details,
+        String::from("rs_db::") + source,
+    )
+}
@@ -0,0 +1,7 @@
+pub(crate) mod history;
+pub use history::{HistoryBatch, HistoryRename};
+
+mod init;
+pub use init::initialize_history_db;
+
+mod schema;
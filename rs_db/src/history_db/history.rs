@@ -0,0 +1,270 @@
+use crate::error_factory::create_error;
+use crate::query::{db_error, FromRow, Query};
+use crate::DbManager;
+use rs_response::DataResponse;
+use rusqlite::params;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ERR_SRC: &str = "history_db::history::HistoryBatch";
+pub const DB_NAME: &str = "history";
+pub const DB_DISPLAY_NAME: &str = "History";
+
+/// A single file of a `HistoryBatch`
+///
+/// # Properties:
+/// - `old_path`: `PathBuf` - The path of the file before the batch
+/// - `new_path`: `PathBuf` - The path of the file after the batch
+/// - `size`: `Option<u64>` - The size of the file after the batch, in bytes
+/// - `modified`: `Option<i64>` - The modification time of the file after the batch,
+///   in nanoseconds since the Unix epoch
+///
+/// **NOTE:** `size` and `modified` are `None` for batches recorded by older
+/// versions of the app, and when they could not be read
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HistoryRename {
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+    pub size: Option<u64>,
+    pub modified: Option<i64>,
+}
+
+/// An executed rename batch, as recorded in the 'History' database
+///
+/// # Properties:
+/// - `id`: `i64` - The id of the batch
+/// - `timestamp`: `i64` - When the batch was executed, in seconds since the Unix epoch
+/// - `root`: `PathBuf` - The folder the batch was run on
+/// - `pipeline`: `Vec<String>` - The description of every rule of the pipeline, in order
+/// - `undone`: `bool` - Whether the batch has been undone
+/// - `rename_count`: `usize` - The number of renamed files
+/// - `renames`: `Vec<HistoryRename>` - Every renamed file. Empty when listed with `list`
+///
+/// # Methods:
+/// - `record` - Records an executed batch and returns its id
+/// - `read` - Reads a batch and all of its renamed files
+/// - `list` - Lists the most recent batches, without their renamed files
+/// - `mark_undone` - Marks a batch as undone
+///
+/// # Example:
+/// ```
+/// use rs_db::history_db::HistoryBatch;
+/// use rs_db::DbManager;
+/// use rs_response::DataResponse;
+///
+/// fn last_batch(db_manager: &DbManager) -> DataResponse<Option<HistoryBatch>> {
+///   let mut batches = HistoryBatch::list(db_manager, 1)?;
+///
+///   Ok(batches.pop())
+/// }
+/// ```
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HistoryBatch {
+    pub id: i64,
+    pub timestamp: i64,
+    pub root: PathBuf,
+    pub pipeline: Vec<String>,
+    pub undone: bool,
+    pub rename_count: usize,
+    pub renames: Vec<HistoryRename>,
+}
+impl HistoryBatch {
+    /// Records an executed batch and returns its id
+    ///
+    /// # Arguments:
+    /// - `db_manager`: `&DbManager` - The shared database connections
+    /// - `root`: `&Path` - The folder the batch was run on
+    /// - `pipeline`: `&[String]` - The description of every rule of the pipeline, in order
+    /// - `renames`: `&[HistoryRename]` - Every renamed file
+    ///
+    /// # Example:
+    /// ```
+    /// use rs_db::history_db::{initialize_history_db, HistoryBatch, HistoryRename};
+    /// use rs_db::DbManager;
+    /// use std::path::PathBuf;
+    ///
+    /// let db_manager = DbManager::in_memory().unwrap();
+    /// initialize_history_db(&db_manager).unwrap();
+    ///
+    /// let renames = vec![HistoryRename {
+    ///   old_path: PathBuf::from("photos/IMG_0001.jpg"),
+    ///   new_path: PathBuf::from("photos/beach.jpg"),
+    ///   size: Some(2048),
+    ///   modified: None,
+    /// }];
+    ///
+    /// let pipeline = vec![String::from("Change the letter case to Lower")];
+    /// let id = HistoryBatch::record(&db_manager, "photos".as_ref(), &pipeline, &renames).unwrap();
+    /// let batch = HistoryBatch::read(&db_manager, id).unwrap().unwrap();
+    ///
+    /// assert_eq!(batch.rename_count, 1);
+    /// assert_eq!(batch.renames[0].size, Some(2048));
+    /// ```
+    pub fn record(
+        db_manager: &DbManager,
+        root: &Path,
+        pipeline: &[String],
+        renames: &[HistoryRename],
+    ) -> DataResponse<i64> {
+        let mut db = db_manager.history()?;
+        let message = format!("Could not write '{}' data to the database", DB_DISPLAY_NAME);
+        let db_error = db_error(message.clone(), ERR_SRC);
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        let pipeline = serde_json::to_string(pipeline)
+            .map_err(|e| create_error(message.clone(), e.to_string(), ERR_SRC))?;
+
+        let tx = db.transaction().map_err(&db_error)?;
+        let id = Query::new(
+            DB_DISPLAY_NAME,
+            "INSERT INTO history (timestamp, root, pipeline, undone) VALUES (?1, ?2, ?3, 0);",
+            ERR_SRC,
+        )
+        .insert(&tx, params![timestamp, path_to_sql(root), pipeline])?;
+
+        let insert_rename = Query::new(
+            DB_DISPLAY_NAME,
+            "INSERT INTO history_renames (batch_id, seq, old_path, new_path, size, modified)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6);",
+            ERR_SRC,
+        );
+        for (seq, rename) in renames.iter().enumerate() {
+            insert_rename.execute(
+                &tx,
+                params![
+                    id,
+                    seq as i64,
+                    path_to_sql(&rename.old_path),
+                    path_to_sql(&rename.new_path),
+                    rename.size.map(|size| size as i64),
+                    rename.modified
+                ],
+            )?;
+        }
+
+        tx.commit().map_err(db_error)?;
+
+        Ok(id)
+    }
+
+    /// Reads a batch and all of its renamed files
+    ///
+    /// **NOTE:** Returns `None` if there is no batch with this id
+    ///
+    /// # Arguments:
+    /// - `db_manager`: `&DbManager` - The shared database connections
+    /// - `id`: `i64` - The id of the batch
+    pub fn read(db_manager: &DbManager, id: i64) -> DataResponse<Option<Self>> {
+        let db = db_manager.history()?;
+
+        let batch = Query::new(
+            DB_DISPLAY_NAME,
+            &format!("{} WHERE h.id = ?1 GROUP BY h.id;", SELECT_BATCHES),
+            ERR_SRC,
+        )
+        .one::<Self>(&db, params![id])?;
+
+        let mut batch = match batch {
+            Some(batch) => batch,
+            None => return Ok(None),
+        };
+
+        batch.renames = Query::new(
+            DB_DISPLAY_NAME,
+            "SELECT old_path, new_path, size, modified
+            FROM history_renames WHERE batch_id = ?1 ORDER BY seq;",
+            ERR_SRC,
+        )
+        .all(&db, params![id])?;
+
+        Ok(Some(batch))
+    }
+
+    /// Lists the most recent batches, newest first, without their renamed files
+    ///
+    /// # Arguments:
+    /// - `db_manager`: `&DbManager` - The shared database connections
+    /// - `limit`: `usize` - The maximum number of batches to list
+    pub fn list(db_manager: &DbManager, limit: usize) -> DataResponse<Vec<Self>> {
+        let db = db_manager.history()?;
+
+        Query::new(
+            DB_DISPLAY_NAME,
+            &format!(
+                "{} GROUP BY h.id ORDER BY h.id DESC LIMIT ?1;",
+                SELECT_BATCHES
+            ),
+            ERR_SRC,
+        )
+        .all(&db, params![limit as i64])
+    }
+
+    /// Marks a batch as undone, once its files have been renamed back, as
+    /// `rs_rename::undo_batch` does
+    ///
+    /// # Arguments:
+    /// - `db_manager`: `&DbManager` - The shared database connections
+    /// - `id`: `i64` - The id of the batch
+    pub fn mark_undone(db_manager: &DbManager, id: i64) -> DataResponse<()> {
+        let db = db_manager.history()?;
+
+        Query::new(
+            DB_DISPLAY_NAME,
+            "UPDATE history SET undone = 1 WHERE id = ?1;",
+            ERR_SRC,
+        )
+        .execute(&db, params![id])?;
+
+        Ok(())
+    }
+}
+
+const SELECT_BATCHES: &str = "
+    SELECT h.id, h.timestamp, h.root, h.pipeline, h.undone, COUNT(r.seq) AS rename_count
+    FROM history h
+    LEFT JOIN history_renames r ON r.batch_id = h.id
+";
+
+/// Maps a row of `SELECT_BATCHES` to a `HistoryBatch` without its renamed files
+impl FromRow for HistoryBatch {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let pipeline: String = row.get("pipeline")?;
+        let pipeline = serde_json::from_str(&pipeline).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                row.as_ref().column_index("pipeline").unwrap_or_default(),
+                rusqlite::types::Type::Text,
+                Box::new(e),
+            )
+        })?;
+
+        Ok(Self {
+            id: row.get("id")?,
+            timestamp: row.get("timestamp")?,
+            root: PathBuf::from(row.get::<&str, String>("root")?),
+            pipeline,
+            undone: row.get::<&str, i64>("undone")? != 0,
+            rename_count: row.get::<&str, i64>("rename_count")? as usize,
+            renames: Vec::new(),
+        })
+    }
+}
+
+impl FromRow for HistoryRename {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            old_path: PathBuf::from(row.get::<&str, String>("old_path")?),
+            new_path: PathBuf::from(row.get::<&str, String>("new_path")?),
+            size: row
+                .get::<&str, Option<i64>>("size")?
+                .map(|size| size as u64),
+            modified: row.get("modified")?,
+        })
+    }
+}
+
+fn path_to_sql(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
@@ -0,0 +1,30 @@
+use super::history::DB_DISPLAY_NAME;
+use super::schema::{MIGRATIONS, TABLES};
+use crate::schema::{migrate, validate_tables};
+use crate::DbManager;
+use rs_response::{OkResponse, Response};
+
+/// Upgrades the 'History' database to the latest schema and checks its tables
+///
+/// **NOTE:** Should be called once on startup, before the history is read
+///
+/// # Arguments:
+/// - `db_manager`: `&DbManager` - The shared database connections
+pub fn initialize_history_db(db_manager: &DbManager) -> Response {
+    let mut db = db_manager.history()?;
+
+    let applied = migrate(&mut db, DB_DISPLAY_NAME, MIGRATIONS)?;
+    validate_tables(&db, DB_DISPLAY_NAME, TABLES)?;
+
+    Ok(OkResponse::new_info(
+        "Database",
+        match applied {
+            0 => String::from("The 'History' database has been initialized"),
+            _ => format!(
+                "The 'History' database has been upgraded with {} migration{}",
+                applied,
+                if applied == 1 { "" } else { "s" }
+            ),
+        },
+    ))
+}
@@ -0,0 +1,109 @@
+use crate::schema::{ColumnSchema, Migration, TableSchema};
+
+/// Every migration of the 'History' database, in order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create the 'history' and 'history_renames' tables",
+        sql: "CREATE TABLE IF NOT EXISTS history (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        timestamp INTEGER NOT NULL,
+        root TEXT NOT NULL,
+        pipeline TEXT NOT NULL,
+        undone INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE TABLE IF NOT EXISTS history_renames (
+        batch_id INTEGER NOT NULL REFERENCES history (id) ON DELETE CASCADE,
+        seq INTEGER NOT NULL,
+        old_path TEXT NOT NULL,
+        new_path TEXT NOT NULL,
+        PRIMARY KEY (batch_id, seq)
+    );",
+    },
+    Migration {
+        version: 2,
+        description: "Add the size and modification time of renamed files to 'history_renames'",
+        sql: "ALTER TABLE history_renames ADD COLUMN size INTEGER;
+        ALTER TABLE history_renames ADD COLUMN modified INTEGER;",
+    },
+];
+
+/// The tables of the 'History' database after every migration
+pub const TABLES: &[TableSchema] = &[
+    TableSchema {
+        name: "history",
+        columns: &[
+            ColumnSchema {
+                name: "id",
+                sql_type: "INTEGER",
+                not_null: false,
+                primary_key: true,
+            },
+            ColumnSchema {
+                name: "timestamp",
+                sql_type: "INTEGER",
+                not_null: true,
+                primary_key: false,
+            },
+            ColumnSchema {
+                name: "root",
+                sql_type: "TEXT",
+                not_null: true,
+                primary_key: false,
+            },
+            ColumnSchema {
+                name: "pipeline",
+                sql_type: "TEXT",
+                not_null: true,
+                primary_key: false,
+            },
+            ColumnSchema {
+                name: "undone",
+                sql_type: "INTEGER",
+                not_null: true,
+                primary_key: false,
+            },
+        ],
+    },
+    TableSchema {
+        name: "history_renames",
+        columns: &[
+            ColumnSchema {
+                name: "batch_id",
+                sql_type: "INTEGER",
+                not_null: true,
+                primary_key: true,
+            },
+            ColumnSchema {
+                name: "seq",
+                sql_type: "INTEGER",
+                not_null: true,
+                primary_key: true,
+            },
+            ColumnSchema {
+                name: "old_path",
+                sql_type: "TEXT",
+                not_null: true,
+                primary_key: false,
+            },
+            ColumnSchema {
+                name: "new_path",
+                sql_type: "TEXT",
+                not_null: true,
+                primary_key: false,
+            },
+            ColumnSchema {
+                name: "size",
+                sql_type: "INTEGER",
+                not_null: false,
+                primary_key: false,
+            },
+            ColumnSchema {
+                name: "modified",
+                sql_type: "INTEGER",
+                not_null: false,
+                primary_key: false,
+            },
+        ],
+    },
+];
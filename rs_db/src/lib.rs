@@ -1,4 +1,10 @@
 mod database;
 mod error_factory;
+mod manager;
+mod query;
+mod schema;
 
+pub mod history_db;
 pub mod settings_db;
+
+pub use manager::{DbLocation, DbManager, DB_DIR_ENV, PORTABLE_MARKER};
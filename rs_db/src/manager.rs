@@ -0,0 +1,125 @@
+mod location;
+mod pool;
+
+pub use location::{DbLocation, DB_DIR_ENV, PORTABLE_MARKER};
+
+use crate::database::{self, DbFile};
+use crate::history_db::history;
+use crate::settings_db::settings;
+use pool::{Pool, PooledConnection};
+use rs_response::DataResponse;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Tells the in-memory databases of different `DbManager`s apart
+static IN_MEMORY_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Shared access to every database of the app
+///
+/// Create it once on startup and keep it for the lifetime of the app, for
+/// example in Tauri's managed state. Every function that reads or writes a
+/// database takes it by reference
+///
+/// **NOTE:** `DbManager` is `Send + Sync`. Each database keeps a small pool
+/// of connections in WAL mode with a busy timeout, so commands running at
+/// the same time can read while another one writes
+///
+/// # Methods:
+/// - `new` - Creates a `DbManager` for the databases in the default location
+/// - `with_location` - Creates a `DbManager` for the databases in a `DbLocation`
+/// - `in_memory` - Creates a `DbManager` for new, empty in-memory databases
+/// - `location` - The location of the databases
+///
+/// # Example:
+/// ```
+/// use rs_db::settings_db::{initialize_settings_db, Settings};
+/// use rs_db::DbManager;
+/// use rs_response::ResponseWithData;
+///
+/// fn startup() -> ResponseWithData<Settings> {
+///   let db = DbManager::new()?;
+///
+///   initialize_settings_db(&db)?;
+///
+///   Settings::read(&db)
+/// }
+/// ```
+pub struct DbManager {
+    location: DbLocation,
+    settings: Pool,
+    history: Pool,
+}
+impl DbManager {
+    /// Creates a `DbManager` for the databases in the default location
+    ///
+    /// **NOTE:** No database file is opened until it is first used
+    pub fn new() -> DataResponse<Self> {
+        Self::with_location(DbLocation::Default)
+    }
+
+    /// Creates a `DbManager` for the databases in a `DbLocation`
+    ///
+    /// **NOTE:** The folder is created if needed. No database file is opened
+    /// until it is first used
+    ///
+    /// # Arguments:
+    /// - `location`: `DbLocation` - Where the databases are kept
+    pub fn with_location(location: DbLocation) -> DataResponse<Self> {
+        let db_dir = location.db_dir()?;
+        if let Some(db_dir) = &db_dir {
+            database::create_db_dir(db_dir)?;
+        }
+
+        let memory_id = IN_MEMORY_ID.fetch_add(1, Ordering::Relaxed);
+        let db_file = |db_name: &str| match &db_dir {
+            Some(db_dir) => DbFile::File(database::db_path(db_dir, db_name)),
+            None => DbFile::Memory(format!(
+                "renamed-{}-{}-{}",
+                process::id(),
+                memory_id,
+                db_name
+            )),
+        };
+
+        Ok(Self {
+            settings: Pool::new(settings::DB_DISPLAY_NAME, db_file(settings::DB_NAME))?,
+            history: Pool::new(history::DB_DISPLAY_NAME, db_file(history::DB_NAME))?,
+            location,
+        })
+    }
+
+    /// Creates a `DbManager` for new, empty in-memory databases
+    ///
+    /// **NOTE:** Every `DbManager` created this way has its own databases,
+    /// which are dropped with it. Useful for development and tests, as the
+    /// user's data is never touched
+    ///
+    /// # Example:
+    /// ```
+    /// use rs_db::settings_db::{initialize_settings_db, Settings};
+    /// use rs_db::DbManager;
+    ///
+    /// let db = DbManager::in_memory().unwrap();
+    /// initialize_settings_db(&db).unwrap();
+    ///
+    /// assert!(Settings::read(&db).is_ok());
+    /// ```
+    pub fn in_memory() -> DataResponse<Self> {
+        Self::with_location(DbLocation::InMemory)
+    }
+
+    /// The location of the databases
+    pub fn location(&self) -> &DbLocation {
+        &self.location
+    }
+
+    /// Borrows a connection to the 'Settings' database
+    pub(crate) fn settings(&self) -> DataResponse<PooledConnection<'_>> {
+        self.settings.get()
+    }
+
+    /// Borrows a connection to the 'History' database
+    pub(crate) fn history(&self) -> DataResponse<PooledConnection<'_>> {
+        self.history.get()
+    }
+}
@@ -0,0 +1,257 @@
+use crate::database;
+use crate::error_factory::create_error;
+use rs_response::DataResponse;
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+const ERR_SRC: &str = "manager::location::DbLocation";
+
+/// The environment variable that overrides the database folder
+///
+/// **NOTE:** Set it to `:memory:` to use in-memory databases
+pub const DB_DIR_ENV: &str = "RENAMED_DB_DIR";
+
+/// The name of the file that switches the app to portable mode when it is
+/// found next to the executable
+pub const PORTABLE_MARKER: &str = "portable";
+
+/// Where the app keeps its databases
+///
+/// # Variants:
+/// - `Default` - The user's data folder, in `com.renamed.app/db/`
+/// - `Directory` - A specific folder
+/// - `Portable` - A `db` folder next to the executable, so the app and its data
+///   can be carried together, e.g. on a USB stick
+/// - `InMemory` - Databases that only live as long as the `DbManager`. Nothing
+///   is read from or written to the disk
+///
+/// # Methods:
+/// - `from_args` - Picks the location from the command line, the environment
+///   and the portable marker file
+/// - `db_dir` - Resolves the folder of the databases
+///
+/// # Example:
+/// ```
+/// use rs_db::{DbLocation, DbManager};
+/// use rs_response::DataResponse;
+///
+/// fn open_databases() -> DataResponse<DbManager> {
+///   let location = DbLocation::from_args(std::env::args().skip(1))?;
+///
+///   DbManager::with_location(location)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DbLocation {
+    Default,
+    Directory(PathBuf),
+    Portable,
+    InMemory,
+}
+impl DbLocation {
+    /// Picks the location from the command line, the environment and the
+    /// portable marker file
+    ///
+    /// In order of priority:
+    /// 1. The `--db-dir <path>`, `--portable` or `--in-memory` flags
+    /// 2. The `RENAMED_DB_DIR` environment variable
+    /// 3. Portable mode, if a `portable` file is next to the executable
+    /// 4. The default location
+    ///
+    /// **NOTE:** Unknown arguments are ignored, so the arguments of the whole
+    /// app can be passed as they are
+    ///
+    /// # Arguments:
+    /// - `args`: `impl IntoIterator<Item = String>` - The command line arguments,
+    ///   without the executable path
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> DataResponse<Self> {
+        Self::resolve(args, env::var_os(DB_DIR_ENV), exe_dir())
+    }
+
+    /// Picks the location like `from_args`, with the value of the
+    /// environment variable and the folder of the executable given
+    fn resolve(
+        args: impl IntoIterator<Item = String>,
+        env_dir: Option<OsString>,
+        exe_dir: Option<PathBuf>,
+    ) -> DataResponse<Self> {
+        let mut args = args.into_iter();
+        let mut location = None;
+
+        while let Some(arg) = args.next() {
+            let flag_location = match arg.as_str() {
+                "--portable" => Self::Portable,
+                "--in-memory" => Self::InMemory,
+                "--db-dir" => match args.next() {
+                    Some(dir) => Self::from_dir(dir),
+                    None => {
+                        return Err(create_error(
+                            "Invalid database location",
+                            "The '--db-dir' flag requires a folder",
+                            ERR_SRC,
+                        ))
+                    }
+                },
+                _ => match arg.strip_prefix("--db-dir=") {
+                    Some(dir) => Self::from_dir(dir),
+                    None => continue,
+                },
+            };
+
+            if location.is_some() {
+                return Err(create_error(
+                    "Invalid database location",
+                    "Only one of '--db-dir', '--portable' and '--in-memory' can be used",
+                    ERR_SRC,
+                ));
+            }
+            location = Some(flag_location);
+        }
+
+        if let Some(location) = location {
+            return Ok(location);
+        }
+
+        if let Some(dir) = env_dir.filter(|dir| !dir.is_empty()) {
+            return Ok(Self::from_dir(dir.to_string_lossy()));
+        }
+
+        let portable = exe_dir
+            .map(|exe_dir| exe_dir.join(PORTABLE_MARKER).is_file())
+            .unwrap_or(false);
+
+        Ok(match portable {
+            true => Self::Portable,
+            false => Self::Default,
+        })
+    }
+
+    /// Resolves the folder of the databases
+    ///
+    /// **NOTE:** Returns `None` for `InMemory`
+    pub fn db_dir(&self) -> DataResponse<Option<PathBuf>> {
+        match self {
+            Self::Default => database::default_db_dir().map(Some),
+            Self::Directory(dir) => Ok(Some(dir.clone())),
+            Self::Portable => match exe_dir() {
+                Some(exe_dir) => Ok(Some(exe_dir.join("db"))),
+                None => Err(create_error(
+                    "Could not access the database directory",
+                    "The folder of the executable was not found",
+                    ERR_SRC,
+                )),
+            },
+            Self::InMemory => Ok(None),
+        }
+    }
+
+    fn from_dir(dir: impl AsRef<str>) -> Self {
+        match dir.as_ref() {
+            ":memory:" => Self::InMemory,
+            dir => Self::Directory(PathBuf::from(dir)),
+        }
+    }
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn resolve(flags: &[&str], env_dir: Option<&str>, portable: bool) -> DataResponse<DbLocation> {
+        // Every call gets its own folder, as the tests run in parallel
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let exe_dir = env::temp_dir().join(format!(
+            "rs_db-location-{}-{}",
+            CALLS.fetch_add(1, Ordering::Relaxed),
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&exe_dir).unwrap();
+        if portable {
+            std::fs::write(exe_dir.join(PORTABLE_MARKER), "").unwrap();
+        }
+
+        let location = DbLocation::resolve(
+            args(flags),
+            env_dir.map(OsString::from),
+            Some(exe_dir.clone()),
+        );
+        std::fs::remove_dir_all(&exe_dir).unwrap();
+        location
+    }
+
+    #[test]
+    fn reads_the_flags() {
+        assert_eq!(
+            resolve(&["--db-dir", "data"], None, false).unwrap(),
+            DbLocation::Directory(PathBuf::from("data"))
+        );
+        assert_eq!(
+            resolve(&["--verbose", "--db-dir=data"], None, false).unwrap(),
+            DbLocation::Directory(PathBuf::from("data"))
+        );
+        assert_eq!(
+            resolve(&["--db-dir", ":memory:"], None, false).unwrap(),
+            DbLocation::InMemory
+        );
+        assert_eq!(
+            resolve(&["--portable"], None, false).unwrap(),
+            DbLocation::Portable
+        );
+        assert_eq!(
+            resolve(&["--in-memory"], None, false).unwrap(),
+            DbLocation::InMemory
+        );
+    }
+
+    #[test]
+    fn refuses_invalid_flags() {
+        assert!(resolve(&["--db-dir"], None, false).is_err());
+        assert!(resolve(&["--portable", "--in-memory"], None, false).is_err());
+    }
+
+    #[test]
+    fn prefers_flags_then_the_environment_then_portable_mode() {
+        assert_eq!(
+            resolve(&["--in-memory"], Some("from-env"), true).unwrap(),
+            DbLocation::InMemory
+        );
+        assert_eq!(
+            resolve(&[], Some("from-env"), true).unwrap(),
+            DbLocation::Directory(PathBuf::from("from-env"))
+        );
+        assert_eq!(
+            resolve(&[], Some(":memory:"), true).unwrap(),
+            DbLocation::InMemory
+        );
+        assert_eq!(resolve(&[], Some(""), true).unwrap(), DbLocation::Portable);
+        assert_eq!(resolve(&[], None, true).unwrap(), DbLocation::Portable);
+        assert_eq!(resolve(&[], None, false).unwrap(), DbLocation::Default);
+    }
+
+    #[test]
+    fn resolves_the_folder() {
+        assert_eq!(DbLocation::InMemory.db_dir().unwrap(), None);
+        assert_eq!(
+            DbLocation::Directory(PathBuf::from("data"))
+                .db_dir()
+                .unwrap(),
+            Some(PathBuf::from("data"))
+        );
+        assert_eq!(
+            DbLocation::Portable.db_dir().unwrap(),
+            exe_dir().map(|exe_dir| exe_dir.join("db"))
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use crate::database::{self, DbFile};
+use rs_response::DataResponse;
+use rusqlite::Connection;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+/// The number of idle connections kept open for each database
+const MAX_IDLE: usize = 4;
+
+/// The connections to a single database, shared between threads
+///
+/// **NOTE:** Connections are opened on demand and handed back to the pool
+/// when the `PooledConnection` is dropped, so concurrent commands each get
+/// their own connection instead of waiting on a single one
+///
+/// **NOTE:** An in-memory database is dropped with its last connection, so
+/// the pool keeps one open for its whole lifetime
+pub struct Pool {
+    db_display_name: &'static str,
+    db_file: DbFile,
+    idle: Mutex<Vec<Connection>>,
+    _keep_alive: Option<Mutex<Connection>>,
+}
+impl Pool {
+    pub fn new(db_display_name: &'static str, db_file: DbFile) -> DataResponse<Self> {
+        let keep_alive = match db_file {
+            DbFile::File(_) => None,
+            DbFile::Memory(_) => Some(Mutex::new(database::open(db_display_name, &db_file)?)),
+        };
+
+        Ok(Self {
+            db_display_name,
+            db_file,
+            idle: Mutex::new(Vec::new()),
+            _keep_alive: keep_alive,
+        })
+    }
+
+    /// Takes an idle connection, or opens a new one if there is none
+    pub fn get(&self) -> DataResponse<PooledConnection<'_>> {
+        let idle = self.idle.lock().ok().and_then(|mut idle| idle.pop());
+
+        let db = match idle {
+            Some(db) => db,
+            None => database::open(self.db_display_name, &self.db_file)?,
+        };
+
+        Ok(PooledConnection {
+            pool: self,
+            db: Some(db),
+        })
+    }
+}
+
+/// A connection borrowed from a `Pool`
+///
+/// **NOTE:** Dereferences to a `rusqlite::Connection`
+pub struct PooledConnection<'a> {
+    pool: &'a Pool,
+    db: Option<Connection>,
+}
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.db
+            .as_ref()
+            .expect("the connection is only taken on drop")
+    }
+}
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.db
+            .as_mut()
+            .expect("the connection is only taken on drop")
+    }
+}
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        let db = match self.db.take() {
+            Some(db) => db,
+            None => return,
+        };
+
+        // A connection left inside a transaction (after a panic) is closed
+        // instead of being handed to the next caller
+        if !db.is_autocommit() {
+            return;
+        }
+
+        if let Ok(mut idle) = self.pool.idle.lock() {
+            if idle.len() < MAX_IDLE {
+                idle.push(db);
+            }
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use crate::error_factory::create_error;
+use rs_response::{DataResponse, ErrorRepsonse};
+use rusqlite::{Connection, ErrorCode, OptionalExtension, Params, Row};
+
+/// Builds a value from a result row, reading the columns by name
+pub(crate) trait FromRow: Sized {
+    fn from_row(row: &Row) -> rusqlite::Result<Self>;
+}
+
+/// A statement with bound parameters, run against one of the app's databases
+///
+/// **NOTE:** Values must always be bound with `?1`, `?2`... placeholders and
+/// never formatted into the SQL
+///
+/// **NOTE:** Every rusqlite error is converted to an `ErrorRepsonse` through
+/// `create_error`, with a message naming the database and whether it was
+/// being read or written
+///
+/// # Properties *(private)*:
+/// - `db_display_name`: `&str` - The name of the database to show to the user
+/// - `sql`: `&str` - The statement to run
+/// - `source`: `&str` - The path of the caller, used as the error source
+///
+/// # Methods:
+/// - `new` - Creates a new `Query`
+/// - `execute` - Runs a statement that writes, returning the number of changed rows
+/// - `insert` - Runs an `INSERT`, returning the id of the new row
+/// - `one` - Reads the first matching row, if any
+/// - `all` - Reads every matching row
+pub(crate) struct Query<'a> {
+    db_display_name: &'a str,
+    sql: &'a str,
+    source: &'a str,
+}
+impl<'a> Query<'a> {
+    /// Creates a new `Query`
+    ///
+    /// # Arguments:
+    /// - `db_display_name`: `&str` - The name of the database to show to the user
+    /// - `sql`: `&str` - The statement to run
+    /// - `source`: `&str` - The path of the caller, used as the error source
+    pub fn new(db_display_name: &'a str, sql: &'a str, source: &'a str) -> Self {
+        Self {
+            db_display_name,
+            sql,
+            source,
+        }
+    }
+
+    /// Runs a statement that writes, returning the number of changed rows
+    pub fn execute(&self, db: &Connection, params: impl Params) -> DataResponse<usize> {
+        let mut stmt = db
+            .prepare_cached(self.sql)
+            .map_err(|e| self.write_error(e))?;
+
+        stmt.execute(params).map_err(|e| self.write_error(e))
+    }
+
+    /// Runs an `INSERT`, returning the id of the new row
+    pub fn insert(&self, db: &Connection, params: impl Params) -> DataResponse<i64> {
+        let mut stmt = db
+            .prepare_cached(self.sql)
+            .map_err(|e| self.write_error(e))?;
+
+        stmt.insert(params).map_err(|e| self.write_error(e))
+    }
+
+    /// Reads the first matching row, if any
+    pub fn one<T: FromRow>(&self, db: &Connection, params: impl Params) -> DataResponse<Option<T>> {
+        let mut stmt = db
+            .prepare_cached(self.sql)
+            .map_err(|e| self.read_error(e))?;
+
+        stmt.query_row(params, T::from_row)
+            .optional()
+            .map_err(|e| self.read_error(e))
+    }
+
+    /// Reads every matching row
+    pub fn all<T: FromRow>(&self, db: &Connection, params: impl Params) -> DataResponse<Vec<T>> {
+        let mut stmt = db
+            .prepare_cached(self.sql)
+            .map_err(|e| self.read_error(e))?;
+
+        let rows = stmt
+            .query_map(params, T::from_row)
+            .map_err(|e| self.read_error(e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| self.read_error(e))?;
+
+        Ok(rows)
+    }
+
+    fn read_error(&self, e: rusqlite::Error) -> ErrorRepsonse {
+        create_error(
+            format!(
+                "Unable to read values from the '{}' database",
+                self.db_display_name
+            ),
+            describe_error(&e),
+            self.source,
+        )
+    }
+
+    fn write_error(&self, e: rusqlite::Error) -> ErrorRepsonse {
+        create_error(
+            format!(
+                "Could not write '{}' data to the database",
+                self.db_display_name
+            ),
+            describe_error(&e),
+            self.source,
+        )
+    }
+}
+
+/// Converts an error from a transaction, or any other statement that does
+/// not go through `Query`, the same way `Query` does
+///
+/// # Arguments:
+/// - `message`: `impl Into<String>` - The main error message to display
+/// - `source`: `&str` - The path of the caller, used as the error source
+pub(crate) fn db_error(
+    message: impl Into<String>,
+    source: &str,
+) -> impl Fn(rusqlite::Error) -> ErrorRepsonse + '_ {
+    let message = message.into();
+
+    move |e| create_error(message.clone(), describe_error(&e), source)
+}
+
+/// Describes a rusqlite error in terms the user can act on
+fn describe_error(e: &rusqlite::Error) -> String {
+    match e {
+        rusqlite::Error::SqliteFailure(err, _) => match err.code {
+            ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked => format!(
+                "The database is in use by another process. Try again in a moment ({})",
+                e
+            ),
+            ErrorCode::ReadOnly | ErrorCode::PermissionDenied => {
+                format!("The database file cannot be written to ({})", e)
+            }
+            ErrorCode::DiskFull => format!("There is no space left on the disk ({})", e),
+            ErrorCode::NotADatabase | ErrorCode::DatabaseCorrupt => {
+                format!("The database file is damaged ({})", e)
+            }
+            ErrorCode::ConstraintViolation => {
+                format!("The data conflicts with existing data ({})", e)
+            }
+            _ => e.to_string(),
+        },
+        rusqlite::Error::InvalidColumnName(column) => {
+            format!("The '{}' column is missing", column)
+        }
+        rusqlite::Error::InvalidColumnType(_, column, found) => format!(
+            "The '{}' column holds a value of the wrong type ({})",
+            column, found
+        ),
+        rusqlite::Error::IntegralValueOutOfRange(_, value) => {
+            format!("The value {} is out of range", value)
+        }
+        rusqlite::Error::FromSqlConversionFailure(_, _, err) => {
+            format!("A value is not valid: {}", err)
+        }
+        _ => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+
+    #[derive(Debug, PartialEq)]
+    struct Note {
+        id: i64,
+        text: String,
+        pinned: bool,
+    }
+    impl FromRow for Note {
+        fn from_row(row: &Row) -> rusqlite::Result<Self> {
+            Ok(Self {
+                id: row.get("id")?,
+                text: row.get("text")?,
+                pinned: row.get::<&str, i64>("pinned")? != 0,
+            })
+        }
+    }
+
+    fn database() -> Connection {
+        let db = Connection::open_in_memory().unwrap();
+        db.execute_batch(
+            "CREATE TABLE notes (
+                id INTEGER PRIMARY KEY,
+                text TEXT NOT NULL UNIQUE,
+                pinned INTEGER NOT NULL DEFAULT 0
+            );",
+        )
+        .unwrap();
+        db
+    }
+
+    #[test]
+    fn maps_rows_by_column_name() {
+        let db = database();
+        let insert = Query::new(
+            "Test",
+            "INSERT INTO notes (text, pinned) VALUES (?1, ?2);",
+            "test",
+        );
+        let first = insert.insert(&db, params!["first", 1]).unwrap();
+        insert.insert(&db, params!["second", 0]).unwrap();
+
+        // The columns are selected in another order than the fields
+        let select = "SELECT pinned, text, id FROM notes WHERE id >= ?1 ORDER BY id;";
+        let notes: Vec<Note> = Query::new("Test", select, "test")
+            .all(&db, params![first])
+            .unwrap();
+        let missing: Option<Note> = Query::new("Test", select, "test")
+            .one(&db, params![first + 10])
+            .unwrap();
+
+        assert_eq!(
+            notes,
+            [
+                Note {
+                    id: first,
+                    text: String::from("first"),
+                    pinned: true
+                },
+                Note {
+                    id: first + 1,
+                    text: String::from("second"),
+                    pinned: false
+                },
+            ]
+        );
+        assert_eq!(missing, None);
+
+        let changed = Query::new("Test", "UPDATE notes SET pinned = 1;", "test")
+            .execute(&db, [])
+            .unwrap();
+        assert_eq!(changed, 2);
+    }
+
+    #[test]
+    fn describes_read_errors() {
+        let db = database();
+        db.execute("INSERT INTO notes (text) VALUES ('a');", [])
+            .unwrap();
+
+        let missing_column = Query::new("Test", "SELECT id, text FROM notes;", "test")
+            .one::<Note>(&db, [])
+            .err()
+            .unwrap();
+        assert_eq!(
+            missing_column.message,
+            "Unable to read values from the 'Test' database"
+        );
+        assert_eq!(missing_column.cause, "The 'pinned' column is missing");
+
+        let wrong_type = Query::new(
+            "Test",
+            "SELECT id, text, 'yes' AS pinned FROM notes;",
+            "test",
+        )
+        .all::<Note>(&db, [])
+        .err()
+        .unwrap();
+        assert!(wrong_type
+            .cause
+            .contains("'pinned' column holds a value of the wrong type"));
+    }
+
+    #[test]
+    fn describes_write_errors() {
+        let db = database();
+        let insert = Query::new("Test", "INSERT INTO notes (text) VALUES (?1);", "test");
+        insert.execute(&db, params!["a"]).unwrap();
+
+        let error = insert.execute(&db, params!["a"]).err().unwrap();
+
+        assert_eq!(error.message, "Could not write 'Test' data to the database");
+        assert!(error
+            .cause
+            .starts_with("The data conflicts with existing data"));
+        assert_eq!(error.source, "rs_db::test");
+    }
+}
@@ -0,0 +1,5 @@
+mod migrate;
+mod validate;
+
+pub use migrate::{migrate, Migration};
+pub use validate::{validate_tables, ColumnSchema, TableSchema};
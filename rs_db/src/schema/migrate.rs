@@ -0,0 +1,221 @@
+use crate::error_factory::create_error;
+use crate::query::db_error;
+use rs_response::DataResponse;
+use rusqlite::{params, Connection};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ERR_SRC: &str = "schema::migrate::migrate()";
+
+/// A single, numbered change to a database's schema
+///
+/// **NOTE:** Versions start at `1` and must be listed in increasing order.
+/// Once released, a migration must never be edited. Add a new one instead
+///
+/// # Properties:
+/// - `version`: `i64` - The schema version after the migration has run
+/// - `description`: `&'static str` - A short summary of the change
+/// - `sql`: `&'static str` - The statements to run
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Brings a database up to the latest version of its schema
+///
+/// The applied versions are stored in a `schema_version` table. Every
+/// pending migration runs in its own transaction, together with the row
+/// that records it, so a failed migration leaves the database at the
+/// previous version
+///
+/// **NOTE:** A database with a newer version than the latest migration was
+/// created by a newer version of the app, and is refused
+///
+/// # Arguments:
+/// - `db`: `&mut Connection` - The database to upgrade
+/// - `db_display_name`: `&str` - The name of the database to show to the user
+/// - `migrations`: `&[Migration]` - Every migration of the database, in order
+///
+/// # Returns:
+/// - The number of migrations that were applied
+pub fn migrate(
+    db: &mut Connection,
+    db_display_name: &str,
+    migrations: &[Migration],
+) -> DataResponse<usize> {
+    let message = format!("Could not upgrade the '{}' database", db_display_name);
+    let db_error = db_error(message.clone(), ERR_SRC);
+
+    if let Some(pair) = migrations
+        .windows(2)
+        .find(|pair| pair[0].version >= pair[1].version)
+    {
+        return Err(create_error(
+            message,
+            format!(
+                "DEVELOPER ERROR: Migration {} is listed before migration {}",
+                pair[0].version, pair[1].version
+            ),
+            ERR_SRC,
+        ));
+    }
+
+    db.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY NOT NULL,
+            description TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        );",
+    )
+    .map_err(&db_error)?;
+
+    let current: i64 = db
+        .query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_version;",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(&db_error)?;
+
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+    if current > latest {
+        return Err(create_error(
+            message,
+            format!(
+                "The database is at version {}, but this version of the app only supports up to version {}",
+                current, latest
+            ),
+            ERR_SRC,
+        ));
+    }
+
+    let mut applied = 0;
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        let migration_error = |e: rusqlite::Error| {
+            create_error(
+                message.clone(),
+                format!(
+                    "Migration {} ('{}') failed: {}",
+                    migration.version, migration.description, e
+                ),
+                ERR_SRC,
+            )
+        };
+
+        let tx = db.transaction().map_err(&db_error)?;
+        tx.execute_batch(migration.sql).map_err(migration_error)?;
+        tx.execute(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3);",
+            params![migration.version, migration.description, now()],
+        )
+        .map_err(migration_error)?;
+        tx.commit().map_err(migration_error)?;
+
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            description: "Create the 'notes' table",
+            sql: "CREATE TABLE notes (id INTEGER PRIMARY KEY, text TEXT NOT NULL);",
+        },
+        Migration {
+            version: 2,
+            description: "Add the 'pinned' column to 'notes'",
+            sql: "ALTER TABLE notes ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;",
+        },
+    ];
+
+    fn version(db: &Connection) -> i64 {
+        db.query_row("SELECT MAX(version) FROM schema_version;", [], |row| {
+            row.get(0)
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn migrates_from_version_0() {
+        let mut db = Connection::open_in_memory().unwrap();
+
+        assert_eq!(migrate(&mut db, "Test", &MIGRATIONS[..1]).unwrap(), 1);
+        assert_eq!(version(&db), 1);
+        assert_eq!(migrate(&mut db, "Test", MIGRATIONS).unwrap(), 1);
+        assert_eq!(version(&db), 2);
+
+        db.execute("INSERT INTO notes (text, pinned) VALUES ('a', 1);", [])
+            .unwrap();
+    }
+
+    #[test]
+    fn does_nothing_at_the_current_version() {
+        let mut db = Connection::open_in_memory().unwrap();
+
+        assert_eq!(migrate(&mut db, "Test", MIGRATIONS).unwrap(), 2);
+        assert_eq!(migrate(&mut db, "Test", MIGRATIONS).unwrap(), 0);
+        assert_eq!(version(&db), 2);
+    }
+
+    #[test]
+    fn keeps_the_previous_version_when_a_migration_fails() {
+        let mut db = Connection::open_in_memory().unwrap();
+        let broken = [
+            Migration {
+                version: 1,
+                description: "Create the 'notes' table",
+                sql: MIGRATIONS[0].sql,
+            },
+            Migration {
+                version: 2,
+                description: "Broken",
+                sql: "CREATE TABLE other (id INTEGER); NOT SQL;",
+            },
+        ];
+
+        let error = migrate(&mut db, "Test", &broken).err().unwrap();
+
+        assert!(error.cause.starts_with("Migration 2 ('Broken') failed"));
+        assert_eq!(version(&db), 1);
+        assert!(db.prepare("SELECT * FROM other;").is_err());
+    }
+
+    #[test]
+    fn refuses_newer_and_unordered_versions() {
+        let mut db = Connection::open_in_memory().unwrap();
+        migrate(&mut db, "Test", MIGRATIONS).unwrap();
+
+        let newer = migrate(&mut db, "Test", &MIGRATIONS[..1]).err().unwrap();
+        assert!(newer.cause.contains("at version 2"));
+
+        let unordered = [
+            Migration {
+                version: 2,
+                ..MIGRATIONS[0]
+            },
+            Migration {
+                version: 1,
+                ..MIGRATIONS[1]
+            },
+        ];
+        let mut db = Connection::open_in_memory().unwrap();
+        assert!(migrate(&mut db, "Test", &unordered)
+            .err()
+            .unwrap()
+            .cause
+            .starts_with("DEVELOPER ERROR"));
+    }
+}
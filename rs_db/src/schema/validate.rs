@@ -0,0 +1,204 @@
+use crate::error_factory::create_error;
+use crate::query::{FromRow, Query};
+use rs_response::DataResponse;
+use rusqlite::{params, Connection};
+
+const ERR_SRC: &str = "schema::validate::validate_tables()";
+
+/// The expected definition of a single column
+///
+/// # Properties:
+/// - `name`: `&'static str` - The column name
+/// - `sql_type`: `&'static str` - The declared type, such as `"INTEGER"` or `"TEXT"`
+/// - `not_null`: `bool` - Whether the column is declared `NOT NULL`
+/// - `primary_key`: `bool` - Whether the column is part of the primary key
+pub struct ColumnSchema {
+    pub name: &'static str,
+    pub sql_type: &'static str,
+    pub not_null: bool,
+    pub primary_key: bool,
+}
+
+/// The expected definition of a table
+///
+/// # Properties:
+/// - `name`: `&'static str` - The table name
+/// - `columns`: `&'static [ColumnSchema]` - Every column the app relies on
+pub struct TableSchema {
+    pub name: &'static str,
+    pub columns: &'static [ColumnSchema],
+}
+
+/// Checks the actual columns of every table against the expected schema
+///
+/// **NOTE:** Extra columns are allowed. Missing columns and columns with a
+/// different type, `NOT NULL` or primary key definition are reported in
+/// the error's `cause`
+///
+/// # Arguments:
+/// - `db`: `&Connection` - The database to check
+/// - `db_display_name`: `&str` - The name of the database to show to the user
+/// - `tables`: `&[TableSchema]` - The expected tables
+pub fn validate_tables(
+    db: &Connection,
+    db_display_name: &str,
+    tables: &[TableSchema],
+) -> DataResponse<()> {
+    let mut problems = Vec::new();
+
+    for table in tables {
+        let actual = table_columns(db, db_display_name, table.name)?;
+
+        if actual.is_empty() {
+            problems.push(format!("The '{}' table is missing", table.name));
+            continue;
+        }
+
+        for expected in table.columns {
+            let found = match actual.iter().find(|column| column.name == expected.name) {
+                Some(found) => found,
+                None => {
+                    problems.push(format!(
+                        "The '{}.{}' column is missing",
+                        table.name, expected.name
+                    ));
+                    continue;
+                }
+            };
+
+            if !found.sql_type.eq_ignore_ascii_case(expected.sql_type) {
+                problems.push(format!(
+                    "The '{}.{}' column should be of type {}, but is of type {}",
+                    table.name, expected.name, expected.sql_type, found.sql_type
+                ));
+            }
+
+            if found.not_null != expected.not_null {
+                problems.push(format!(
+                    "The '{}.{}' column should {}be NOT NULL",
+                    table.name,
+                    expected.name,
+                    if expected.not_null { "" } else { "not " }
+                ));
+            }
+
+            if found.primary_key != expected.primary_key {
+                problems.push(format!(
+                    "The '{}.{}' column should {}be part of the primary key",
+                    table.name,
+                    expected.name,
+                    if expected.primary_key { "" } else { "not " }
+                ));
+            }
+        }
+    }
+
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(create_error(
+            format!(
+                "The '{}' database does not have the expected structure",
+                db_display_name
+            ),
+            problems.join("\n"),
+            ERR_SRC,
+        )),
+    }
+}
+
+struct ActualColumn {
+    name: String,
+    sql_type: String,
+    not_null: bool,
+    primary_key: bool,
+}
+impl FromRow for ActualColumn {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            name: row.get("name")?,
+            sql_type: row.get("type")?,
+            not_null: row.get::<&str, i64>("notnull")? != 0,
+            primary_key: row.get::<&str, i64>("pk")? != 0,
+        })
+    }
+}
+
+fn table_columns(
+    db: &Connection,
+    db_display_name: &str,
+    table: &str,
+) -> DataResponse<Vec<ActualColumn>> {
+    Query::new(
+        db_display_name,
+        "SELECT name, type, \"notnull\", pk FROM pragma_table_info(?1);",
+        ERR_SRC,
+    )
+    .all(db, params![table])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLES: &[TableSchema] = &[TableSchema {
+        name: "notes",
+        columns: &[
+            ColumnSchema {
+                name: "id",
+                sql_type: "INTEGER",
+                not_null: false,
+                primary_key: true,
+            },
+            ColumnSchema {
+                name: "text",
+                sql_type: "TEXT",
+                not_null: true,
+                primary_key: false,
+            },
+        ],
+    }];
+
+    fn problems(sql: &str) -> Vec<String> {
+        let db = Connection::open_in_memory().unwrap();
+        db.execute_batch(sql).unwrap();
+
+        match validate_tables(&db, "Test", TABLES) {
+            Ok(()) => Vec::new(),
+            Err(e) => e.cause.lines().map(String::from).collect(),
+        }
+    }
+
+    #[test]
+    fn accepts_the_expected_tables() {
+        assert!(
+            problems("CREATE TABLE notes (id INTEGER PRIMARY KEY, text text NOT NULL);").is_empty()
+        );
+        // Extra columns are allowed
+        assert!(problems(
+            "CREATE TABLE notes (id INTEGER PRIMARY KEY, text TEXT NOT NULL, extra BLOB);"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn reports_missing_tables_and_columns() {
+        assert_eq!(problems(""), ["The 'notes' table is missing"]);
+        assert_eq!(
+            problems("CREATE TABLE notes (id INTEGER PRIMARY KEY);"),
+            ["The 'notes.text' column is missing"]
+        );
+    }
+
+    #[test]
+    fn reports_mistyped_columns() {
+        assert_eq!(
+            problems("CREATE TABLE notes (id TEXT, text INTEGER);"),
+            [
+                "The 'notes.id' column should be of type INTEGER, but is of type TEXT",
+                "The 'notes.id' column should be part of the primary key",
+                "The 'notes.text' column should be of type TEXT, but is of type INTEGER",
+                "The 'notes.text' column should be NOT NULL",
+            ]
+        );
+    }
+}
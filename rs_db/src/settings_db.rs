@@ -1,5 +1,7 @@
-mod settings;
+pub(crate) mod settings;
 pub use settings::Settings;
 
 mod init;
 pub use init::initialize_settings_db;
+
+mod schema;
@@ -1,8 +0,0 @@
-use rs_response::DataResponse;
-use rusqlite::Connection;
-
-pub fn validate_table_columns(db: &Connection) -> DataResponse<bool> {
-    // let stmt = db.prepare("sql")
-
-    Ok(false)
-}
@@ -0,0 +1,64 @@
+use crate::schema::{ColumnSchema, Migration, TableSchema};
+
+/// Every migration of the 'Settings' database, in order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create the 'settings' table",
+        sql: "CREATE TABLE IF NOT EXISTS settings (
+            id TEXT PRIMARY KEY NOT NULL,
+            theme INTEGER NOT NULL,
+            welcome_screen INTEGER NOT NULL,
+            db_notifs INTEGER NOT NULL,
+            confirm_rename INTEGER NOT NULL
+        );",
+    },
+    Migration {
+        version: 2,
+        description: "Seed the default settings",
+        sql: "INSERT OR IGNORE INTO settings (
+            id,
+            theme,
+            welcome_screen,
+            db_notifs,
+            confirm_rename
+        ) VALUES ('main', 0, 1, 0, 1);",
+    },
+];
+
+/// The tables of the 'Settings' database after every migration
+pub const TABLES: &[TableSchema] = &[TableSchema {
+    name: "settings",
+    columns: &[
+        ColumnSchema {
+            name: "id",
+            sql_type: "TEXT",
+            not_null: true,
+            primary_key: true,
+        },
+        ColumnSchema {
+            name: "theme",
+            sql_type: "INTEGER",
+            not_null: true,
+            primary_key: false,
+        },
+        ColumnSchema {
+            name: "welcome_screen",
+            sql_type: "INTEGER",
+            not_null: true,
+            primary_key: false,
+        },
+        ColumnSchema {
+            name: "db_notifs",
+            sql_type: "INTEGER",
+            not_null: true,
+            primary_key: false,
+        },
+        ColumnSchema {
+            name: "confirm_rename",
+            sql_type: "INTEGER",
+            not_null: true,
+            primary_key: false,
+        },
+    ],
+}];
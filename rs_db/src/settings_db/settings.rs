@@ -1,13 +1,16 @@
-use crate::database;
-use crate::error_factory::create_error;
-use rs_response::DataResponse;
+use crate::error_factory::{create_error, create_warning};
+use crate::query::{FromRow, Query};
+use crate::DbManager;
+use rs_response::{DataResponse, OkDataResponse, ResponseWithData};
+use rusqlite::params;
+use rusqlite::types::Value;
 use serde::ser::{Serialize, SerializeStruct};
 
 const ERR_SRC: &str = "settings_db::settings::Settings";
 const ERR_SRC2: &str = "settings_db::settings::SettingsTable";
 pub const DB_NAME: &str = "settings";
 pub const DB_DISPLAY_NAME: &str = "Settings";
-pub const DB_ID: &str = "'main'";
+pub const DB_ID: &str = "main";
 
 /// Data structure for interacting with the 'Settings' database and the frontend
 ///
@@ -25,17 +28,18 @@ pub const DB_ID: &str = "'main'";
 /// - `parse_settings` - Creates a `Settings` item from parsed data from the frontend
 /// - `write` - Writes the `Settings` data to the database
 /// - `read` - Reads `Settings` data from the database if available. Otherwise, writes default
-/// values to the database and returns it
+///   values to the database and returns it. Invalid values are reset to their defaults
 ///
 /// # Example:
 /// ```
 /// use rs_db::settings_db::Settings;
+/// use rs_db::DbManager;
 /// use rs_response::DataResponse;
 ///
-/// fn set_default_settings() -> DataResponse<()> {
+/// fn set_default_settings(db_manager: &DbManager) -> DataResponse<()> {
 ///   let default_settings = Settings::default_settings();
 ///
-///   default_settings.write()?;
+///   default_settings.write(db_manager)?;
 ///
 ///   Ok(())
 /// }
@@ -106,12 +110,13 @@ impl Settings {
     /// # Example:
     /// ```
     /// use rs_db::settings_db::Settings;
+    /// use rs_db::DbManager;
     /// use rs_response::DataResponse;
     ///
-    /// fn reset_settings() -> DataResponse<Settings> {
+    /// fn reset_settings(db_manager: &DbManager) -> DataResponse<Settings> {
     ///   let default_settings = Settings::default_settings();
     ///
-    ///   default_settings.write()?;
+    ///   default_settings.write(db_manager)?;
     ///
     ///   Ok(default_settings)
     /// }
@@ -137,11 +142,12 @@ impl Settings {
     ///
     /// # Example:
     /// ```
-    /// use rs_db::Settings;
+    /// use rs_db::settings_db::Settings;
+    /// use rs_db::DbManager;
     /// use rs_response::{OkResponse, Response};
     ///
-    /// #[tauri::command]
     /// fn update_settings(
+    ///   db_manager: &DbManager,
     ///   theme: String,
     ///   welcome_screen: bool,
     ///   db_notifs: bool,
@@ -154,7 +160,7 @@ impl Settings {
     ///     confirm_rename,
     ///   )?;
     ///
-    ///   settings.write()?;
+    ///   settings.write(db_manager)?;
     ///
     ///   Ok(
     ///     OkResponse::new_info(
@@ -193,40 +199,35 @@ impl Settings {
     ///
     /// **NOTE:** Data is validated prior to being written
     /// to the database
-    pub fn write(&self) -> DataResponse<()> {
-        let db = database::connect(DB_DISPLAY_NAME, DB_NAME)?;
+    ///
+    /// # Arguments:
+    /// - `db_manager`: `&DbManager` - The shared database connections
+    pub fn write(&self, db_manager: &DbManager) -> DataResponse<()> {
+        let db = db_manager.settings()?;
 
         let settings_table = SettingsTable::from_settings(self)?;
 
-        let sqlite_stmt = format!(
-            "INSERT or REPLACE INTO {} (
+        Query::new(
+            DB_DISPLAY_NAME,
+            "INSERT OR REPLACE INTO settings (
                 id,
                 theme,
                 welcome_screen,
                 db_notifs,
                 confirm_rename
-            ) VALUES (
-                {},
-                {},
-                {},
-                {},
-                {}
-            );",
-            DB_NAME,
-            DB_ID,
-            settings_table.theme,
-            settings_table.welcome_screen,
-            settings_table.db_notifs,
-            settings_table.confirm_rename
-        );
-
-        db.execute(&sqlite_stmt, []).map_err(|e| {
-            create_error(
-                format!("Could not write '{}' data to the database", DB_DISPLAY_NAME),
-                e.to_string(),
-                ERR_SRC,
-            )
-        })?;
+            ) VALUES (?1, ?2, ?3, ?4, ?5);",
+            ERR_SRC,
+        )
+        .execute(
+            &db,
+            params![
+                DB_ID,
+                settings_table.theme,
+                settings_table.welcome_screen,
+                settings_table.db_notifs,
+                settings_table.confirm_rename
+            ],
+        )?;
 
         Ok(())
     }
@@ -237,61 +238,67 @@ impl Settings {
     /// values will be written to the database and retrieved
     ///
     /// **NOTE:** Data from the database is validated to ensure
-    /// proper formatting
+    /// proper formatting. Invalid values are reset to their defaults,
+    /// written back to the database and reported with a warning
+    ///
+    /// # Arguments:
+    /// - `db_manager`: `&DbManager` - The shared database connections
     ///
     /// # Example:
     /// ```
-    /// use rs_db::Settings;
-    /// use rs_response::DataResponse;
-    ///
-    /// fn get_settings() -> DataResponse<Settings> {
-    ///   let settings = Settings::read()?;
+    /// use rs_db::settings_db::Settings;
+    /// use rs_db::DbManager;
+    /// use rs_response::ResponseWithData;
     ///
-    ///   Ok(settings)
+    /// fn get_settings(db_manager: &DbManager) -> ResponseWithData<Settings> {
+    ///   Settings::read(db_manager)
     /// }
     /// ```
-    pub fn read() -> DataResponse<Self> {
-        let db = database::connect(DB_DISPLAY_NAME, DB_NAME)?;
+    pub fn read(db_manager: &DbManager) -> ResponseWithData<Self> {
+        let db = db_manager.settings()?;
 
-        let sqlite_stmt = format!("SELECT * FROM {} WHERE id = {};", DB_DISPLAY_NAME, DB_ID);
+        let row = Query::new(
+            DB_DISPLAY_NAME,
+            "SELECT * FROM settings WHERE id = ?1;",
+            ERR_SRC,
+        )
+        .one::<SettingsTable>(&db, params![DB_ID])?;
 
-        let row = db.query_row(&sqlite_stmt, [], |row| {
-            let _id = row.get::<usize, String>(0)?;
-            let theme = row.get::<usize, u8>(1)?;
-            let welcome_screen = row.get::<usize, u8>(2)?;
-            let db_notifs = row.get::<usize, u8>(3)?;
-            let confirm_rename = row.get::<usize, u8>(4)?;
+        let settings_table = match row {
+            Some(settings_table) => settings_table,
+            None => {
+                let defaults = Self::default_settings();
+                defaults.write(db_manager)?;
 
-            Ok(SettingsTable::new(
-                theme,
-                welcome_screen,
-                db_notifs,
-                confirm_rename,
-            ))
-        });
-
-        let settings = match row {
-            Ok(settings_table) => settings_table.to_settings()?,
-            Err(e) => match e {
-                rusqlite::Error::QueryReturnedNoRows => {
-                    let defaults = Self::default_settings();
-                    defaults.write()?;
-                    defaults
-                }
-                _ => {
-                    return Err(create_error(
-                        format!(
-                            "Unable to read values from the '{}' database",
-                            DB_DISPLAY_NAME
-                        ),
-                        e.to_string(),
-                        ERR_SRC,
-                    ))
-                }
-            },
+                return Ok(OkDataResponse::new_info(
+                    "Database",
+                    format!("The default '{}' have been restored", DB_DISPLAY_NAME),
+                    defaults,
+                ));
+            }
         };
 
-        Ok(settings)
+        let (settings, repairs) = settings_table.to_settings();
+
+        if repairs.is_empty() {
+            return Ok(OkDataResponse::new_info(
+                "Database",
+                format!("The '{}' have been read", DB_DISPLAY_NAME),
+                settings,
+            ));
+        }
+
+        settings.write(db_manager)?;
+
+        Ok(create_warning(
+            format!(
+                "Invalid '{}' data was reset to the default values",
+                DB_DISPLAY_NAME
+            ),
+            repairs.join("; "),
+            ERR_SRC,
+        )
+        .add_data(settings))
     }
 }
 
@@ -300,18 +307,36 @@ impl Settings {
 /// # Methods:
 /// - `new` - Creates a new `SettingsTable` item
 /// - `from_settings` - Converts `Settings` data to `SettingsTable` data
-/// - `to_settings` - Converts `SettingsTable` data to `Settings` data
+/// - `to_settings` - Converts `SettingsTable` data to `Settings` data, resetting invalid values
+///
+/// **NOTE:** Values are kept as read from the database, so values that are
+/// not integers can be reset like any other invalid value
 struct SettingsTable {
-    theme: u8,
-    welcome_screen: u8,
-    db_notifs: u8,
-    confirm_rename: u8,
+    theme: Value,
+    welcome_screen: Value,
+    db_notifs: Value,
+    confirm_rename: Value,
+}
+impl FromRow for SettingsTable {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        Ok(Self::new(
+            row.get("theme")?,
+            row.get("welcome_screen")?,
+            row.get("db_notifs")?,
+            row.get("confirm_rename")?,
+        ))
+    }
 }
 impl SettingsTable {
     /// Creates a new `SettingsTable` item
     ///
     /// **NOTE:** The `SettingsTable` data is not validated
-    pub fn new(theme: u8, welcome_screen: u8, db_notifs: u8, confirm_rename: u8) -> Self {
+    pub fn new(
+        theme: Value,
+        welcome_screen: Value,
+        db_notifs: Value,
+        confirm_rename: Value,
+    ) -> Self {
         Self {
             theme,
             welcome_screen,
@@ -326,8 +351,8 @@ impl SettingsTable {
     pub fn from_settings(settings: &Settings) -> DataResponse<Self> {
         Ok(Self {
             theme: match settings.theme.as_str() {
-                "DARK" => 0,
-                "LIGHT" => 1,
+                "DARK" => Value::Integer(0),
+                "LIGHT" => Value::Integer(1),
                 _ => {
                     return Err(create_error(
                         format!(
@@ -335,57 +360,134 @@ impl SettingsTable {
                             DB_DISPLAY_NAME
                         ),
                         format!("'{}' is not a valid 'theme' value", settings.theme),
-                        ERR_SRC,
+                        ERR_SRC2,
                     ))
                 }
             },
-            welcome_screen: match settings.welcome_screen {
-                true => 1,
-                false => 0,
-            },
-            db_notifs: match settings.db_notifs {
-                true => 1,
-                false => 0,
-            },
-            confirm_rename: match settings.confirm_rename {
-                true => 1,
-                false => 0,
-            },
+            welcome_screen: Value::Integer(settings.welcome_screen as i64),
+            db_notifs: Value::Integer(settings.db_notifs as i64),
+            confirm_rename: Value::Integer(settings.confirm_rename as i64),
         })
     }
 
     /// Converts `SettingsTable` data to `Settings` data
     ///
-    /// **NOTE:** Data is validated to ensure proper formatting
-    pub fn to_settings(&self) -> DataResponse<Settings> {
-        fn validate_0_or_1<T>(
-            key: &str,
-            value: u8,
-            if_value_is_0: T,
-            if_value_is_1: T,
-        ) -> DataResponse<T> {
-            match value {
-                0 => Ok(if_value_is_0),
-                1 => Ok(if_value_is_1),
-                _ => Err(create_error(
-                    format!(
-                        "Unable to parse the '{}' data from the database due to invalid data",
-                        DB_DISPLAY_NAME
-                    ),
-                    format!(
-                        "The '{}' value should be either 0 or 1, but found {}",
-                        key, value
-                    ),
-                    ERR_SRC2,
-                )),
+    /// **NOTE:** Data is validated to ensure proper formatting. Invalid
+    /// values are replaced with their default value, and the reason for
+    /// every replaced value is returned alongside the `Settings`
+    pub fn to_settings(&self) -> (Settings, Vec<String>) {
+        let defaults = Settings::default_settings();
+        let mut repairs: Vec<String> = Vec::new();
+
+        let mut validate_0_or_1 = |key: &str, value: &Value, default: bool| match value {
+            Value::Integer(0) => false,
+            Value::Integer(1) => true,
+            _ => {
+                repairs.push(format!(
+                    "The '{}' value should be either 0 or 1, but found {}",
+                    key,
+                    describe_value(value)
+                ));
+                default
             }
-        }
+        };
+
+        let theme = match validate_0_or_1("theme", &self.theme, defaults.theme == "LIGHT") {
+            true => "LIGHT",
+            false => "DARK",
+        };
+        let welcome_screen = validate_0_or_1(
+            "welcome_screen",
+            &self.welcome_screen,
+            defaults.welcome_screen,
+        );
+        let db_notifs = validate_0_or_1("db_notifs", &self.db_notifs, defaults.db_notifs);
+        let confirm_rename = validate_0_or_1(
+            "confirm_rename",
+            &self.confirm_rename,
+            defaults.confirm_rename,
+        );
+
+        (
+            Settings::new(theme, welcome_screen, db_notifs, confirm_rename),
+            repairs,
+        )
+    }
+}
+
+/// Describes a value read from the database for a repair message
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::from("NULL"),
+        Value::Integer(value) => value.to_string(),
+        Value::Real(value) => value.to_string(),
+        Value::Text(value) => format!("'{}'", value),
+        Value::Blob(value) => format!("{} bytes of binary data", value.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings_db::initialize_settings_db;
+
+    fn settings_db() -> DbManager {
+        let db_manager = DbManager::in_memory().unwrap();
+        initialize_settings_db(&db_manager).unwrap();
+        db_manager
+    }
 
-        Settings::parse_settings(
-            validate_0_or_1("theme", self.theme, "DARK", "LIGHT")?,
-            validate_0_or_1("welcome_screen", self.welcome_screen, false, true)?,
-            validate_0_or_1("db_notifs", self.db_notifs, false, true)?,
-            validate_0_or_1("confirm_rename", self.confirm_rename, false, true)?,
+    fn store(db_manager: &DbManager, column: &str, value: Value) {
+        let db = db_manager.settings().unwrap();
+        db.execute(
+            &format!("UPDATE settings SET {} = ?1 WHERE id = ?2;", column),
+            params![value, DB_ID],
         )
+        .unwrap();
+    }
+
+    #[test]
+    fn reads_the_seeded_defaults() {
+        let db_manager = settings_db();
+
+        let response = Settings::read(&db_manager).unwrap();
+
+        assert!(matches!(response, OkDataResponse::INFOData(_)));
+    }
+
+    #[test]
+    fn resets_out_of_range_values() {
+        let db_manager = settings_db();
+        store(&db_manager, "theme", Value::Integer(7));
+
+        let response = Settings::read(&db_manager).unwrap();
+
+        match response {
+            OkDataResponse::WARNData(warning) => {
+                assert_eq!(warning.data.theme, "DARK");
+                assert!(warning.cause.contains("found 7"));
+            }
+            _ => panic!("an out of range value was not reported"),
+        }
+    }
+
+    #[test]
+    fn resets_values_that_are_not_integers() {
+        let db_manager = settings_db();
+        store(&db_manager, "db_notifs", Value::Text(String::from("yes")));
+
+        let response = Settings::read(&db_manager).unwrap();
+
+        match response {
+            OkDataResponse::WARNData(warning) => {
+                assert!(!warning.data.db_notifs);
+                assert!(warning.cause.contains("found 'yes'"));
+            }
+            _ => panic!("a text value was not reported"),
+        }
+
+        // The repaired value was written back
+        let response = Settings::read(&db_manager).unwrap();
+        assert!(matches!(response, OkDataResponse::INFOData(_)));
     }
 }
@@ -1,14 +1,29 @@
 use super::print_response;
 
 pub fn dev_rs_db(run: bool) {
-    if run == false {
+    if !run {
         return;
     }
 
     eprintln!("=== TESTING: 'rs_db' ===\n");
 
-    let data = rs_db::settings_db::initialize_settings_db();
+    // In-memory databases, so the real user data is never touched
+    let db_manager = match rs_db::DbManager::in_memory() {
+        Ok(db_manager) => db_manager,
+        Err(err) => {
+            eprintln!("> ERR:\n{:#?}\n", err);
+            return;
+        }
+    };
+
+    let data = rs_db::settings_db::initialize_settings_db(&db_manager);
     print_response("Settings Initialization", data);
 
+    let data = rs_db::history_db::initialize_history_db(&db_manager);
+    print_response("History Initialization", data);
+
+    let data = rs_db::settings_db::Settings::read(&db_manager).map(|data| data.drop_data());
+    print_response("Settings Read", data);
+
     eprintln!("==========");
 }
@@ -0,0 +1,27 @@
+use rs_response::{ErrorRepsonse, OkResponse};
+
+pub fn create_error(
+    message: impl Into<String>,
+    details: impl Into<String>,
+    source: &str,
+) -> ErrorRepsonse {
+    ErrorRepsonse::new_error(
+        "File System",
+        message,
+        details,
+        String::from("rs_fs::") + source,
+    )
+}
+
+pub fn create_warning(
+    message: impl Into<String>,
+    details: impl Into<String>,
+    source: &str,
+) -> OkResponse {
+    OkResponse::new_warning(
+        "File System",
+        message,
+        details,
+        String::from("rs_fs::") + source,
+    )
+}
@@ -0,0 +1,432 @@
+mod hasher;
+
+use crate::error_factory::{create_error, create_warning};
+use hasher::Hasher;
+use rs_response::{DataResponse, ErrorRepsonse, OkDataResponse};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::SystemTime;
+
+const ERR_SRC: &str = "hash::HashCache";
+
+/// How much of a file is read at once while hashing
+const BUFFER_SIZE: usize = 256 * 1024;
+
+/// The most files hashed at the same time by `HashCache::hash_batch`, as
+/// hashing is mostly limited by the disk
+const MAX_WORKERS: usize = 8;
+
+/// The most files a `HashCache::new` keeps the digests of
+const MAX_CACHED_FILES: usize = 100_000;
+
+/// A hash algorithm, to compute the digest of a file's content
+///
+/// - `Crc32`: CRC-32, 8 hexadecimal digits
+/// - `Md5`: MD5, 32 hexadecimal digits
+/// - `Sha1`: SHA-1, 40 hexadecimal digits
+/// - `Sha256`: SHA-256, 64 hexadecimal digits
+/// - `Blake3`: BLAKE3, 64 hexadecimal digits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum HashAlgorithm {
+    Crc32,
+    Md5,
+    Sha1,
+    Sha256,
+    Blake3,
+}
+
+/// The progress of `HashCache::hash_batch`, reported after every file
+///
+/// # Properties:
+/// - `done`: `usize` - The number of files finished so far, including cached and failed ones
+/// - `total`: `usize` - The number of files in the batch
+/// - `path`: `PathBuf` - The file that was just finished
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct HashProgress {
+    pub done: usize,
+    pub total: usize,
+    pub path: PathBuf,
+}
+
+/// The digests of a file, along with the size and modification time they
+/// were computed for
+struct CachedFile {
+    size: u64,
+    modified: Option<SystemTime>,
+    digests: HashMap<HashAlgorithm, String>,
+    /// When the file was last hashed or read from the cache, to evict the
+    /// least recently used files first
+    used: u64,
+}
+
+/// The cached files, with a clock counting every use
+#[derive(Default)]
+struct CachedFiles {
+    files: HashMap<PathBuf, CachedFile>,
+    clock: u64,
+}
+
+/// Computes the digests of files by streaming their content, and keeps them
+/// so unchanged files are never hashed twice
+///
+/// **NOTE:** A file is hashed again when its size or modification time
+/// changes. Every algorithm a file is hashed with at once is computed in a
+/// single read of the file
+///
+/// **NOTE:** At most 100000 files are kept by default. Once full, the least
+/// recently used half is forgotten
+///
+/// # Methods:
+/// - `new` - Creates an empty `HashCache`
+/// - `with_max_files` - Creates an empty `HashCache` keeping at most the given number of files
+/// - `hash` - Gets the digest of a single file
+/// - `hash_batch` - Hashes many files on a pool of worker threads
+/// - `len` - The number of files cached
+/// - `clear` - Forgets every cached digest
+///
+/// # Example:
+/// ```
+/// use rs_fs::{HashAlgorithm, HashCache};
+/// use std::path::PathBuf;
+///
+/// fn warm_up(cache: &HashCache, files: &[PathBuf]) -> usize {
+///   let warnings = cache.hash_batch(files, &[HashAlgorithm::Sha256], |progress| {
+///     println!("Hashed {} of {} files", progress.done, progress.total);
+///   });
+///
+///   files.len() - warnings.len()
+/// }
+/// ```
+pub struct HashCache {
+    files: Mutex<CachedFiles>,
+    max_files: usize,
+}
+impl Default for HashCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl HashCache {
+    /// Creates an empty `HashCache`, keeping at most 100000 files
+    pub fn new() -> Self {
+        Self::with_max_files(MAX_CACHED_FILES)
+    }
+
+    /// Creates an empty `HashCache`, keeping at most `max_files` files
+    ///
+    /// # Arguments:
+    /// - `max_files`: `usize` - The most files to keep the digests of, at least `1`
+    pub fn with_max_files(max_files: usize) -> Self {
+        Self {
+            files: Mutex::new(CachedFiles::default()),
+            max_files: max_files.max(1),
+        }
+    }
+
+    /// The number of files cached
+    pub fn len(&self) -> usize {
+        self.files.lock().map_or(0, |cached| cached.files.len())
+    }
+
+    /// Whether no file is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets every cached digest
+    pub fn clear(&self) {
+        if let Ok(mut cached) = self.files.lock() {
+            cached.files.clear();
+        }
+    }
+
+    /// Gets the digest of a single file, as lowercase hexadecimal
+    ///
+    /// **NOTE:** The file is only read when it is not cached yet, or changed
+    /// since it was
+    ///
+    /// # Arguments:
+    /// - `path`: `&Path` - The file to hash
+    /// - `algorithm`: `HashAlgorithm` - The hash algorithm
+    pub fn hash(&self, path: &Path, algorithm: HashAlgorithm) -> DataResponse<String> {
+        let mut digests = self
+            .digests(path, &[algorithm])
+            .map_err(|e| hash_error(path, e))?;
+
+        Ok(digests.remove(0))
+    }
+
+    /// Hashes many files on a pool of worker threads, so their digests are
+    /// cached for `hash`
+    ///
+    /// **NOTE:** Files that cannot be read do not stop the batch. They are
+    /// reported as *Warning* responses with their path, in batch order
+    ///
+    /// # Arguments:
+    /// - `paths`: `&[PathBuf]` - The files to hash
+    /// - `algorithms`: `&[HashAlgorithm]` - The hash algorithms, computed in a single read of each file
+    /// - `on_progress`: `impl Fn(HashProgress) + Sync` - Called from the worker threads after every file
+    pub fn hash_batch(
+        &self,
+        paths: &[PathBuf],
+        algorithms: &[HashAlgorithm],
+        on_progress: impl Fn(HashProgress) + Sync,
+    ) -> Vec<OkDataResponse<PathBuf>> {
+        let total = paths.len();
+        let next = AtomicUsize::new(0);
+        let done = AtomicUsize::new(0);
+        let failures = Mutex::new(Vec::new());
+
+        let workers = thread::available_parallelism()
+            .map_or(1, usize::from)
+            .min(MAX_WORKERS)
+            .min(total);
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let path = match paths.get(index) {
+                        Some(path) => path,
+                        None => break,
+                    };
+
+                    if let Err(e) = self.digests(path, algorithms) {
+                        if let Ok(mut failures) = failures.lock() {
+                            failures.push((index, e));
+                        }
+                    }
+
+                    on_progress(HashProgress {
+                        done: done.fetch_add(1, Ordering::Relaxed) + 1,
+                        total,
+                        path: path.clone(),
+                    });
+                });
+            }
+        });
+
+        let mut failures = failures.into_inner().unwrap_or_default();
+        failures.sort_by_key(|(index, _)| *index);
+
+        failures
+            .into_iter()
+            .map(|(index, e)| {
+                create_warning(
+                    format!("Unable to hash '{}'", paths[index].display()),
+                    e.to_string(),
+                    ERR_SRC,
+                )
+                .add_data(paths[index].clone())
+            })
+            .collect()
+    }
+
+    /// Gets the digests of a file in the order of `algorithms`, hashing it
+    /// for the ones that are not cached
+    fn digests(&self, path: &Path, algorithms: &[HashAlgorithm]) -> io::Result<Vec<String>> {
+        let metadata = fs::metadata(path)?;
+        let (size, modified) = (metadata.len(), metadata.modified().ok());
+
+        let mut missing = algorithms.to_vec();
+        if let Ok(cached) = self.files.lock() {
+            if let Some(cached) = cached
+                .files
+                .get(path)
+                .filter(|cached| cached.size == size && cached.modified == modified)
+            {
+                missing.retain(|algorithm| !cached.digests.contains_key(algorithm));
+            }
+        }
+
+        let computed = match missing.is_empty() {
+            true => HashMap::new(),
+            false => hash_file(path, &missing)?,
+        };
+
+        let mut cached = self
+            .files
+            .lock()
+            .map_err(|_| io::Error::other("The hash cache is unusable"))?;
+
+        if !cached.files.contains_key(path) && cached.files.len() >= self.max_files {
+            cached.evict(self.max_files / 2);
+        }
+        cached.clock += 1;
+        let used = cached.clock;
+
+        let cached = cached
+            .files
+            .entry(path.to_path_buf())
+            .or_insert(CachedFile {
+                size,
+                modified,
+                digests: HashMap::new(),
+                used,
+            });
+        if cached.size != size || cached.modified != modified {
+            *cached = CachedFile {
+                size,
+                modified,
+                digests: HashMap::new(),
+                used,
+            };
+        }
+        cached.digests.extend(computed);
+        cached.used = used;
+
+        algorithms
+            .iter()
+            .map(|algorithm| {
+                cached
+                    .digests
+                    .get(algorithm)
+                    .cloned()
+                    .ok_or_else(|| io::Error::other("The file changed while it was hashed"))
+            })
+            .collect()
+    }
+}
+
+impl CachedFiles {
+    /// Forgets the least recently used files, keeping `keep` of them
+    fn evict(&mut self, keep: usize) {
+        let mut used: Vec<u64> = self.files.values().map(|file| file.used).collect();
+        used.sort_unstable();
+
+        let oldest_kept = match used.len().checked_sub(keep) {
+            Some(evicted) => used.get(evicted).copied().unwrap_or(u64::MAX),
+            None => return,
+        };
+        self.files.retain(|_, file| file.used >= oldest_kept);
+    }
+}
+
+/// Streams a file through every hash algorithm at once
+fn hash_file(
+    path: &Path,
+    algorithms: &[HashAlgorithm],
+) -> io::Result<HashMap<HashAlgorithm, String>> {
+    let mut file = File::open(path)?;
+    let mut hashers: Vec<(HashAlgorithm, Hasher)> = algorithms
+        .iter()
+        .map(|algorithm| (*algorithm, Hasher::new(*algorithm)))
+        .collect();
+
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    loop {
+        let read = match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        for (_, hasher) in hashers.iter_mut() {
+            hasher.update(&buffer[..read]);
+        }
+    }
+
+    Ok(hashers
+        .into_iter()
+        .map(|(algorithm, hasher)| (algorithm, hasher.finish()))
+        .collect())
+}
+
+fn hash_error(path: &Path, e: io::Error) -> ErrorRepsonse {
+    create_error(
+        format!("Unable to hash '{}'", path.display()),
+        e.to_string(),
+        ERR_SRC,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("rs_fs-hash-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    #[test]
+    fn hashes_again_when_files_change() {
+        let folder = temp_folder("change");
+        let path = folder.join("a.txt");
+        fs::write(&path, "abc").unwrap();
+        let cache = HashCache::new();
+
+        assert_eq!(cache.hash(&path, HashAlgorithm::Crc32).unwrap(), "352441c2");
+        assert_eq!(cache.len(), 1);
+
+        fs::write(&path, "abcd").unwrap();
+        assert_eq!(cache.hash(&path, HashAlgorithm::Crc32).unwrap(), "ed82cd11");
+
+        cache.clear();
+        assert!(cache.is_empty());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn reports_files_that_cannot_be_hashed() {
+        let folder = temp_folder("batch");
+        let paths = vec![
+            folder.join("a.txt"),
+            folder.join("missing.txt"),
+            folder.join("b.txt"),
+        ];
+        fs::write(&paths[0], "abc").unwrap();
+        fs::write(&paths[2], "").unwrap();
+        let cache = HashCache::new();
+        let calls = AtomicUsize::new(0);
+
+        let warnings = cache.hash_batch(&paths, &[HashAlgorithm::Md5], |progress| {
+            assert_eq!(progress.total, 3);
+            calls.fetch_add(1, Ordering::Relaxed);
+        });
+
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            OkDataResponse::WARNData(warning) => assert_eq!(warning.data, paths[1]),
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(cache.hash(&paths[1], HashAlgorithm::Md5).is_err());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn forgets_the_least_recently_used_files() {
+        let folder = temp_folder("evict");
+        let paths: Vec<PathBuf> = (0..5).map(|i| folder.join(format!("{}.txt", i))).collect();
+        for path in paths.iter() {
+            fs::write(path, "abc").unwrap();
+        }
+        let cache = HashCache::with_max_files(4);
+
+        for path in paths[..4].iter() {
+            cache.hash(path, HashAlgorithm::Crc32).unwrap();
+        }
+        cache.hash(&paths[0], HashAlgorithm::Crc32).unwrap();
+        cache.hash(&paths[4], HashAlgorithm::Crc32).unwrap();
+
+        let cached = cache.files.lock().unwrap();
+        assert_eq!(cached.files.len(), 3);
+        assert!(cached.files.contains_key(&paths[0]));
+        assert!(cached.files.contains_key(&paths[3]));
+        assert!(!cached.files.contains_key(&paths[1]));
+        drop(cached);
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
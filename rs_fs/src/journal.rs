@@ -0,0 +1,338 @@
+use crate::error_factory::create_error;
+use rs_response::{DataResponse, ErrorRepsonse};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const ERR_SRC: &str = "journal::Journal";
+
+/// A move that was completed by a `Journal`
+///
+/// # Properties:
+/// - `from`: `PathBuf` - The path the file was moved from
+/// - `to`: `PathBuf` - The path the file was moved to
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct JournalEntry {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Moves files on disk while recording every completed move, so they can
+/// be undone in reverse order
+///
+/// # Methods:
+/// - `new` - Creates an empty `Journal`
+/// - `rename` - Moves a file without overwriting anything and records the move
+/// - `entries` - The completed moves, oldest first
+/// - `rollback` - Undoes the most recent moves, newest first
+///
+/// # Example:
+/// ```
+/// use rs_fs::Journal;
+/// use rs_response::DataResponse;
+///
+/// fn swap_extensions() -> DataResponse<()> {
+///   let mut journal = Journal::new();
+///
+///   if let Err(e) = journal.rename("report.txt", "report.md") {
+///     journal.rollback(0);
+///     return Err(e);
+///   }
+///
+///   Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+}
+impl Journal {
+    /// Creates an empty `Journal`
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Moves a file without overwriting anything and records the move
+    ///
+    /// **NOTE:** Nothing is recorded if the move fails
+    ///
+    /// # Arguments:
+    /// - `from`: `impl AsRef<Path>` - The current path of the file
+    /// - `to`: `impl AsRef<Path>` - The new path of the file. It must not exist yet
+    pub fn rename(&mut self, from: impl AsRef<Path>, to: impl AsRef<Path>) -> DataResponse<()> {
+        let (from, to) = (from.as_ref(), to.as_ref());
+
+        rename_file(from, to)?;
+        self.entries.push(JournalEntry {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+
+        Ok(())
+    }
+
+    /// The completed moves, oldest first
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Undoes the moves recorded after the first `keep` moves, newest first
+    ///
+    /// **NOTE:** A move that cannot be undone is left in place and its
+    /// error is returned. The rollback carries on with the older moves
+    ///
+    /// # Arguments:
+    /// - `keep`: `usize` - The number of oldest moves to keep. Use `0` to undo everything
+    ///
+    /// # Returns:
+    /// - The moves that were undone, newest first
+    /// - The errors of the moves that could not be undone
+    pub fn rollback(&mut self, keep: usize) -> (Vec<JournalEntry>, Vec<ErrorRepsonse>) {
+        let mut undone = Vec::new();
+        let mut failures = Vec::new();
+
+        while self.entries.len() > keep {
+            let entry = match self.entries.pop() {
+                Some(entry) => entry,
+                None => break,
+            };
+
+            match rename_file(&entry.to, &entry.from) {
+                Ok(()) => undone.push(entry),
+                Err(e) => failures.push(e),
+            }
+        }
+
+        (undone, failures)
+    }
+}
+
+/// Moves a file, refusing to overwrite an existing path
+///
+/// **NOTE:** The check and the move are a single step, so a file created
+/// at `to` in the meantime is never replaced. File systems that cannot
+/// do this fall back to creating a hard link at `to` and removing `from`
+///
+/// # Arguments:
+/// - `from`: `&Path` - The current path of the file
+/// - `to`: `&Path` - The new path of the file. It must not exist yet
+pub fn rename_file(from: &Path, to: &Path) -> DataResponse<()> {
+    let message = format!(
+        "Unable to rename '{}' to '{}'",
+        from.display(),
+        to.display()
+    );
+
+    rename_no_replace(from, to).map_err(|e| match e.kind() {
+        io::ErrorKind::AlreadyExists => create_error(
+            message,
+            format!("'{}' already exists", to.display()),
+            ERR_SRC,
+        ),
+        _ => create_error(message, e.to_string(), ERR_SRC),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn rename_no_replace(from: &Path, to: &Path) -> io::Result<()> {
+    let (from_c, to_c) = (c_path(from)?, c_path(to)?);
+
+    // SAFETY: Both paths are valid, null-terminated strings
+    let result = unsafe {
+        libc::renameat2(
+            libc::AT_FDCWD,
+            from_c.as_ptr(),
+            libc::AT_FDCWD,
+            to_c.as_ptr(),
+            libc::RENAME_NOREPLACE,
+        )
+    };
+
+    match result {
+        0 => Ok(()),
+        _ => match io::Error::last_os_error() {
+            // The kernel or the file system does not support the flag
+            e if matches!(e.raw_os_error(), Some(libc::EINVAL | libc::ENOSYS)) => {
+                link_and_unlink(from, to)
+            }
+            e => Err(e),
+        },
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn rename_no_replace(from: &Path, to: &Path) -> io::Result<()> {
+    let (from_c, to_c) = (c_path(from)?, c_path(to)?);
+
+    // SAFETY: Both paths are valid, null-terminated strings
+    let result = unsafe { libc::renamex_np(from_c.as_ptr(), to_c.as_ptr(), libc::RENAME_EXCL) };
+
+    match result {
+        0 => Ok(()),
+        _ => match io::Error::last_os_error() {
+            // The file system does not support the flag
+            e if e.raw_os_error() == Some(libc::ENOTSUP) => link_and_unlink(from, to),
+            e => Err(e),
+        },
+    }
+}
+
+#[cfg(windows)]
+fn rename_no_replace(from: &Path, to: &Path) -> io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn MoveFileExW(existing: *const u16, new: *const u16, flags: u32) -> i32;
+    }
+
+    let wide =
+        |path: &Path| -> Vec<u16> { path.as_os_str().encode_wide().chain(Some(0)).collect() };
+    let (from_w, to_w) = (wide(from), wide(to));
+
+    // Without `MOVEFILE_REPLACE_EXISTING`, the move fails if `to` exists
+    // SAFETY: Both paths are valid, null-terminated wide strings
+    match unsafe { MoveFileExW(from_w.as_ptr(), to_w.as_ptr(), 0) } {
+        0 => Err(io::Error::last_os_error()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "ios", windows)))]
+fn rename_no_replace(from: &Path, to: &Path) -> io::Result<()> {
+    link_and_unlink(from, to)
+}
+
+/// Moves a file by creating a hard link, which fails if `to` exists, and
+/// removing the original path
+///
+/// **NOTE:** Folders cannot be moved this way
+#[cfg_attr(windows, allow(dead_code))]
+fn link_and_unlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::hard_link(from, to)?;
+
+    if let Err(e) = fs::remove_file(from) {
+        // Leave the file at its original path only
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn c_path(path: &Path) -> io::Result<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+
+    std::ffi::CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_folder(name: &str, files: &[&str]) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("rs_fs-journal-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        for file in files {
+            fs::write(folder.join(file), file).unwrap();
+        }
+        folder
+    }
+
+    #[test]
+    fn never_replaces_an_existing_file() {
+        let folder = temp_folder("replace", &["a.txt", "b.txt"]);
+        let (a, b) = (folder.join("a.txt"), folder.join("b.txt"));
+
+        let error = rename_file(&a, &b).err().unwrap();
+        let contents = (fs::read_to_string(&a), fs::read_to_string(&b));
+        let linked = link_and_unlink(&a, &b).is_err();
+        let moved = rename_file(&a, &folder.join("c.txt"));
+        let missing = rename_file(&a, &folder.join("d.txt"));
+        let listing = fs::read_dir(&folder).unwrap().count();
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(error.cause, format!("'{}' already exists", b.display()));
+        assert_eq!(contents.0.unwrap(), "a.txt");
+        assert_eq!(contents.1.unwrap(), "b.txt");
+        assert!(linked);
+        assert!(moved.is_ok());
+        assert!(missing.is_err());
+        assert_eq!(listing, 2);
+    }
+
+    #[test]
+    fn moves_with_links() {
+        let folder = temp_folder("link", &["a.txt"]);
+        let moved = link_and_unlink(&folder.join("a.txt"), &folder.join("b.txt"));
+        let contents = fs::read_to_string(folder.join("b.txt"));
+        let original = folder.join("a.txt").exists();
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert!(moved.is_ok());
+        assert_eq!(contents.unwrap(), "a.txt");
+        assert!(!original);
+    }
+
+    #[test]
+    fn rolls_back_newest_first() {
+        let folder = temp_folder("rollback", &["a", "b", "c"]);
+        let path = |name: &str| folder.join(name);
+
+        let mut journal = Journal::new();
+        journal.rename(path("a"), path("a2")).unwrap();
+        journal.rename(path("b"), path("b2")).unwrap();
+        journal.rename(path("c"), path("c2")).unwrap();
+        assert!(journal.rename(path("missing"), path("d")).is_err());
+        assert_eq!(journal.entries().len(), 3);
+
+        let (undone, failures) = journal.rollback(1);
+        let kept = journal.entries().to_vec();
+        let (a2, b, c) = (path("a2").exists(), path("b").exists(), path("c").exists());
+        fs::remove_dir_all(&folder).unwrap();
+
+        let moves: Vec<(PathBuf, PathBuf)> = undone
+            .into_iter()
+            .map(|entry| (entry.from, entry.to))
+            .collect();
+        assert_eq!(moves, [(path("c"), path("c2")), (path("b"), path("b2"))]);
+        assert!(failures.is_empty());
+        assert_eq!(
+            kept,
+            [JournalEntry {
+                from: path("a"),
+                to: path("a2")
+            }]
+        );
+        assert!(a2 && b && c);
+    }
+
+    #[test]
+    fn keeps_moves_that_cannot_be_undone() {
+        let folder = temp_folder("blocked", &["a", "b"]);
+        let path = |name: &str| folder.join(name);
+
+        let mut journal = Journal::new();
+        journal.rename(path("a"), path("a2")).unwrap();
+        journal.rename(path("b"), path("b2")).unwrap();
+        // Another program takes the original name of 'b'
+        fs::write(path("b"), "new").unwrap();
+
+        let (undone, failures) = journal.rollback(0);
+        let contents = fs::read_to_string(path("b"));
+        let (a, b2) = (path("a").exists(), path("b2").exists());
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(undone.len(), 1);
+        assert_eq!(undone[0].from, path("a"));
+        assert_eq!(failures.len(), 1);
+        assert_eq!(contents.unwrap(), "new");
+        assert!(a && b2);
+    }
+}
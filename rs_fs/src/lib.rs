@@ -1 +1,19 @@
+mod error_factory;
 
+mod hash;
+pub use hash::{HashAlgorithm, HashCache, HashProgress};
+
+mod journal;
+pub use journal::{rename_file, Journal, JournalEntry};
+
+mod metadata;
+pub use metadata::{
+    probe_image, read_audio_tags, read_document_info, read_exif, read_media_info, AudioTags,
+    DocumentInfo, ExifData, ImageInfo, MediaInfo,
+};
+
+mod scanner;
+pub use scanner::{scan, FileEntry, FileKind, ScanOptions, ScanResults};
+
+mod sniff;
+pub use sniff::{is_known_extension, sniff, FileCategory, FileType};
@@ -0,0 +1,33 @@
+mod archive;
+mod audio;
+mod document;
+mod exif;
+mod image;
+mod isobmff;
+mod media;
+#[cfg(test)]
+pub(crate) use archive::temp_zip;
+pub(crate) use archive::ZipPackage;
+pub use audio::{read_audio_tags, AudioTags};
+pub use document::{read_document_info, DocumentInfo};
+pub use exif::{read_exif, ExifData};
+pub use image::{probe_image, ImageInfo};
+pub use media::{read_media_info, MediaInfo};
+
+use std::io::{self, Read};
+
+/// Fills `buf` as far as the reader allows, returning the number of bytes read
+///
+/// **NOTE:** Unlike `read_exact`, reaching the end of a short file is not an error
+pub(crate) fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+
+    while read < buf.len() {
+        match reader.read(&mut buf[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+
+    Ok(read)
+}
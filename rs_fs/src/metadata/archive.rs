@@ -0,0 +1,112 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+/// The largest entry read from an archive, to bound damaged or hostile files
+const MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// A zip based file, such as an EPUB book or an office document, opened to
+/// read a few of its entries
+///
+/// **NOTE:** Only the central directory is read when opening. Entries are
+/// decompressed on demand
+///
+/// # Methods:
+/// - `open` - Opens a zip based file
+/// - `read` - Reads an entry as bytes
+/// - `read_text` - Reads an entry as UTF-8 text
+/// - `mimetype` - Reads the media type stored in the `mimetype` entry
+/// - `has` - Whether an entry exists
+pub struct ZipPackage {
+    archive: ZipArchive<BufReader<File>>,
+}
+impl ZipPackage {
+    /// Opens a zip based file
+    ///
+    /// **NOTE:** Returns `None` when the file is not a zip archive
+    ///
+    /// # Arguments:
+    /// - `path`: `&Path` - The file to open
+    pub fn open(path: &Path) -> io::Result<Option<Self>> {
+        let file = File::open(path)?;
+
+        match ZipArchive::new(BufReader::new(file)) {
+            Ok(archive) => Ok(Some(Self { archive })),
+            Err(ZipError::Io(e)) => Err(e),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Reads an entry as bytes
+    ///
+    /// **NOTE:** Returns `None` when the entry does not exist, is encrypted or
+    /// is larger than 16 MB
+    ///
+    /// # Arguments:
+    /// - `name`: `&str` - The full path of the entry, such as `docProps/core.xml`
+    pub fn read(&mut self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        let mut entry = match self.archive.by_name(name) {
+            Ok(entry) => entry,
+            Err(ZipError::Io(e)) => return Err(e),
+            Err(_) => return Ok(None),
+        };
+
+        if entry.size() > MAX_ENTRY_SIZE {
+            return Ok(None);
+        }
+
+        let mut data = Vec::with_capacity(entry.size() as usize);
+        entry.by_ref().take(MAX_ENTRY_SIZE).read_to_end(&mut data)?;
+
+        Ok(Some(data))
+    }
+
+    /// Reads an entry as UTF-8 text, without its byte order mark
+    ///
+    /// # Arguments:
+    /// - `name`: `&str` - The full path of the entry, such as `docProps/core.xml`
+    pub fn read_text(&mut self, name: &str) -> io::Result<Option<String>> {
+        Ok(self.read(name)?.map(|data| {
+            let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&data);
+            String::from_utf8_lossy(data).to_string()
+        }))
+    }
+
+    /// Reads the media type stored in the `mimetype` entry, as EPUB books and
+    /// OpenDocument files do, e.g. `application/epub+zip`
+    ///
+    /// **NOTE:** Returns `None` when there is no `mimetype` entry
+    pub fn mimetype(&mut self) -> io::Result<Option<String>> {
+        Ok(self
+            .read_text("mimetype")?
+            .map(|mimetype| mimetype.trim().to_string()))
+    }
+
+    /// Whether an entry exists
+    ///
+    /// # Arguments:
+    /// - `name`: `&str` - The full path of the entry, such as `word/document.xml`
+    pub fn has(&self, name: &str) -> bool {
+        self.archive.file_names().any(|entry| entry == name)
+    }
+}
+
+/// Writes a zip archive of text entries to the temporary folder, for tests
+#[cfg(test)]
+pub(crate) fn temp_zip(name: &str, entries: &[(&str, &str)]) -> std::path::PathBuf {
+    use std::io::Write;
+    use zip::write::{FileOptions, ZipWriter};
+
+    let path = std::env::temp_dir().join(format!("rs_fs-zip-{}-{}.zip", name, std::process::id()));
+    let mut zip = ZipWriter::new(File::create(&path).unwrap());
+
+    for (name, content) in entries {
+        zip.start_file(*name, FileOptions::default()).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+
+    path
+}
@@ -0,0 +1,165 @@
+mod id3;
+mod mp4;
+mod vorbis;
+
+use crate::error_factory::create_error;
+use crate::metadata::{isobmff, read_up_to};
+use rs_response::{DataResponse, ErrorRepsonse};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const ERR_SRC: &str = "metadata::audio::read_audio_tags()";
+
+/// The tags of an audio file
+///
+/// **NOTE:** Every property is `None` when the file does not record it
+///
+/// # Properties:
+/// - `title`: `Option<String>` - The title of the track
+/// - `artist`: `Option<String>` - The performing artist
+/// - `album`: `Option<String>` - The album title
+/// - `track`: `Option<u32>` - The track number, without the track count
+/// - `disc`: `Option<u32>` - The disc number, without the disc count
+/// - `year`: `Option<i32>` - The release year
+/// - `genre`: `Option<String>` - The genre, with numeric ID3 genres resolved to their name
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct AudioTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub track: Option<u32>,
+    pub disc: Option<u32>,
+    pub year: Option<i32>,
+    pub genre: Option<String>,
+}
+
+/// Reads the tags of an audio file
+///
+/// Supports ID3v1 and ID3v2 (MP3, and any file with an ID3 tag), Vorbis
+/// comments (FLAC, and Ogg Vorbis, Opus or FLAC) and MP4 `ilst` atoms (M4A,
+/// M4B, MP4)
+///
+/// **NOTE:** Returns `None` for files without tags, including any file that
+/// is not a supported audio file. When an MP3 has both an ID3v2 and an ID3v1
+/// tag, the missing ID3v2 values are taken from the ID3v1 tag
+///
+/// # Arguments:
+/// - `path`: `&Path` - The file to read
+///
+/// # Example:
+/// ```
+/// use rs_fs::read_audio_tags;
+/// use rs_response::DataResponse;
+/// use std::path::Path;
+///
+/// fn artist(path: &Path) -> DataResponse<Option<String>> {
+///   let tags = read_audio_tags(path)?;
+///
+///   Ok(tags.and_then(|tags| tags.artist))
+/// }
+/// ```
+pub fn read_audio_tags(path: &Path) -> DataResponse<Option<AudioTags>> {
+    let file = File::open(path).map_err(|e| read_error(path, e))?;
+    let mut reader = BufReader::new(file);
+
+    read_tags(&mut reader).map_err(|e| read_error(path, e))
+}
+
+fn read_tags<R: Read + Seek>(reader: &mut R) -> io::Result<Option<AudioTags>> {
+    let mut header = [0u8; 12];
+    let header_len = read_up_to(reader, &mut header)?;
+    let header = &header[..header_len];
+    reader.seek(SeekFrom::Start(0))?;
+
+    let tags = if header.starts_with(b"ID3") {
+        // FLAC files are sometimes written with an ID3v2 tag in front
+        let (tags, end) = id3::read_v2(reader)?;
+        let mut magic = [0u8; 4];
+        reader.seek(SeekFrom::Start(end))?;
+
+        match read_up_to(reader, &mut magic)? == 4 && &magic == b"fLaC" {
+            true => merge(vorbis::read_flac(reader)?, tags),
+            false => tags,
+        }
+    } else if header.starts_with(b"fLaC") {
+        reader.seek(SeekFrom::Start(4))?;
+        vorbis::read_flac(reader)?
+    } else if header.starts_with(b"OggS") {
+        vorbis::read_ogg(reader)?
+    } else if isobmff::is_isobmff(header) {
+        mp4::read_ilst(reader)?
+    } else {
+        None
+    };
+
+    // ID3v1 is a trailer, found at the end of MP3 files with or without ID3v2
+    Ok(merge(tags, id3::read_v1(reader)?))
+}
+
+impl AudioTags {
+    /// Fills every missing property from `other`
+    fn or(self, other: Self) -> Self {
+        Self {
+            title: self.title.or(other.title),
+            artist: self.artist.or(other.artist),
+            album: self.album.or(other.album),
+            track: self.track.or(other.track),
+            disc: self.disc.or(other.disc),
+            year: self.year.or(other.year),
+            genre: self.genre.or(other.genre),
+        }
+    }
+
+    /// `None` when no tag was found
+    fn found(self) -> Option<Self> {
+        match self == Self::default() {
+            true => None,
+            false => Some(self),
+        }
+    }
+}
+
+fn merge(tags: Option<AudioTags>, fallback: Option<AudioTags>) -> Option<AudioTags> {
+    match (tags, fallback) {
+        (Some(tags), Some(fallback)) => Some(tags.or(fallback)),
+        (tags, fallback) => tags.or(fallback),
+    }
+}
+
+/// Trims a text value, `None` when it ends up empty
+fn text(value: &str) -> Option<String> {
+    let value = value.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+
+    match value.is_empty() {
+        true => None,
+        false => Some(value.to_string()),
+    }
+}
+
+/// Reads the number of a `3` or `3/12` value
+fn number(value: &str) -> Option<u32> {
+    value
+        .split('/')
+        .next()
+        .and_then(|number| number.trim().parse().ok())
+        .filter(|number| *number > 0)
+}
+
+/// Reads the year of a `2019` or `2019-05-01T...` value
+fn year(value: &str) -> Option<i32> {
+    value
+        .trim()
+        .get(..4)
+        .filter(|year| year.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|year| year.parse().ok())
+        .filter(|year| *year > 0)
+}
+
+fn read_error(path: &Path, e: io::Error) -> ErrorRepsonse {
+    create_error(
+        format!("Unable to read the audio tags of '{}'", path.display()),
+        e.to_string(),
+        ERR_SRC,
+    )
+}
@@ -0,0 +1,509 @@
+use super::{number, text, year, AudioTags};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// The largest ID3v2 tag read, cover art included
+const MAX_TAG_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The genres of ID3v1, with the Winamp extensions, by index
+const GENRES: [&str; 148] = [
+    "Blues",
+    "Classic Rock",
+    "Country",
+    "Dance",
+    "Disco",
+    "Funk",
+    "Grunge",
+    "Hip-Hop",
+    "Jazz",
+    "Metal",
+    "New Age",
+    "Oldies",
+    "Other",
+    "Pop",
+    "R&B",
+    "Rap",
+    "Reggae",
+    "Rock",
+    "Techno",
+    "Industrial",
+    "Alternative",
+    "Ska",
+    "Death Metal",
+    "Pranks",
+    "Soundtrack",
+    "Euro-Techno",
+    "Ambient",
+    "Trip-Hop",
+    "Vocal",
+    "Jazz+Funk",
+    "Fusion",
+    "Trance",
+    "Classical",
+    "Instrumental",
+    "Acid",
+    "House",
+    "Game",
+    "Sound Clip",
+    "Gospel",
+    "Noise",
+    "Alternative Rock",
+    "Bass",
+    "Soul",
+    "Punk",
+    "Space",
+    "Meditative",
+    "Instrumental Pop",
+    "Instrumental Rock",
+    "Ethnic",
+    "Gothic",
+    "Darkwave",
+    "Techno-Industrial",
+    "Electronic",
+    "Pop-Folk",
+    "Eurodance",
+    "Dream",
+    "Southern Rock",
+    "Comedy",
+    "Cult",
+    "Gangsta",
+    "Top 40",
+    "Christian Rap",
+    "Pop/Funk",
+    "Jungle",
+    "Native American",
+    "Cabaret",
+    "New Wave",
+    "Psychedelic",
+    "Rave",
+    "Showtunes",
+    "Trailer",
+    "Lo-Fi",
+    "Tribal",
+    "Acid Punk",
+    "Acid Jazz",
+    "Polka",
+    "Retro",
+    "Musical",
+    "Rock & Roll",
+    "Hard Rock",
+    "Folk",
+    "Folk-Rock",
+    "National Folk",
+    "Swing",
+    "Fast Fusion",
+    "Bebop",
+    "Latin",
+    "Revival",
+    "Celtic",
+    "Bluegrass",
+    "Avantgarde",
+    "Gothic Rock",
+    "Progressive Rock",
+    "Psychedelic Rock",
+    "Symphonic Rock",
+    "Slow Rock",
+    "Big Band",
+    "Chorus",
+    "Easy Listening",
+    "Acoustic",
+    "Humour",
+    "Speech",
+    "Chanson",
+    "Opera",
+    "Chamber Music",
+    "Sonata",
+    "Symphony",
+    "Booty Bass",
+    "Primus",
+    "Porn Groove",
+    "Satire",
+    "Slow Jam",
+    "Club",
+    "Tango",
+    "Samba",
+    "Folklore",
+    "Ballad",
+    "Power Ballad",
+    "Rhythmic Soul",
+    "Freestyle",
+    "Duet",
+    "Punk Rock",
+    "Drum Solo",
+    "A Cappella",
+    "Euro-House",
+    "Dance Hall",
+    "Goa",
+    "Drum & Bass",
+    "Club-House",
+    "Hardcore",
+    "Terror",
+    "Indie",
+    "BritPop",
+    "Afro-Punk",
+    "Polsk Punk",
+    "Beat",
+    "Christian Gangsta Rap",
+    "Heavy Metal",
+    "Black Metal",
+    "Crossover",
+    "Contemporary Christian",
+    "Christian Rock",
+    "Merengue",
+    "Salsa",
+    "Thrash Metal",
+    "Anime",
+    "JPop",
+    "Synthpop",
+];
+
+/// The name of an ID3v1 genre index
+pub fn genre_name(index: usize) -> Option<String> {
+    GENRES.get(index).map(|genre| genre.to_string())
+}
+
+/// Reads the ID3v2 tag at the start of a file
+///
+/// **NOTE:** Also returns the offset right after the tag, where the audio
+/// data starts. Tags that are too large or of an unknown version are skipped
+pub fn read_v2<R: Read + Seek>(reader: &mut R) -> io::Result<(Option<AudioTags>, u64)> {
+    let mut header = [0u8; 10];
+    reader.seek(SeekFrom::Start(0))?;
+    reader.read_exact(&mut header)?;
+
+    let version = header[3];
+    let flags = header[5];
+    let size = syncsafe(&header[6..10]) as u64;
+    let footer = match version == 4 && flags & 0x10 != 0 {
+        true => 10,
+        false => 0,
+    };
+    let end = 10 + size + footer;
+
+    if !(2..=4).contains(&version) || size > MAX_TAG_SIZE {
+        return Ok((None, end));
+    }
+
+    let mut data = Vec::with_capacity(size as usize);
+    reader.take(size).read_to_end(&mut data)?;
+
+    // Before ID3v2.4, unsynchronisation applies to the whole tag
+    if version < 4 && flags & 0x80 != 0 {
+        data = resync(&data);
+    }
+
+    let mut pos = 0;
+    if version > 2 && flags & 0x40 != 0 && data.len() >= 4 {
+        pos = match version {
+            3 => 4 + u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize,
+            _ => syncsafe(&data[..4]) as usize,
+        };
+    }
+
+    let mut tags = AudioTags::default();
+    let mut years = (None, None);
+
+    while let Some((id, frame_flags, body, next)) = next_frame(&data, pos, version) {
+        pos = next;
+
+        let body = match frame_body(body, frame_flags, version, flags & 0x80 != 0) {
+            Some(body) => body,
+            None => continue,
+        };
+
+        let value = || decode_text(&body);
+
+        match &id {
+            b"TIT2" | b"TT2\0" => tags.title = value(),
+            b"TPE1" | b"TP1\0" => tags.artist = value(),
+            b"TALB" | b"TAL\0" => tags.album = value(),
+            b"TRCK" | b"TRK\0" => tags.track = value().as_deref().and_then(number),
+            b"TPOS" | b"TPA\0" => tags.disc = value().as_deref().and_then(number),
+            b"TDRC" => years.0 = value().as_deref().and_then(year),
+            b"TYER" | b"TYE\0" => years.1 = value().as_deref().and_then(year),
+            b"TCON" | b"TCO\0" => tags.genre = value().as_deref().and_then(content_type),
+            _ => {}
+        }
+    }
+
+    tags.year = years.0.or(years.1);
+
+    Ok((tags.found(), end))
+}
+
+/// Reads the ID3v1 tag at the end of a file
+pub fn read_v1<R: Read + Seek>(reader: &mut R) -> io::Result<Option<AudioTags>> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    if len < 128 {
+        return Ok(None);
+    }
+
+    let mut tag = [0u8; 128];
+    reader.seek(SeekFrom::End(-128))?;
+    reader.read_exact(&mut tag)?;
+
+    if !tag.starts_with(b"TAG") {
+        return Ok(None);
+    }
+
+    let field = |range: std::ops::Range<usize>| {
+        let value = &tag[range];
+        let value = value.split(|b| *b == 0).next().unwrap_or(value);
+        text(&latin1(value))
+    };
+
+    // ID3v1.1 stores the track number in the last byte of the comment
+    let track = match tag[125] == 0 && tag[126] != 0 {
+        true => Some(tag[126] as u32),
+        false => None,
+    };
+
+    let tags = AudioTags {
+        title: field(3..33),
+        artist: field(33..63),
+        album: field(63..93),
+        track,
+        disc: None,
+        year: field(93..97).as_deref().and_then(year),
+        genre: genre_name(tag[127] as usize),
+    };
+
+    Ok(tags.found())
+}
+
+/// Splits the next frame of a tag, returning its id, flags, body and the
+/// position of the frame after it
+fn next_frame(data: &[u8], pos: usize, version: u8) -> Option<([u8; 4], u16, &[u8], usize)> {
+    let header_len = match version {
+        2 => 6,
+        _ => 10,
+    };
+    let header = data.get(pos..pos + header_len)?;
+
+    // Padding, or the end of the frames
+    if header[0] == 0 {
+        return None;
+    }
+
+    let (id, size, flags) = match version {
+        2 => (
+            [header[0], header[1], header[2], 0],
+            u32::from_be_bytes([0, header[3], header[4], header[5]]),
+            0,
+        ),
+        3 => (
+            [header[0], header[1], header[2], header[3]],
+            u32::from_be_bytes([header[4], header[5], header[6], header[7]]),
+            u16::from_be_bytes([header[8], header[9]]),
+        ),
+        _ => (
+            [header[0], header[1], header[2], header[3]],
+            syncsafe(&header[4..8]),
+            u16::from_be_bytes([header[8], header[9]]),
+        ),
+    };
+
+    let start = pos + header_len;
+    let end = start.checked_add(size as usize)?;
+    let body = data.get(start..end)?;
+
+    Some((id, flags, body, end))
+}
+
+/// The text of a frame body, once its flags are handled
+///
+/// **NOTE:** Returns `None` for compressed and encrypted frames
+fn frame_body(body: &[u8], flags: u16, version: u8, unsynchronised: bool) -> Option<Vec<u8>> {
+    let mut body = body;
+
+    match version {
+        3 => {
+            if flags & 0x00c0 != 0 {
+                return None;
+            }
+            if flags & 0x0020 != 0 {
+                body = body.get(1..)?;
+            }
+            Some(body.to_vec())
+        }
+        4 => {
+            if flags & 0x000c != 0 {
+                return None;
+            }
+            if flags & 0x0040 != 0 {
+                body = body.get(1..)?;
+            }
+            if flags & 0x0001 != 0 {
+                body = body.get(4..)?;
+            }
+            match unsynchronised || flags & 0x0002 != 0 {
+                true => Some(resync(body)),
+                false => Some(body.to_vec()),
+            }
+        }
+        _ => Some(body.to_vec()),
+    }
+}
+
+/// Decodes the first value of a text frame
+fn decode_text(body: &[u8]) -> Option<String> {
+    let (encoding, value) = body.split_first()?;
+
+    let value = match encoding {
+        0 => latin1(until_nul(value, 1)),
+        1 => utf16(until_nul(value, 2), None),
+        2 => utf16(until_nul(value, 2), Some(false)),
+        3 => String::from_utf8_lossy(until_nul(value, 1)).to_string(),
+        _ => return None,
+    };
+
+    text(&value)
+}
+
+/// Resolves `17`, `(17)`, `(17)Rock` and `(RX)` genres to their name
+fn content_type(value: &str) -> Option<String> {
+    let value = value.trim();
+
+    if let Some(rest) = value.strip_prefix('(') {
+        if let Some((reference, name)) = rest.split_once(')') {
+            if !name.trim().is_empty() {
+                return text(name);
+            }
+
+            return match reference {
+                "RX" => Some(String::from("Remix")),
+                "CR" => Some(String::from("Cover")),
+                reference => reference.parse().ok().and_then(genre_name),
+            };
+        }
+    }
+
+    match value.parse() {
+        Ok(index) => genre_name(index),
+        Err(_) => text(value),
+    }
+}
+
+/// The value up to its first `NUL` terminator, of one or two bytes
+fn until_nul(value: &[u8], width: usize) -> &[u8] {
+    let end = value
+        .chunks(width)
+        .position(|unit| unit.iter().all(|b| *b == 0))
+        .map(|units| units * width)
+        .unwrap_or(value.len());
+
+    &value[..end]
+}
+
+fn latin1(value: &[u8]) -> String {
+    value.iter().map(|b| *b as char).collect()
+}
+
+/// Decodes UTF-16, using the byte order mark unless `big_endian` is given
+fn utf16(value: &[u8], big_endian: Option<bool>) -> String {
+    let (big_endian, value) = match (big_endian, value) {
+        (Some(big_endian), value) => (big_endian, value),
+        (None, [0xfe, 0xff, rest @ ..]) => (true, rest),
+        (None, [0xff, 0xfe, rest @ ..]) => (false, rest),
+        (None, value) => (false, value),
+    };
+
+    let units = value.chunks_exact(2).map(|unit| match big_endian {
+        true => u16::from_be_bytes([unit[0], unit[1]]),
+        false => u16::from_le_bytes([unit[0], unit[1]]),
+    });
+
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+/// Removes the `0x00` inserted after every `0xFF` by unsynchronisation
+fn resync(data: &[u8]) -> Vec<u8> {
+    let mut resynced = Vec::with_capacity(data.len());
+
+    for (i, b) in data.iter().enumerate() {
+        if *b == 0 && i > 0 && data[i - 1] == 0xff {
+            continue;
+        }
+        resynced.push(*b);
+    }
+
+    resynced
+}
+
+/// Reads a 28 bit integer stored in the low 7 bits of 4 bytes
+fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |size, b| (size << 7) | (*b as u32 & 0x7f))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn frame(id: &[u8; 4], value: &str) -> Vec<u8> {
+        let mut data = id.to_vec();
+        data.extend(((value.len() + 1) as u32).to_be_bytes());
+        data.extend([0, 0, 0]);
+        data.extend(value.as_bytes());
+        data
+    }
+
+    fn tag(frames: &[u8], size: u32) -> Vec<u8> {
+        let mut data = b"ID3\x03\x00\x00".to_vec();
+        data.extend([size >> 21, size >> 14, size >> 7, size].map(|b| (b & 0x7f) as u8));
+        data.extend(frames);
+        data
+    }
+
+    #[test]
+    fn reads_v2_frames() {
+        let mut frames = frame(b"TIT2", "Title");
+        frames.extend(frame(b"TPE1", "Artist"));
+        frames.extend(frame(b"TRCK", "3/12"));
+        frames.extend(frame(b"TCON", "(17)"));
+        let data = tag(&frames, frames.len() as u32);
+
+        let (tags, end) = read_v2(&mut Cursor::new(data)).unwrap();
+        let tags = tags.unwrap();
+
+        assert_eq!(end, 10 + frames.len() as u64);
+        assert_eq!(tags.title.as_deref(), Some("Title"));
+        assert_eq!(tags.artist.as_deref(), Some("Artist"));
+        assert_eq!(tags.track, Some(3));
+        assert_eq!(tags.genre.as_deref(), Some("Rock"));
+    }
+
+    #[test]
+    fn skips_truncated_frames() {
+        let mut frames = frame(b"TIT2", "Title");
+        frames.truncate(frames.len() - 2);
+        let data = tag(&frames, 100);
+
+        let (tags, end) = read_v2(&mut Cursor::new(data)).unwrap();
+
+        assert!(tags.is_none());
+        assert_eq!(end, 110);
+    }
+
+    #[test]
+    fn reads_v1_trailers_only() {
+        let mut trailer = vec![0u8; 128];
+        trailer[..3].copy_from_slice(b"TAG");
+        trailer[3..8].copy_from_slice(b"Title");
+        trailer[93..97].copy_from_slice(b"1999");
+        trailer[126] = 7;
+        trailer[127] = 0;
+
+        let tags = read_v1(&mut Cursor::new(trailer)).unwrap().unwrap();
+        assert_eq!(tags.title.as_deref(), Some("Title"));
+        assert_eq!(tags.year, Some(1999));
+        assert_eq!(tags.track, Some(7));
+        assert_eq!(tags.genre.as_deref(), Some("Blues"));
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".repeat(10);
+        assert!(read_v1(&mut Cursor::new(png)).unwrap().is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use super::id3::genre_name;
+use super::{text, year, AudioTags};
+use crate::metadata::isobmff;
+use std::io::{self, Read, Seek};
+
+/// The largest tag value read. Larger ones, such as cover art, are skipped
+const MAX_VALUE_SIZE: u64 = 1024 * 1024;
+
+/// Reads the iTunes style tags of an MP4 file, in `moov/udta/meta/ilst`
+pub fn read_ilst<R: Read + Seek>(reader: &mut R) -> io::Result<Option<AudioTags>> {
+    let ilst = match isobmff::find_path(reader, &[b"moov", b"udta", b"meta", b"ilst"])? {
+        Some(ilst) => ilst,
+        None => return Ok(None),
+    };
+
+    let mut tags = AudioTags::default();
+
+    for item in isobmff::children(reader, ilst.start, ilst.end)? {
+        let value = match &item.kind {
+            b"\xa9nam" | b"\xa9ART" | b"\xa9alb" | b"\xa9day" | b"\xa9gen" | b"gnre" | b"trkn"
+            | b"disk" => match read_data(reader, item)? {
+                Some(value) => value,
+                None => continue,
+            },
+            _ => continue,
+        };
+
+        let string = || text(&String::from_utf8_lossy(&value));
+
+        match &item.kind {
+            b"\xa9nam" => tags.title = string(),
+            b"\xa9ART" => tags.artist = string(),
+            b"\xa9alb" => tags.album = string(),
+            b"\xa9day" => tags.year = string().as_deref().and_then(year),
+            b"\xa9gen" => tags.genre = string().or(tags.genre),
+            // Numeric genres are ID3v1 genres, plus one
+            b"gnre" if tags.genre.is_none() => {
+                tags.genre = big_endian(&value)
+                    .filter(|index| *index > 0)
+                    .and_then(|index| genre_name(index as usize - 1))
+            }
+            // Track and disc numbers are stored as binary pairs: number, then count
+            b"trkn" => tags.track = value.get(2..4).and_then(big_endian).filter(|n| *n > 0),
+            b"disk" => tags.disc = value.get(2..4).and_then(big_endian).filter(|n| *n > 0),
+            _ => {}
+        }
+    }
+
+    Ok(tags.found())
+}
+
+/// Reads the value of the `data` box of a tag, after its type and locale
+fn read_data<R: Read + Seek>(reader: &mut R, item: isobmff::IsoBox) -> io::Result<Option<Vec<u8>>> {
+    let data = match isobmff::find(reader, b"data", item.start, item.end)? {
+        Some(data) if data.len() >= 8 && data.len() <= MAX_VALUE_SIZE => data,
+        _ => return Ok(None),
+    };
+
+    let value = data.read(reader, MAX_VALUE_SIZE)?;
+
+    Ok(Some(value[8..].to_vec()))
+}
+
+fn big_endian(bytes: &[u8]) -> Option<u32> {
+    match bytes.len() {
+        1..=4 => Some(bytes.iter().fold(0, |value, b| (value << 8) | *b as u32)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn iso_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend(kind);
+        data.extend(payload);
+        data
+    }
+
+    fn item(kind: &[u8; 4], value: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; 8];
+        data.extend(value);
+        iso_box(kind, &iso_box(b"data", &data))
+    }
+
+    fn file(ilst: &[u8]) -> Vec<u8> {
+        let mut meta = vec![0u8; 4];
+        meta.extend(iso_box(b"ilst", ilst));
+        let udta = iso_box(b"udta", &iso_box(b"meta", &meta));
+
+        let mut data = iso_box(b"ftyp", b"M4A \0\0\0\0");
+        data.extend(iso_box(b"moov", &udta));
+        data
+    }
+
+    #[test]
+    fn reads_ilst_items() {
+        let mut ilst = item(b"\xa9nam", b"Title");
+        ilst.extend(item(b"\xa9day", b"2019-05-01T00:00:00Z"));
+        ilst.extend(item(b"trkn", &[0, 0, 0, 5, 0, 12, 0, 0]));
+        ilst.extend(item(b"gnre", &[0, 18]));
+
+        let tags = read_ilst(&mut Cursor::new(file(&ilst))).unwrap().unwrap();
+
+        assert_eq!(tags.title.as_deref(), Some("Title"));
+        assert_eq!(tags.year, Some(2019));
+        assert_eq!(tags.track, Some(5));
+        assert_eq!(tags.genre.as_deref(), Some("Rock"));
+    }
+
+    #[test]
+    fn skips_truncated_items() {
+        let mut ilst = item(b"\xa9nam", b"Title");
+        ilst.extend(iso_box(b"\xa9ART", &iso_box(b"data", b"\0\0")));
+        let mut data = file(&ilst);
+        let truncated = data.len() - 30;
+        data.truncate(truncated);
+
+        assert!(read_ilst(&mut Cursor::new(data)).unwrap().is_none());
+
+        let tags = read_ilst(&mut Cursor::new(file(&ilst))).unwrap().unwrap();
+        assert_eq!(tags.title.as_deref(), Some("Title"));
+        assert_eq!(tags.artist, None);
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+
+        assert!(read_ilst(&mut Cursor::new(png)).unwrap().is_none());
+    }
+}
@@ -0,0 +1,234 @@
+use super::{number, text, year, AudioTags};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// The largest comment block read. Cover art can be stored as a comment
+const MAX_COMMENTS_SIZE: usize = 64 * 1024 * 1024;
+
+/// The number of Ogg pages read before giving up on finding the comments
+const MAX_OGG_PAGES: usize = 512;
+
+/// The `VORBIS_COMMENT` type of a FLAC metadata block
+const FLAC_VORBIS_COMMENT: u8 = 4;
+
+/// Reads the Vorbis comments of a FLAC file
+///
+/// **NOTE:** The reader must be right after the `fLaC` marker. A file that
+/// ends before its last metadata block has no comments
+pub fn read_flac<R: Read + Seek>(reader: &mut R) -> io::Result<Option<AudioTags>> {
+    loop {
+        let mut header = [0u8; 4];
+        match reader.read_exact(&mut header) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            result => result?,
+        }
+
+        let last = header[0] & 0x80 != 0;
+        let size = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+
+        if header[0] & 0x7f == FLAC_VORBIS_COMMENT {
+            let mut block = Vec::with_capacity(size.min(MAX_COMMENTS_SIZE));
+            reader
+                .take(size.min(MAX_COMMENTS_SIZE) as u64)
+                .read_to_end(&mut block)?;
+
+            return Ok(parse_comments(&block));
+        }
+
+        if last {
+            return Ok(None);
+        }
+
+        reader.seek(SeekFrom::Current(size as i64))?;
+    }
+}
+
+/// Reads the comments of an Ogg Vorbis, Opus or FLAC file, found in the
+/// second packet of the first stream
+pub fn read_ogg<R: Read + Seek>(reader: &mut R) -> io::Result<Option<AudioTags>> {
+    let mut identification: Option<Vec<u8>> = None;
+    let mut packet = Vec::new();
+    let mut serial = None;
+
+    for _ in 0..MAX_OGG_PAGES {
+        let mut header = [0u8; 27];
+        if reader.read_exact(&mut header).is_err() || &header[..4] != b"OggS" {
+            return Ok(None);
+        }
+
+        let mut segments = vec![0u8; header[26] as usize];
+        reader.read_exact(&mut segments)?;
+        let page_len: u64 = segments.iter().map(|len| *len as u64).sum();
+
+        // Pages of other streams may be interleaved with the first one
+        let page_serial = [header[14], header[15], header[16], header[17]];
+        if *serial.get_or_insert(page_serial) != page_serial {
+            reader.seek(SeekFrom::Current(page_len as i64))?;
+            continue;
+        }
+
+        for len in segments {
+            reader.by_ref().take(len as u64).read_to_end(&mut packet)?;
+
+            if packet.len() > MAX_COMMENTS_SIZE {
+                return Ok(None);
+            }
+
+            // A segment shorter than 255 bytes ends its packet
+            if len < 255 {
+                match &identification {
+                    Some(identification) => return Ok(comments_packet(identification, &packet)),
+                    None => identification = Some(std::mem::take(&mut packet)),
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Reads the comments packet of a stream, depending on its codec
+fn comments_packet(identification: &[u8], comments: &[u8]) -> Option<AudioTags> {
+    if identification.starts_with(b"\x01vorbis") {
+        return comments
+            .strip_prefix(b"\x03vorbis")
+            .and_then(parse_comments);
+    }
+
+    if identification.starts_with(b"OpusHead") {
+        return comments.strip_prefix(b"OpusTags").and_then(parse_comments);
+    }
+
+    // FLAC in Ogg wraps its metadata blocks in packets, headers included
+    if identification.starts_with(b"\x7fFLAC") {
+        return match comments.first() {
+            Some(kind) if kind & 0x7f == FLAC_VORBIS_COMMENT => parse_comments(comments.get(4..)?),
+            _ => None,
+        };
+    }
+
+    None
+}
+
+/// Reads a Vorbis comment block: a vendor string, then `KEY=value` comments
+fn parse_comments(block: &[u8]) -> Option<AudioTags> {
+    let mut pos = 0;
+
+    let _vendor = next_string(block, &mut pos)?;
+    let count = next_u32(block, &mut pos)?;
+
+    let mut tags = AudioTags::default();
+    for _ in 0..count {
+        let comment = match next_string(block, &mut pos) {
+            Some(comment) => String::from_utf8_lossy(comment),
+            None => break,
+        };
+
+        let (key, value) = match comment.split_once('=') {
+            Some(comment) => comment,
+            None => continue,
+        };
+
+        // Keys are case insensitive, and only the first of repeated keys is kept
+        match key.to_ascii_uppercase().as_str() {
+            "TITLE" if tags.title.is_none() => tags.title = text(value),
+            "ARTIST" if tags.artist.is_none() => tags.artist = text(value),
+            "ALBUM" if tags.album.is_none() => tags.album = text(value),
+            "TRACKNUMBER" if tags.track.is_none() => tags.track = number(value),
+            "DISCNUMBER" if tags.disc.is_none() => tags.disc = number(value),
+            "DATE" | "YEAR" if tags.year.is_none() => tags.year = year(value),
+            "GENRE" if tags.genre.is_none() => tags.genre = text(value),
+            _ => {}
+        }
+    }
+
+    tags.found()
+}
+
+fn next_u32(block: &[u8], pos: &mut usize) -> Option<u32> {
+    let value = block.get(*pos..*pos + 4)?;
+    *pos += 4;
+
+    Some(u32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+}
+
+/// Reads a string prefixed by its length
+fn next_string<'a>(block: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+    let len = next_u32(block, pos)? as usize;
+    let value = block.get(*pos..pos.checked_add(len)?)?;
+    *pos += len;
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn comments(comments: &[&str]) -> Vec<u8> {
+        let mut block = 6u32.to_le_bytes().to_vec();
+        block.extend(b"vendor");
+        block.extend((comments.len() as u32).to_le_bytes());
+        for comment in comments {
+            block.extend((comment.len() as u32).to_le_bytes());
+            block.extend(comment.as_bytes());
+        }
+        block
+    }
+
+    fn flac_block(kind: u8, last: bool, block: &[u8]) -> Vec<u8> {
+        let mut data = vec![kind | if last { 0x80 } else { 0 }];
+        data.extend(&(block.len() as u32).to_be_bytes()[1..]);
+        data.extend(block);
+        data
+    }
+
+    #[test]
+    fn reads_flac_comments() {
+        let mut data = flac_block(0, false, &[0u8; 34]);
+        data.extend(flac_block(
+            FLAC_VORBIS_COMMENT,
+            true,
+            &comments(&[
+                "title=Title",
+                "TRACKNUMBER=4/10",
+                "DATE=2019-05-01",
+                "TITLE=Other",
+            ]),
+        ));
+
+        let tags = read_flac(&mut Cursor::new(data)).unwrap().unwrap();
+
+        assert_eq!(tags.title.as_deref(), Some("Title"));
+        assert_eq!(tags.track, Some(4));
+        assert_eq!(tags.year, Some(2019));
+    }
+
+    #[test]
+    fn stops_at_truncated_comments() {
+        let mut block = comments(&["TITLE=Title", "ARTIST=Artist"]);
+        block.truncate(block.len() - 4);
+        let tags = parse_comments(&block).unwrap();
+        assert_eq!(tags.title.as_deref(), Some("Title"));
+        assert_eq!(tags.artist, None);
+
+        // A comment count far larger than the block
+        let mut block = 0u32.to_le_bytes().to_vec();
+        block.extend(u32::MAX.to_le_bytes());
+        assert!(parse_comments(&block).is_none());
+
+        // Metadata blocks that end before the last one
+        let data = flac_block(0, false, &[0u8; 34]);
+        assert_eq!(read_flac(&mut Cursor::new(data)).unwrap(), None);
+        let mut data = flac_block(0, false, &[0u8; 34]);
+        data.extend([0x84, 0, 0]);
+        assert_eq!(read_flac(&mut Cursor::new(data)).unwrap(), None);
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".repeat(4);
+
+        assert!(read_ogg(&mut Cursor::new(png)).unwrap().is_none());
+    }
+}
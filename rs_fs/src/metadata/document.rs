@@ -0,0 +1,173 @@
+mod epub;
+mod office;
+mod pdf;
+mod xmp;
+
+use crate::error_factory::create_error;
+use crate::metadata::archive::ZipPackage;
+use crate::metadata::read_up_to;
+use chrono::{NaiveDate, NaiveDateTime};
+use rs_response::{DataResponse, ErrorRepsonse};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const ERR_SRC: &str = "metadata::document::read_document_info()";
+
+/// How far into a file the `%PDF-` header is looked for
+const PDF_HEADER_SEARCH: usize = 1024;
+
+/// The metadata of a document, such as a PDF, an EPUB book or an office document
+///
+/// **NOTE:** Every property is `None` when the document does not record it
+///
+/// # Properties:
+/// - `title`: `Option<String>` - The title of the document
+/// - `author`: `Option<String>` - The author, or the first creator of a book
+/// - `subject`: `Option<String>` - The subject or description of the document
+/// - `language`: `Option<String>` - The language code, e.g. `en` or `fr-CA`
+/// - `series`: `Option<String>` - The series a book belongs to
+/// - `modified_by`: `Option<String>` - The last person who modified the document
+/// - `created`: `Option<NaiveDateTime>` - The creation date, in the time zone it was recorded in
+/// - `modified`: `Option<NaiveDateTime>` - The date of the last modification, in the time zone it was recorded in
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct DocumentInfo {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub language: Option<String>,
+    pub series: Option<String>,
+    pub modified_by: Option<String>,
+    pub created: Option<NaiveDateTime>,
+    pub modified: Option<NaiveDateTime>,
+}
+
+/// Reads the metadata of a document
+///
+/// Supports PDF files, from their document information dictionary and XMP
+/// metadata, EPUB books, from their OPF package, Office Open XML files
+/// (.docx, .xlsx, .pptx), from their core properties, and OpenDocument files
+/// (.odt, .ods), from their `meta.xml`
+///
+/// **NOTE:** Returns `None` for documents without metadata, including any
+/// file that is not a supported document and encrypted PDF files
+///
+/// # Arguments:
+/// - `path`: `&Path` - The file to read
+///
+/// # Example:
+/// ```
+/// use rs_fs::read_document_info;
+/// use rs_response::DataResponse;
+/// use std::path::Path;
+///
+/// fn author(path: &Path) -> DataResponse<Option<String>> {
+///   let info = read_document_info(path)?;
+///
+///   Ok(info.and_then(|info| info.author))
+/// }
+/// ```
+pub fn read_document_info(path: &Path) -> DataResponse<Option<DocumentInfo>> {
+    let file = File::open(path).map_err(|e| read_error(path, e))?;
+    let mut reader = BufReader::new(file);
+
+    read_info(path, &mut reader).map_err(|e| read_error(path, e))
+}
+
+fn read_info<R: Read + Seek>(path: &Path, reader: &mut R) -> io::Result<Option<DocumentInfo>> {
+    let mut header = [0u8; PDF_HEADER_SEARCH];
+    let header_len = read_up_to(reader, &mut header)?;
+    let header = &header[..header_len];
+    reader.seek(SeekFrom::Start(0))?;
+
+    if header.starts_with(b"PK\x03\x04") {
+        let mut package = match ZipPackage::open(path)? {
+            Some(package) => package,
+            None => return Ok(None),
+        };
+
+        match epub::read(&mut package)? {
+            Some(info) => Ok(Some(info)),
+            None => office::read(&mut package),
+        }
+    } else if header.windows(5).any(|window| window == b"%PDF-") {
+        // Some writers put junk bytes before the header
+        pdf::read(reader)
+    } else {
+        Ok(None)
+    }
+}
+
+impl DocumentInfo {
+    /// Fills every missing property from `other`
+    fn or(self, other: Self) -> Self {
+        Self {
+            title: self.title.or(other.title),
+            author: self.author.or(other.author),
+            subject: self.subject.or(other.subject),
+            language: self.language.or(other.language),
+            series: self.series.or(other.series),
+            modified_by: self.modified_by.or(other.modified_by),
+            created: self.created.or(other.created),
+            modified: self.modified.or(other.modified),
+        }
+    }
+
+    /// `None` when no metadata was found
+    fn found(self) -> Option<Self> {
+        match self == Self::default() {
+            true => None,
+            false => Some(self),
+        }
+    }
+}
+
+/// Trims a text value, `None` when it ends up empty
+fn text(value: &str) -> Option<String> {
+    let value = value.trim_matches(|c: char| c.is_whitespace() || c == '\0');
+
+    match value.is_empty() {
+        true => None,
+        false => Some(value.to_string()),
+    }
+}
+
+/// Reads a W3C date, such as `2023`, `2023-04-01` or `2023-04-01T13:45:00+02:00`
+///
+/// **NOTE:** The time zone is dropped, the date keeps the time it was recorded in
+fn iso_date(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    let number = |range: std::ops::Range<usize>| -> Option<u32> {
+        let digits = value.get(range)?;
+        match digits.bytes().all(|b| b.is_ascii_digit()) {
+            true => digits.parse().ok(),
+            false => None,
+        }
+    };
+
+    let date = NaiveDate::from_ymd_opt(
+        number(0..4)? as i32,
+        number(5..7).unwrap_or(1),
+        number(8..10).unwrap_or(1),
+    )?;
+
+    match value.get(10..11) {
+        Some("T") | Some(" ") => date.and_hms_opt(
+            number(11..13)?,
+            number(14..16)?,
+            number(17..19).unwrap_or(0),
+        ),
+        _ => date.and_hms_opt(0, 0, 0),
+    }
+}
+
+fn read_error(path: &Path, e: io::Error) -> ErrorRepsonse {
+    create_error(
+        format!(
+            "Unable to read the document metadata of '{}'",
+            path.display()
+        ),
+        e.to_string(),
+        ERR_SRC,
+    )
+}
@@ -0,0 +1,180 @@
+use super::{text, DocumentInfo};
+use crate::metadata::archive::ZipPackage;
+use roxmltree::{Document, Node};
+use std::io;
+
+const DC: &str = "http://purl.org/dc/elements/1.1/";
+const OPF: &str = "http://www.idpf.org/2007/opf";
+const CONTAINER: &str = "urn:oasis:names:tc:opendocument:xmlns:container";
+
+/// Reads the title, author, language and series of an EPUB book from its OPF
+/// package
+///
+/// **NOTE:** Returns `None` for any other zip archive
+pub fn read(package: &mut ZipPackage) -> io::Result<Option<DocumentInfo>> {
+    if package.mimetype()?.as_deref() != Some("application/epub+zip") {
+        return Ok(None);
+    }
+
+    let opf_path = match package.read_text("META-INF/container.xml")? {
+        Some(container) => rootfile(&container),
+        None => None,
+    };
+    let opf = match opf_path {
+        Some(path) => package.read_text(&path)?,
+        None => None,
+    };
+
+    Ok(opf
+        .and_then(|opf| read_opf(&opf))
+        .and_then(DocumentInfo::found))
+}
+
+/// Reads the path of the OPF package, the first `rootfile` of `container.xml`
+fn rootfile(container: &str) -> Option<String> {
+    let document = Document::parse(container).ok()?;
+
+    let path = document
+        .descendants()
+        .find(|node| node.has_tag_name((CONTAINER, "rootfile")))?
+        .attribute("full-path")?;
+
+    Some(path.to_string())
+}
+
+fn read_opf(opf: &str) -> Option<DocumentInfo> {
+    let document = Document::parse(opf).ok()?;
+    let metadata = document
+        .descendants()
+        .find(|node| node.has_tag_name((OPF, "metadata")))?;
+
+    let elements = |name: &'static str| {
+        metadata
+            .descendants()
+            .filter(move |node| node.has_tag_name((DC, name)))
+    };
+
+    // The first creator with the author role, or the first creator when no role is given
+    let author = elements("creator")
+        .find(|node| is_author(&document, node))
+        .or_else(|| elements("creator").next());
+
+    Some(DocumentInfo {
+        title: elements("title").find_map(|node| text(node.text()?)),
+        author: author.and_then(|node| text(node.text()?)),
+        subject: elements("description").find_map(|node| text(node.text()?)),
+        language: elements("language").find_map(|node| text(node.text()?)),
+        series: series(metadata),
+        modified_by: None,
+        created: None,
+        modified: None,
+    })
+}
+
+/// Whether a creator has the `aut` role, as an EPUB 2 attribute or an EPUB 3
+/// refinement
+fn is_author(document: &Document, creator: &Node) -> bool {
+    if let Some(role) = creator.attribute((OPF, "role")) {
+        return role == "aut";
+    }
+
+    let id = match creator.attribute("id") {
+        Some(id) => format!("#{}", id),
+        None => return false,
+    };
+
+    document.descendants().any(|node| {
+        node.tag_name().name() == "meta"
+            && node.attribute("refines") == Some(id.as_str())
+            && node.attribute("property") == Some("role")
+            && node.text().map(str::trim) == Some("aut")
+    })
+}
+
+/// Reads the series of a book, from the Calibre `calibre:series` meta or the
+/// EPUB 3 `belongs-to-collection` meta
+fn series(metadata: Node) -> Option<String> {
+    let mut metas = metadata
+        .children()
+        .filter(|node| node.tag_name().name() == "meta");
+
+    metas.find_map(
+        |node| match (node.attribute("name"), node.attribute("property")) {
+            (Some("calibre:series"), _) => text(node.attribute("content")?),
+            (_, Some("belongs-to-collection")) => text(node.text()?),
+            _ => None,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::archive::temp_zip;
+
+    const CONTAINER_XML: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles>
+</container>"#;
+
+    const OPF_XML: &str = r##"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:title>The Book</dc:title>
+    <dc:creator id="editor">Ed</dc:creator>
+    <dc:creator id="author">Ann</dc:creator>
+    <meta refines="#author" property="role">aut</meta>
+    <dc:language>fr</dc:language>
+    <meta name="calibre:series" content="Saga"/>
+  </metadata>
+</package>"##;
+
+    fn read_zip(name: &str, entries: &[(&str, &str)]) -> Option<DocumentInfo> {
+        let path = temp_zip(name, entries);
+        let info = read(&mut ZipPackage::open(&path).unwrap().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        info
+    }
+
+    #[test]
+    fn reads_the_opf_package() {
+        let info = read_zip(
+            "epub",
+            &[
+                ("mimetype", "application/epub+zip"),
+                ("META-INF/container.xml", CONTAINER_XML),
+                ("OEBPS/content.opf", OPF_XML),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(info.title.as_deref(), Some("The Book"));
+        assert_eq!(info.author.as_deref(), Some("Ann"));
+        assert_eq!(info.language.as_deref(), Some("fr"));
+        assert_eq!(info.series.as_deref(), Some("Saga"));
+    }
+
+    #[test]
+    fn reads_nothing_from_damaged_books() {
+        let truncated = &OPF_XML[..OPF_XML.len() / 2];
+        let entries = [
+            ("mimetype", "application/epub+zip"),
+            ("META-INF/container.xml", CONTAINER_XML),
+            ("OEBPS/content.opf", truncated),
+        ];
+        assert!(read_zip("epub-truncated", &entries).is_none());
+
+        let entries = [
+            ("mimetype", "application/epub+zip"),
+            ("OEBPS/content.opf", OPF_XML),
+        ];
+        assert!(read_zip("epub-no-container", &entries).is_none());
+    }
+
+    #[test]
+    fn reads_nothing_from_other_archives() {
+        let entries = [("OEBPS/content.opf", OPF_XML)];
+
+        assert!(read_zip("epub-other", &entries).is_none());
+    }
+}
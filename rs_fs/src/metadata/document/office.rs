@@ -0,0 +1,201 @@
+use super::{iso_date, text, DocumentInfo};
+use crate::metadata::archive::ZipPackage;
+use roxmltree::{Document, Node};
+use std::io;
+
+const DC: &str = "http://purl.org/dc/elements/1.1/";
+const DCTERMS: &str = "http://purl.org/dc/terms/";
+const CORE: &str = "http://schemas.openxmlformats.org/package/2006/metadata/core-properties";
+const RELATIONSHIPS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+const CORE_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties";
+const OFFICE: &str = "urn:oasis:names:tc:opendocument:xmlns:office:1.0";
+const ODF_META: &str = "urn:oasis:names:tc:opendocument:xmlns:meta:1.0";
+
+/// Where the core properties of an Office Open XML file usually are
+const DEFAULT_CORE_PATH: &str = "docProps/core.xml";
+
+/// Reads the properties of an Office Open XML file (.docx, .xlsx, .pptx) or
+/// an OpenDocument file (.odt, .ods)
+///
+/// **NOTE:** Returns `None` for any other zip archive
+pub fn read(package: &mut ZipPackage) -> io::Result<Option<DocumentInfo>> {
+    let is_odf = package
+        .mimetype()?
+        .is_some_and(|mimetype| mimetype.starts_with("application/vnd.oasis.opendocument."));
+
+    let info = if is_odf {
+        package
+            .read_text("meta.xml")?
+            .and_then(|xml| read_odf(&xml))
+    } else {
+        let path = match package.read_text("_rels/.rels")? {
+            Some(rels) => core_path(&rels),
+            None => None,
+        };
+
+        package
+            .read_text(path.as_deref().unwrap_or(DEFAULT_CORE_PATH))?
+            .and_then(|xml| read_core(&xml))
+    };
+
+    Ok(info.and_then(DocumentInfo::found))
+}
+
+/// Reads the path of the core properties from the package relationships
+fn core_path(rels: &str) -> Option<String> {
+    let document = Document::parse(rels).ok()?;
+
+    let target = document
+        .descendants()
+        .filter(|node| node.has_tag_name((RELATIONSHIPS, "Relationship")))
+        .find(|node| node.attribute("Type") == Some(CORE_RELATIONSHIP))?
+        .attribute("Target")?;
+
+    Some(target.trim_start_matches('/').to_string())
+}
+
+/// Reads `docProps/core.xml`, the core properties of an Office Open XML file
+fn read_core(xml: &str) -> Option<DocumentInfo> {
+    let document = Document::parse(xml).ok()?;
+    let root = document.root_element();
+    if !root.has_tag_name((CORE, "coreProperties")) {
+        return None;
+    }
+
+    Some(DocumentInfo {
+        title: child_text(root, DC, "title"),
+        author: child_text(root, DC, "creator"),
+        subject: child_text(root, DC, "subject"),
+        language: child_text(root, DC, "language"),
+        series: None,
+        modified_by: child_text(root, CORE, "lastModifiedBy"),
+        created: child_text(root, DCTERMS, "created")
+            .as_deref()
+            .and_then(iso_date),
+        modified: child_text(root, DCTERMS, "modified")
+            .as_deref()
+            .and_then(iso_date),
+    })
+}
+
+/// Reads `meta.xml`, the metadata of an OpenDocument file
+///
+/// **NOTE:** There, `dc:creator` is the last person who modified the document
+/// and `meta:initial-creator` its author
+fn read_odf(xml: &str) -> Option<DocumentInfo> {
+    let document = Document::parse(xml).ok()?;
+    let meta = document
+        .root_element()
+        .children()
+        .find(|node| node.has_tag_name((OFFICE, "meta")))?;
+
+    Some(DocumentInfo {
+        title: child_text(meta, DC, "title"),
+        author: child_text(meta, ODF_META, "initial-creator"),
+        subject: child_text(meta, DC, "subject"),
+        language: child_text(meta, DC, "language"),
+        series: None,
+        modified_by: child_text(meta, DC, "creator"),
+        created: child_text(meta, ODF_META, "creation-date")
+            .as_deref()
+            .and_then(iso_date),
+        modified: child_text(meta, DC, "date").as_deref().and_then(iso_date),
+    })
+}
+
+fn child_text(parent: Node, namespace: &str, name: &str) -> Option<String> {
+    parent
+        .children()
+        .find(|node| node.has_tag_name((namespace, name)))
+        .and_then(|node| text(node.text()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::archive::temp_zip;
+
+    const RELS: &str = r#"<?xml version="1.0"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Target="/props/core.xml"
+    Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties"/>
+</Relationships>"#;
+
+    const CORE_XML: &str = r#"<?xml version="1.0"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties"
+    xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/">
+  <dc:title>Budget</dc:title>
+  <dc:creator>Ann</dc:creator>
+  <cp:lastModifiedBy>Bob</cp:lastModifiedBy>
+  <dcterms:created>2019-05-01T12:30:00Z</dcterms:created>
+  <dcterms:modified>2019-06-01T08:00:00Z</dcterms:modified>
+</cp:coreProperties>"#;
+
+    const META_XML: &str = r#"<?xml version="1.0"?>
+<office:document-meta xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+    xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0"
+    xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <office:meta>
+    <dc:title>Letter</dc:title>
+    <meta:initial-creator>Ann</meta:initial-creator>
+    <dc:creator>Bob</dc:creator>
+    <dc:language>de</dc:language>
+    <dc:date>2019-06-01T08:00:00.123</dc:date>
+  </office:meta>
+</office:document-meta>"#;
+
+    fn read_zip(name: &str, entries: &[(&str, &str)]) -> Option<DocumentInfo> {
+        let path = temp_zip(name, entries);
+        let info = read(&mut ZipPackage::open(&path).unwrap().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        info
+    }
+
+    #[test]
+    fn reads_ooxml_and_odf_properties() {
+        let info = read_zip(
+            "ooxml",
+            &[("_rels/.rels", RELS), ("props/core.xml", CORE_XML)],
+        )
+        .unwrap();
+        assert_eq!(info.title.as_deref(), Some("Budget"));
+        assert_eq!(info.author.as_deref(), Some("Ann"));
+        assert_eq!(info.modified_by.as_deref(), Some("Bob"));
+        assert_eq!(info.created, iso_date("2019-05-01T12:30:00"));
+        assert_eq!(info.modified, iso_date("2019-06-01T08:00:00"));
+
+        let info = read_zip(
+            "odf",
+            &[
+                ("mimetype", "application/vnd.oasis.opendocument.text"),
+                ("meta.xml", META_XML),
+            ],
+        )
+        .unwrap();
+        assert_eq!(info.title.as_deref(), Some("Letter"));
+        assert_eq!(info.author.as_deref(), Some("Ann"));
+        assert_eq!(info.modified_by.as_deref(), Some("Bob"));
+        assert_eq!(info.language.as_deref(), Some("de"));
+        assert_eq!(info.modified, iso_date("2019-06-01T08:00:00"));
+    }
+
+    #[test]
+    fn reads_nothing_from_damaged_properties() {
+        let truncated = &CORE_XML[..CORE_XML.len() / 2];
+        assert!(read_zip("ooxml-truncated", &[("docProps/core.xml", truncated)]).is_none());
+
+        let entries = [
+            ("mimetype", "application/vnd.oasis.opendocument.text"),
+            ("meta.xml", "<office:document-meta/>"),
+        ];
+        assert!(read_zip("odf-damaged", &entries).is_none());
+    }
+
+    #[test]
+    fn reads_nothing_from_other_archives() {
+        let entries = [("docProps/core.xml", META_XML), ("readme.txt", "Hello")];
+
+        assert!(read_zip("office-other", &entries).is_none());
+    }
+}
@@ -0,0 +1,678 @@
+mod object;
+
+use super::{iso_date, text, xmp, DocumentInfo};
+use chrono::{NaiveDate, NaiveDateTime};
+use flate2::read::ZlibDecoder;
+use object::{Dict, Lexer, Object};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// How much of the end of the file is searched for `startxref`
+const TAIL_SIZE: u64 = 4096;
+
+/// The most bytes read to parse an object, before its stream data
+const MAX_OBJECT_SIZE: u64 = 64 * 1024;
+
+/// The largest cross-reference table or decoded stream read
+const MAX_XREF_SIZE: u64 = 32 * 1024 * 1024;
+
+/// How deep references are followed, to bound circular ones
+const MAX_DEPTH: usize = 8;
+
+/// Where an object is stored
+#[derive(Debug, Clone, Copy)]
+enum XrefEntry {
+    /// At an offset of the file
+    Offset(u64),
+    /// Inside an object stream, at an index
+    Compressed(u32, u32),
+}
+
+/// A PDF file, opened through its cross-reference sections
+struct Pdf<'r, R> {
+    reader: &'r mut R,
+    file_len: u64,
+    xref: HashMap<u32, XrefEntry>,
+    trailer: Dict,
+}
+
+/// Reads the document information dictionary and the XMP metadata of a PDF
+///
+/// **NOTE:** Values of the information dictionary take precedence over the
+/// XMP ones. Encrypted files have no readable metadata
+pub fn read<R: Read + Seek>(reader: &mut R) -> io::Result<Option<DocumentInfo>> {
+    let mut pdf = match Pdf::open(reader)? {
+        Some(pdf) => pdf,
+        None => return Ok(None),
+    };
+
+    if pdf.trailer.get(b"Encrypt").is_some() {
+        return Ok(None);
+    }
+
+    let mut info = DocumentInfo::default();
+
+    if let Some(Object::Dict(dict)) = pdf.resolve(pdf.trailer.get(b"Info").cloned(), 0)? {
+        let mut string = |key: &[u8]| -> io::Result<Option<String>> {
+            Ok(match pdf.resolve(dict.get(key).cloned(), 0)? {
+                Some(Object::String(value)) => text(&decode_text(&value)),
+                _ => None,
+            })
+        };
+
+        info.title = string(b"Title")?;
+        info.author = string(b"Author")?;
+        info.subject = string(b"Subject")?;
+        info.created = string(b"CreationDate")?.as_deref().and_then(pdf_date);
+        info.modified = string(b"ModDate")?.as_deref().and_then(pdf_date);
+    }
+
+    let catalog = pdf.resolve(pdf.trailer.get(b"Root").cloned(), 0)?;
+    if let Some(Object::Dict(catalog)) = catalog {
+        if let Some(Object::Stream(dict, data)) =
+            pdf.resolve(catalog.get(b"Metadata").cloned(), 0)?
+        {
+            if let Some(xml) = decode_stream(&dict, data) {
+                info = info.or(xmp::read(&String::from_utf8_lossy(&xml)));
+            }
+        }
+    }
+
+    Ok(info.found())
+}
+
+impl<'r, R: Read + Seek> Pdf<'r, R> {
+    /// Reads every cross-reference section, from the last one
+    fn open(reader: &'r mut R) -> io::Result<Option<Self>> {
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        let tail_start = file_len.saturating_sub(TAIL_SIZE);
+        let tail = read_at(reader, tail_start, TAIL_SIZE)?;
+
+        let start = match rfind(&tail, b"startxref") {
+            Some(start) => start + b"startxref".len(),
+            None => return Ok(None),
+        };
+        let mut offset = match Lexer::new(&tail[start..]).object() {
+            Some(Object::Number(offset)) if offset >= 0.0 => offset as u64,
+            _ => return Ok(None),
+        };
+
+        let mut pdf = Self {
+            reader,
+            file_len,
+            xref: HashMap::new(),
+            trailer: Dict::default(),
+        };
+        let mut visited = HashSet::new();
+
+        // Newer sections come first, so the entries already known win
+        loop {
+            if !visited.insert(offset) {
+                break;
+            }
+
+            let trailer = match pdf.read_xref(offset)? {
+                Some(trailer) => trailer,
+                None => break,
+            };
+
+            // Hybrid files store the entries of their compressed objects in a stream
+            if let Some(Object::Number(stream)) = trailer.get(b"XRefStm") {
+                pdf.read_xref(*stream as u64)?;
+            }
+
+            let prev = trailer.get(b"Prev").cloned();
+            if pdf.trailer.is_empty() {
+                pdf.trailer = trailer;
+            }
+
+            match prev {
+                Some(Object::Number(prev)) if prev >= 0.0 => offset = prev as u64,
+                _ => break,
+            }
+        }
+
+        match pdf.trailer.is_empty() {
+            true => Ok(None),
+            false => Ok(Some(pdf)),
+        }
+    }
+
+    /// Reads a cross-reference table or stream, returning its trailer
+    fn read_xref(&mut self, offset: u64) -> io::Result<Option<Dict>> {
+        if offset >= self.file_len {
+            return Ok(None);
+        }
+
+        if !read_at(self.reader, offset, 4)?.starts_with(b"xref") {
+            return self.read_xref_stream(offset);
+        }
+
+        // Subsections start with their first object number and their size,
+        // followed by 20 byte entries: `offset generation n|f`
+        let mut pos = offset + 4;
+        loop {
+            let header = read_at(self.reader, pos, 64)?;
+            let mut lexer = Lexer::new(&header);
+
+            let (first, count) = match (lexer.object(), lexer.object()) {
+                (Some(Object::Number(first)), Some(Object::Number(count))) => {
+                    (first as u32, count as u32)
+                }
+                _ => break,
+            };
+
+            pos += lexer.pos() as u64;
+            let table_len = count as u64 * 20 + 2;
+            if table_len > MAX_XREF_SIZE {
+                return Ok(None);
+            }
+
+            let table = read_at(self.reader, pos, table_len)?;
+            let mut lexer = Lexer::new(&table);
+
+            for number in first..first.saturating_add(count) {
+                match (lexer.object(), lexer.object(), lexer.object()) {
+                    (Some(Object::Number(offset)), Some(_), Some(Object::Keyword(kind))) => {
+                        if kind == b"n" {
+                            self.xref
+                                .entry(number)
+                                .or_insert(XrefEntry::Offset(offset as u64));
+                        }
+                    }
+                    _ => return Ok(None),
+                }
+            }
+
+            pos += lexer.pos() as u64;
+        }
+
+        // The subsections end with the `trailer` keyword, then its dictionary
+        let trailer = read_at(self.reader, pos, MAX_OBJECT_SIZE)?;
+        let mut lexer = Lexer::new(&trailer);
+
+        match (lexer.object(), lexer.object()) {
+            (Some(Object::Keyword(keyword)), Some(Object::Dict(trailer)))
+                if keyword == b"trailer" =>
+            {
+                Ok(Some(trailer))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Reads a cross-reference stream, whose dictionary is the trailer
+    fn read_xref_stream(&mut self, offset: u64) -> io::Result<Option<Dict>> {
+        let (dict, data) = match self.read_object_at(offset, 0)? {
+            Some(Object::Stream(dict, data)) => (dict, data),
+            _ => return Ok(None),
+        };
+
+        let data = match decode_stream(&dict, data) {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let widths: Vec<usize> = match dict.get(b"W") {
+            Some(Object::Array(widths)) if widths.len() == 3 => widths
+                .iter()
+                .map(|width| width.as_number().unwrap_or(0.0) as usize)
+                .collect(),
+            _ => return Ok(None),
+        };
+        let entry_len: usize = widths.iter().sum();
+        if entry_len == 0 || widths.iter().any(|width| *width > 8) {
+            return Ok(None);
+        }
+
+        let index: Vec<u32> = match dict.get(b"Index") {
+            Some(Object::Array(index)) => index
+                .iter()
+                .map(|value| value.as_number().unwrap_or(0.0) as u32)
+                .collect(),
+            _ => vec![
+                0,
+                dict.get(b"Size").and_then(Object::as_number).unwrap_or(0.0) as u32,
+            ],
+        };
+
+        let mut entries = data.chunks_exact(entry_len);
+        for range in index.chunks_exact(2) {
+            for number in range[0]..range[0].saturating_add(range[1]) {
+                let entry = match entries.next() {
+                    Some(entry) => entry,
+                    None => return Ok(Some(dict)),
+                };
+
+                let kind = match widths[0] {
+                    0 => 1,
+                    width => be_uint(&entry[..width]),
+                };
+                let field2 = be_uint(&entry[widths[0]..widths[0] + widths[1]]);
+                let field3 = be_uint(&entry[widths[0] + widths[1]..]);
+
+                let entry = match kind {
+                    1 => XrefEntry::Offset(field2),
+                    2 => XrefEntry::Compressed(field2 as u32, field3 as u32),
+                    _ => continue,
+                };
+                self.xref.entry(number).or_insert(entry);
+            }
+        }
+
+        Ok(Some(dict))
+    }
+
+    /// Follows a reference to its object, leaving any other object as is
+    fn resolve(&mut self, object: Option<Object>, depth: usize) -> io::Result<Option<Object>> {
+        match object {
+            Some(Object::Ref(number)) if depth < MAX_DEPTH => self.object(number, depth + 1),
+            Some(Object::Ref(_)) => Ok(None),
+            object => Ok(object),
+        }
+    }
+
+    /// Reads an object by number
+    fn object(&mut self, number: u32, depth: usize) -> io::Result<Option<Object>> {
+        match self.xref.get(&number).copied() {
+            Some(XrefEntry::Offset(offset)) => self.read_object_at(offset, depth),
+            Some(XrefEntry::Compressed(stream, index)) => {
+                self.read_compressed(stream, index, depth)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the indirect object at an offset, with the data of its stream
+    fn read_object_at(&mut self, offset: u64, depth: usize) -> io::Result<Option<Object>> {
+        let data = read_at(self.reader, offset, MAX_OBJECT_SIZE)?;
+        let mut lexer = Lexer::new(&data);
+
+        // `<number> <generation> obj`
+        match (lexer.object(), lexer.object(), lexer.object()) {
+            (Some(Object::Number(_)), Some(Object::Number(_)), Some(Object::Keyword(obj)))
+                if obj == b"obj" => {}
+            _ => return Ok(None),
+        }
+
+        let object = match lexer.object() {
+            Some(object) => object,
+            None => return Ok(None),
+        };
+
+        let (dict, start) = match (object, lexer.stream_start()) {
+            (Object::Dict(dict), Some(start)) => (dict, offset + start as u64),
+            (object, _) => return Ok(Some(object)),
+        };
+
+        let len = match self.resolve(dict.get(b"Length").cloned(), depth)? {
+            Some(Object::Number(len)) if len >= 0.0 => len as u64,
+            _ => return Ok(None),
+        };
+        if len > MAX_XREF_SIZE {
+            return Ok(None);
+        }
+
+        let data = read_at(self.reader, start, len)?;
+
+        Ok(Some(Object::Stream(dict, data)))
+    }
+
+    /// Reads an object stored in an object stream
+    fn read_compressed(
+        &mut self,
+        stream: u32,
+        index: u32,
+        depth: usize,
+    ) -> io::Result<Option<Object>> {
+        if depth >= MAX_DEPTH {
+            return Ok(None);
+        }
+
+        let (dict, data) = match self.object(stream, depth + 1)? {
+            Some(Object::Stream(dict, data)) => (dict, data),
+            _ => return Ok(None),
+        };
+        let data = match decode_stream(&dict, data) {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let first = dict
+            .get(b"First")
+            .and_then(Object::as_number)
+            .unwrap_or(0.0) as usize;
+        let mut header = Lexer::new(data.get(..first).unwrap_or_default());
+
+        // The header lists pairs of an object number and an offset after `First`
+        let mut offset = None;
+        for _ in 0..=index {
+            offset = match (header.object(), header.object()) {
+                (Some(Object::Number(_)), Some(Object::Number(offset))) => Some(offset as usize),
+                _ => return Ok(None),
+            };
+        }
+
+        Ok(offset
+            .and_then(|offset| first.checked_add(offset))
+            .and_then(|start| data.get(start..))
+            .and_then(|object| Lexer::new(object).object()))
+    }
+}
+
+/// Decompresses a stream, when it is not compressed or uses `FlateDecode`
+///
+/// **NOTE:** PNG predictors, common in cross-reference streams, are undone
+fn decode_stream(dict: &Dict, data: Vec<u8>) -> Option<Vec<u8>> {
+    let filter = match dict.get(b"Filter") {
+        None => return Some(data),
+        Some(Object::Name(filter)) => filter.clone(),
+        Some(Object::Array(filters)) if filters.len() == 1 => match &filters[0] {
+            Object::Name(filter) => filter.clone(),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    if filter != b"FlateDecode" {
+        return None;
+    }
+
+    let mut decoded = Vec::new();
+    ZlibDecoder::new(&data[..])
+        .take(MAX_XREF_SIZE)
+        .read_to_end(&mut decoded)
+        .ok()?;
+
+    let params = match dict.get(b"DecodeParms") {
+        Some(Object::Dict(params)) => params,
+        Some(Object::Array(params)) => match params.first() {
+            Some(Object::Dict(params)) => params,
+            _ => return Some(decoded),
+        },
+        _ => return Some(decoded),
+    };
+
+    let predictor = params
+        .get(b"Predictor")
+        .and_then(Object::as_number)
+        .unwrap_or(1.0);
+    if predictor < 10.0 {
+        return Some(decoded);
+    }
+
+    let columns = params
+        .get(b"Columns")
+        .and_then(Object::as_number)
+        .unwrap_or(1.0) as usize;
+    unpredict_png(&decoded, columns.max(1))
+}
+
+/// Undoes the PNG predictors of a stream, a filter byte starting every row
+fn unpredict_png(data: &[u8], columns: usize) -> Option<Vec<u8>> {
+    // A damaged `Columns` cannot make a row longer than the whole stream
+    let columns = columns.min(data.len());
+    let mut decoded = Vec::with_capacity(data.len());
+    let mut previous = vec![0u8; columns];
+
+    for row in data.chunks(columns + 1) {
+        let (filter, row) = row.split_first()?;
+        let mut current = vec![0u8; columns];
+
+        for (i, byte) in row.iter().enumerate() {
+            let left = if i > 0 { current[i - 1] } else { 0 };
+            let up = previous[i];
+            let up_left = if i > 0 { previous[i - 1] } else { 0 };
+
+            current[i] = match filter {
+                1 => byte.wrapping_add(left),
+                2 => byte.wrapping_add(up),
+                3 => byte.wrapping_add(((left as u16 + up as u16) / 2) as u8),
+                4 => byte.wrapping_add(paeth(left, up, up_left)),
+                _ => *byte,
+            };
+        }
+
+        decoded.extend_from_slice(&current[..row.len()]);
+        previous = current;
+    }
+
+    Some(decoded)
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let (to_left, to_up, to_up_left) = (
+        (estimate - left as i16).abs(),
+        (estimate - up as i16).abs(),
+        (estimate - up_left as i16).abs(),
+    );
+
+    match (
+        to_left <= to_up && to_left <= to_up_left,
+        to_up <= to_up_left,
+    ) {
+        (true, _) => left,
+        (false, true) => up,
+        (false, false) => up_left,
+    }
+}
+
+/// Decodes a text string: UTF-16BE or UTF-8 with a byte order mark, and
+/// `PDFDocEncoding` otherwise
+fn decode_text(value: &[u8]) -> String {
+    if let Some(utf16) = value.strip_prefix(b"\xfe\xff") {
+        let units = utf16
+            .chunks_exact(2)
+            .map(|unit| u16::from_be_bytes([unit[0], unit[1]]));
+
+        return char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+    }
+
+    if let Some(utf8) = value.strip_prefix(b"\xef\xbb\xbf") {
+        return String::from_utf8_lossy(utf8).to_string();
+    }
+
+    value.iter().map(|b| pdf_doc_char(*b)).collect()
+}
+
+/// Maps a byte of `PDFDocEncoding`, which differs from Latin-1 in a few places
+fn pdf_doc_char(b: u8) -> char {
+    const HIGH: [char; 33] = [
+        '•', '†', '‡', '…', '—', '–', 'ƒ', '⁄', '‹', '›', '−', '‰', '„', '“', '”', '‘', '’', '‚',
+        '™', 'ﬁ', 'ﬂ', 'Ł', 'Œ', 'Š', 'Ÿ', 'Ž', 'ı', 'ł', 'œ', 'š', 'ž', '\u{fffd}', '€',
+    ];
+
+    match b {
+        0x80..=0xa0 => HIGH[(b - 0x80) as usize],
+        _ => b as char,
+    }
+}
+
+/// Reads a date such as `D:20190501123000+02'00'`, keeping the local time
+///
+/// **NOTE:** Some writers store W3C dates instead, such as `2019-05-01`
+fn pdf_date(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim().trim_start_matches("D:");
+    if value.get(4..5) == Some("-") {
+        return iso_date(value);
+    }
+
+    let digits: String = value.chars().take_while(char::is_ascii_digit).collect();
+
+    let part = |range: std::ops::Range<usize>, default: u32| {
+        digits
+            .get(range)
+            .map(|part| part.parse().ok())
+            .unwrap_or(Some(default))
+    };
+
+    NaiveDate::from_ymd_opt(
+        digits.get(..4)?.parse().ok()?,
+        part(4..6, 1)?,
+        part(6..8, 1)?,
+    )?
+    .and_hms_opt(part(8..10, 0)?, part(10..12, 0)?, part(12..14, 0)?)
+}
+
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, limit: u64) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.seek(SeekFrom::Start(offset))?;
+    reader.take(limit).read_to_end(&mut data)?;
+
+    Ok(data)
+}
+
+fn rfind(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len())
+        .rposition(|window| window == needle)
+}
+
+fn be_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |value, b| (value << 8) | *b as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A PDF file with its objects, numbered from `1`, and a classic
+    /// cross-reference table
+    fn pdf(objects: &[&[u8]], trailer: &str) -> Vec<u8> {
+        let mut data = b"%PDF-1.7\n".to_vec();
+        let mut offsets = Vec::new();
+
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(data.len());
+            data.extend(format!("{} 0 obj\n", i + 1).as_bytes());
+            data.extend(*object);
+            data.extend(b"\nendobj\n");
+        }
+
+        let xref = data.len();
+        data.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes());
+        for offset in offsets {
+            data.extend(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        data.extend(format!("trailer\n{}\nstartxref\n{}\n%%EOF\n", trailer, xref).as_bytes());
+        data
+    }
+
+    /// A PDF file whose information dictionary is packed in an object
+    /// stream, listed by a cross-reference stream
+    fn packed_pdf(info_offset: &str) -> Vec<u8> {
+        let mut data = b"%PDF-1.7\n".to_vec();
+
+        let catalog = data.len();
+        data.extend(b"1 0 obj\n<< /Type /Catalog >>\nendobj\n");
+
+        let header = format!("2 {} ", info_offset);
+        let content = format!("{}<< /Title (Packed) >>", header);
+        let objects = data.len();
+        data.extend(
+            format!(
+                "3 0 obj\n<< /Type /ObjStm /N 1 /First {} /Length {} >>\nstream\n{}\nendstream\nendobj\n",
+                header.len(),
+                content.len(),
+                content
+            )
+            .as_bytes(),
+        );
+
+        let xref = data.len();
+        let mut entries = vec![0u8, 0, 0, 0];
+        entries.extend([1, 0, catalog as u8, 0]);
+        entries.extend([2, 0, 3, 0]);
+        entries.extend([1, 0, objects as u8, 0]);
+        entries.extend([1, 0, xref as u8, 0]);
+        data.extend(
+            b"4 0 obj\n<< /Type /XRef /W [1 2 1] /Size 5 /Root 1 0 R /Info 2 0 R /Length 20 >>\nstream\n",
+        );
+        data.extend(entries);
+        data.extend(format!("\nendstream\nendobj\nstartxref\n{}\n%%EOF\n", xref).as_bytes());
+        data
+    }
+
+    fn read_bytes(data: Vec<u8>) -> Option<DocumentInfo> {
+        read(&mut Cursor::new(data)).unwrap()
+    }
+
+    #[test]
+    fn reads_the_information_dictionary() {
+        let data = pdf(
+            &[
+                b"<< /Type /Catalog >>",
+                b"<< /Title (Annual \\(2019\\) Report) /Author <FEFF0041006E006E> \
+                  /CreationDate (D:20190501123000+02'00') /ModDate 3 0 R >>",
+                b"(2019-06-01T08:00:00Z)",
+            ],
+            "<< /Size 4 /Root 1 0 R /Info 2 0 R >>",
+        );
+
+        let info = read_bytes(data).unwrap();
+
+        assert_eq!(info.title.as_deref(), Some("Annual (2019) Report"));
+        assert_eq!(info.author.as_deref(), Some("Ann"));
+        assert_eq!(info.created, pdf_date("D:20190501123000"));
+        assert_eq!(info.modified, pdf_date("2019-06-01T08:00:00Z"));
+    }
+
+    #[test]
+    fn reads_cross_reference_and_object_streams() {
+        let info = read_bytes(packed_pdf("0")).unwrap();
+
+        assert_eq!(info.title.as_deref(), Some("Packed"));
+    }
+
+    #[test]
+    fn refuses_damaged_files() {
+        // An offset in the object stream that overflows
+        assert!(read_bytes(packed_pdf("18446744073709551615")).is_none());
+
+        let mut data = pdf(
+            &[b"<< >>", b"<< /Title (Report) >>"],
+            "<< /Root 1 0 R /Info 2 0 R /Encrypt 1 0 R >>",
+        );
+        assert!(read_bytes(data.clone()).is_none());
+
+        data.truncate(data.len() / 2);
+        assert!(read_bytes(data).is_none());
+
+        let data = pdf(&[b"<< /Title 1 0 R >>"], "<< /Info 1 0 R /Prev 0 >>");
+        assert!(read_bytes(data).is_none());
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        assert!(read_bytes(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec()).is_none());
+        assert!(read_bytes(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn undoes_png_predictors() {
+        // `Up` rows, then a `Sub` row
+        let data = [2, 1, 2, 2, 1, 1, 1, 5, 1];
+
+        assert_eq!(unpredict_png(&data, 2), Some(vec![1, 2, 2, 3, 5, 6]));
+        assert_eq!(unpredict_png(&data, usize::MAX).map(|d| d.len()), Some(8));
+        assert_eq!(unpredict_png(&[], 4), Some(Vec::new()));
+    }
+
+    #[test]
+    fn reads_pdf_dates() {
+        let date = |value: &str| pdf_date(value).map(|date| date.to_string());
+
+        assert_eq!(
+            date("D:20190501123000+02'00'").as_deref(),
+            Some("2019-05-01 12:30:00")
+        );
+        assert_eq!(date("D:2019").as_deref(), Some("2019-01-01 00:00:00"));
+        assert_eq!(date("2019-05-01").as_deref(), Some("2019-05-01 00:00:00"));
+        assert_eq!(date("D:20191301"), None);
+        assert_eq!(date("D:"), None);
+        assert_eq!(date("Tuesday"), None);
+    }
+}
@@ -0,0 +1,388 @@
+/// How deeply arrays and dictionaries may nest, to bound damaged files
+const MAX_NESTING: usize = 32;
+
+/// A PDF object
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Name(Vec<u8>),
+    String(Vec<u8>),
+    Array(Vec<Object>),
+    Dict(Dict),
+    /// A reference to an indirect object, by number
+    Ref(u32),
+    /// A dictionary with its raw stream data
+    Stream(Dict, Vec<u8>),
+    /// Any other word, such as `obj` or `trailer`
+    Keyword(Vec<u8>),
+}
+impl Object {
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Object::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+}
+
+/// A PDF dictionary, keyed by name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dict(Vec<(Vec<u8>, Object)>);
+impl Dict {
+    pub fn get(&self, key: &[u8]) -> Option<&Object> {
+        self.0
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Reads PDF objects from a buffer, one after the other
+pub struct Lexer<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+impl<'a> Lexer<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// The number of bytes read so far
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// The offset of the stream data, when a `stream` keyword follows
+    pub fn stream_start(&self) -> Option<usize> {
+        let mut pos = self.pos;
+        while self.data.get(pos).is_some_and(|b| is_whitespace(*b)) {
+            pos += 1;
+        }
+
+        if !self.data.get(pos..)?.starts_with(b"stream") {
+            return None;
+        }
+        pos += b"stream".len();
+
+        // The keyword is followed by CRLF or LF
+        match self.data.get(pos..pos + 2) {
+            Some(b"\r\n") => Some(pos + 2),
+            _ => Some(pos + 1),
+        }
+    }
+
+    /// Reads the next object, `None` at the end of the buffer or on a syntax error
+    pub fn object(&mut self) -> Option<Object> {
+        self.nested_object(0)
+    }
+
+    fn nested_object(&mut self, depth: usize) -> Option<Object> {
+        if depth > MAX_NESTING {
+            return None;
+        }
+
+        self.skip_whitespace();
+
+        match *self.data.get(self.pos)? {
+            b'<' if self.data.get(self.pos + 1) == Some(&b'<') => {
+                self.pos += 2;
+                let mut entries = Vec::new();
+
+                loop {
+                    self.skip_whitespace();
+                    if self.data.get(self.pos..)?.starts_with(b">>") {
+                        self.pos += 2;
+                        return Some(Object::Dict(Dict(entries)));
+                    }
+
+                    let key = match self.nested_object(depth + 1)? {
+                        Object::Name(key) => key,
+                        _ => return None,
+                    };
+                    let value = self.nested_object(depth + 1)?;
+                    entries.push((key, value));
+                }
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+
+                loop {
+                    self.skip_whitespace();
+                    if self.data.get(self.pos)? == &b']' {
+                        self.pos += 1;
+                        return Some(Object::Array(items));
+                    }
+
+                    items.push(self.nested_object(depth + 1)?);
+                }
+            }
+            b'<' => self.hex_string(),
+            b'(' => self.literal_string(),
+            b'/' => {
+                self.pos += 1;
+                Some(Object::Name(self.name()))
+            }
+            b'0'..=b'9' | b'+' | b'-' | b'.' => self.number_or_ref(),
+            _ => {
+                let word = self.word();
+                match word.as_slice() {
+                    b"" => None,
+                    b"true" => Some(Object::Bool(true)),
+                    b"false" => Some(Object::Bool(false)),
+                    b"null" => Some(Object::Null),
+                    _ => Some(Object::Keyword(word)),
+                }
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b) = self.data.get(self.pos) {
+            match b {
+                b'%' => {
+                    while self
+                        .data
+                        .get(self.pos)
+                        .is_some_and(|b| *b != b'\n' && *b != b'\r')
+                    {
+                        self.pos += 1;
+                    }
+                }
+                b if is_whitespace(*b) => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// Reads regular characters, up to a delimiter or whitespace
+    fn word(&mut self) -> Vec<u8> {
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|b| !is_whitespace(*b) && !is_delimiter(*b))
+        {
+            self.pos += 1;
+        }
+
+        self.data[start..self.pos].to_vec()
+    }
+
+    /// Reads a name, decoding its `#xx` escapes
+    fn name(&mut self) -> Vec<u8> {
+        let word = self.word();
+        let mut name = Vec::with_capacity(word.len());
+        let mut i = 0;
+
+        while i < word.len() {
+            match word.get(i + 1..i + 3).and_then(hex_byte) {
+                Some(b) if word[i] == b'#' => {
+                    name.push(b);
+                    i += 3;
+                }
+                _ => {
+                    name.push(word[i]);
+                    i += 1;
+                }
+            }
+        }
+
+        name
+    }
+
+    /// Reads a number, or a `<number> <generation> R` reference
+    fn number_or_ref(&mut self) -> Option<Object> {
+        let number: f64 = std::str::from_utf8(&self.word()).ok()?.parse().ok()?;
+
+        let after_number = self.pos;
+        if number >= 0.0 && number.fract() == 0.0 {
+            self.skip_whitespace();
+            if self.data.get(self.pos).is_some_and(u8::is_ascii_digit) {
+                let generation = self.word();
+                self.skip_whitespace();
+
+                if !generation.is_empty()
+                    && generation.iter().all(u8::is_ascii_digit)
+                    && self.data.get(self.pos) == Some(&b'R')
+                    && !matches!(
+                        self.data.get(self.pos + 1),
+                        Some(b) if !is_whitespace(*b) && !is_delimiter(*b)
+                    )
+                {
+                    self.pos += 1;
+                    return Some(Object::Ref(number as u32));
+                }
+            }
+        }
+
+        self.pos = after_number;
+        Some(Object::Number(number))
+    }
+
+    fn hex_string(&mut self) -> Option<Object> {
+        self.pos += 1;
+        let mut digits = Vec::new();
+
+        loop {
+            let b = *self.data.get(self.pos)?;
+            self.pos += 1;
+
+            match b {
+                b'>' => break,
+                b if b.is_ascii_hexdigit() => digits.push(b),
+                _ => {}
+            }
+        }
+
+        // An odd last digit is followed by an implicit 0
+        if digits.len() % 2 == 1 {
+            digits.push(b'0');
+        }
+
+        Some(Object::String(
+            digits.chunks(2).filter_map(hex_byte).collect(),
+        ))
+    }
+
+    fn literal_string(&mut self) -> Option<Object> {
+        self.pos += 1;
+        let mut value = Vec::new();
+        let mut depth = 0;
+
+        loop {
+            let b = *self.data.get(self.pos)?;
+            self.pos += 1;
+
+            match b {
+                b'(' => {
+                    depth += 1;
+                    value.push(b);
+                }
+                b')' if depth == 0 => break,
+                b')' => {
+                    depth -= 1;
+                    value.push(b);
+                }
+                b'\\' => {
+                    let escaped = *self.data.get(self.pos)?;
+                    self.pos += 1;
+
+                    match escaped {
+                        b'n' => value.push(b'\n'),
+                        b'r' => value.push(b'\r'),
+                        b't' => value.push(b'\t'),
+                        b'b' => value.push(0x08),
+                        b'f' => value.push(0x0c),
+                        // A backslash at the end of a line continues the string
+                        b'\r' => {
+                            if self.data.get(self.pos) == Some(&b'\n') {
+                                self.pos += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        b'0'..=b'7' => {
+                            let mut code = (escaped - b'0') as u32;
+                            for _ in 0..2 {
+                                match self.data.get(self.pos) {
+                                    Some(digit @ b'0'..=b'7') => {
+                                        code = code * 8 + (digit - b'0') as u32;
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            value.push(code as u8);
+                        }
+                        other => value.push(other),
+                    }
+                }
+                b => value.push(b),
+            }
+        }
+
+        Some(Object::String(value))
+    }
+}
+
+fn is_whitespace(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\r' | b'\n' | b'\x0c' | b'\0')
+}
+
+fn is_delimiter(b: u8) -> bool {
+    matches!(
+        b,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_objects() {
+        let mut lexer = Lexer::new(
+            b"<< /Type /Catalog /Kids [1 0 R -2.5 true null] /Name#20Sp (a\\(b\\)\\n\\101) \
+              /Hex <48 69 7> % comment\n>>\nstream\r\ndata",
+        );
+
+        let dict = match lexer.object() {
+            Some(Object::Dict(dict)) => dict,
+            other => panic!("{:?}", other),
+        };
+
+        assert_eq!(dict.get(b"Type"), Some(&Object::Name(b"Catalog".to_vec())));
+        assert_eq!(
+            dict.get(b"Kids"),
+            Some(&Object::Array(vec![
+                Object::Ref(1),
+                Object::Number(-2.5),
+                Object::Bool(true),
+                Object::Null,
+            ]))
+        );
+        assert_eq!(
+            dict.get(b"Name Sp"),
+            Some(&Object::String(b"a(b)\nA".to_vec()))
+        );
+        assert_eq!(dict.get(b"Hex"), Some(&Object::String(b"Hip".to_vec())));
+        assert_eq!(lexer.stream_start(), Some(lexer.pos() + 9));
+    }
+
+    #[test]
+    fn stops_at_truncated_or_damaged_objects() {
+        assert_eq!(Lexer::new(b"<< /Kids [1 2").object(), None);
+        assert_eq!(Lexer::new(b"(unterminated").object(), None);
+        assert_eq!(Lexer::new(b"<< (key) 1 >>").object(), None);
+        assert_eq!(Lexer::new(b"<48").object(), None);
+        assert_eq!(Lexer::new(&[b'['; 1000]).object(), None);
+        assert_eq!(Lexer::new(b"").object(), None);
+
+        // A number followed by a number is not a reference without `R`
+        let mut lexer = Lexer::new(b"12 0 obj");
+        assert_eq!(lexer.object(), Some(Object::Number(12.0)));
+        assert_eq!(lexer.object(), Some(Object::Number(0.0)));
+        assert_eq!(lexer.object(), Some(Object::Keyword(b"obj".to_vec())));
+    }
+
+    #[test]
+    fn reads_other_bytes_as_keywords() {
+        let mut lexer = Lexer::new(b"\x89PNG\r\n\x1a\n");
+
+        assert_eq!(lexer.object(), Some(Object::Keyword(b"\x89PNG".to_vec())));
+        assert_eq!(lexer.object(), Some(Object::Keyword(b"\x1a".to_vec())));
+        assert_eq!(lexer.object(), None);
+        assert_eq!(lexer.stream_start(), None);
+    }
+}
@@ -0,0 +1,99 @@
+use super::{iso_date, text, DocumentInfo};
+use roxmltree::{Document, Node};
+
+const DC: &str = "http://purl.org/dc/elements/1.1/";
+const XMP: &str = "http://ns.adobe.com/xap/1.0/";
+
+/// Reads the Dublin Core and XMP properties of an XMP packet
+///
+/// **NOTE:** A packet that is not well formed has no properties
+pub fn read(xml: &str) -> DocumentInfo {
+    // The packet is sometimes padded with a trailing `<?xpacket end?>` and NUL bytes
+    let xml = xml.trim_end_matches(|c: char| c.is_whitespace() || c == '\0');
+
+    let document = match Document::parse(xml) {
+        Ok(document) => document,
+        Err(_) => return DocumentInfo::default(),
+    };
+
+    let element = |namespace: &str, name: &str| {
+        document
+            .descendants()
+            .find(|node| node.has_tag_name((namespace, name)))
+    };
+
+    // Dates are written as elements or as attributes of `rdf:Description`
+    let date = |name: &str| {
+        element(XMP, name)
+            .and_then(|node| node.text())
+            .or_else(|| {
+                document
+                    .descendants()
+                    .find_map(|node| node.attribute((XMP, name)))
+            })
+            .and_then(iso_date)
+    };
+
+    DocumentInfo {
+        title: element(DC, "title").and_then(value),
+        author: element(DC, "creator").and_then(value),
+        subject: element(DC, "description").and_then(value),
+        language: element(DC, "language").and_then(value),
+        series: None,
+        modified_by: None,
+        created: date("CreateDate"),
+        modified: date("ModifyDate"),
+    }
+}
+
+/// Reads the first item of an RDF container, or the text of a plain element
+fn value(node: Node) -> Option<String> {
+    let item = node
+        .descendants()
+        .find(|child| child.tag_name().name() == "li");
+
+    text(item.unwrap_or(node).text()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:xmp="http://ns.adobe.com/xap/1.0/" xmp:CreateDate="2019-05-01T12:30:00+02:00">
+      <dc:title><rdf:Alt><rdf:li xml:lang="x-default">Report</rdf:li></rdf:Alt></dc:title>
+      <dc:creator><rdf:Seq><rdf:li>Ann</rdf:li><rdf:li>Bob</rdf:li></rdf:Seq></dc:creator>
+      <dc:language>en</dc:language>
+      <xmp:ModifyDate>2019-06-01</xmp:ModifyDate>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+    #[test]
+    fn reads_dublin_core_and_xmp_properties() {
+        let info = read(&format!("{}\0\0\0", PACKET));
+
+        assert_eq!(info.title.as_deref(), Some("Report"));
+        assert_eq!(info.author.as_deref(), Some("Ann"));
+        assert_eq!(info.language.as_deref(), Some("en"));
+        assert_eq!(info.created, iso_date("2019-05-01T12:30:00"));
+        assert_eq!(info.modified, iso_date("2019-06-01"));
+    }
+
+    #[test]
+    fn reads_nothing_from_truncated_packets() {
+        assert_eq!(read(&PACKET[..PACKET.len() / 2]), DocumentInfo::default());
+    }
+
+    #[test]
+    fn reads_nothing_from_other_xml() {
+        let info = read(r#"<svg xmlns="http://www.w3.org/2000/svg"><title>Logo</title></svg>"#);
+
+        assert_eq!(info, DocumentInfo::default());
+        assert_eq!(read("\u{89}PNG"), DocumentInfo::default());
+    }
+}
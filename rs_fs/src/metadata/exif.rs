@@ -0,0 +1,191 @@
+mod container;
+
+use container::Embedded;
+
+use crate::error_factory::create_error;
+use chrono::NaiveDateTime;
+use exif::{In, Reader, Tag, Value};
+use rs_response::DataResponse;
+use std::fs::File;
+use std::io::{self, BufReader, Cursor};
+use std::path::Path;
+
+const ERR_SRC: &str = "metadata::exif::read_exif()";
+
+/// The EXIF metadata of a photo
+///
+/// **NOTE:** Every property is `None` when the photo does not record it
+///
+/// # Properties:
+/// - `date_taken`: `Option<NaiveDateTime>` - When the photo was taken, in the camera's clock.
+///   Falls back to the digitized and modified dates
+/// - `make`: `Option<String>` - The camera maker
+/// - `model`: `Option<String>` - The camera model
+/// - `lens`: `Option<String>` - The lens model
+/// - `iso`: `Option<u32>` - The ISO speed
+/// - `exposure_time`: `Option<f64>` - The exposure time, in seconds
+/// - `f_number`: `Option<f64>` - The aperture, as an f-number
+/// - `focal_length`: `Option<f64>` - The focal length, in millimeters
+/// - `orientation`: `Option<u16>` - The EXIF orientation, from `1` to `8`
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct ExifData {
+    pub date_taken: Option<NaiveDateTime>,
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub lens: Option<String>,
+    pub iso: Option<u32>,
+    pub exposure_time: Option<f64>,
+    pub f_number: Option<f64>,
+    pub focal_length: Option<f64>,
+    pub orientation: Option<u16>,
+}
+
+/// Reads the EXIF metadata of a photo
+///
+/// Supports JPEG, TIFF, HEIF/HEIC, PNG, WebP and the common RAW formats:
+/// the TIFF based ones (DNG, CR2, NEF, ARW, PEF, ORF, RW2...), Fujifilm RAF
+/// and Canon CR3
+///
+/// **NOTE:** Returns `None` for files without EXIF metadata, including any
+/// file that is not a supported image. Damaged metadata is read as far as
+/// possible
+///
+/// # Arguments:
+/// - `path`: `&Path` - The file to read
+///
+/// # Example:
+/// ```
+/// use rs_fs::read_exif;
+/// use rs_response::DataResponse;
+/// use std::path::Path;
+///
+/// fn camera_model(path: &Path) -> DataResponse<Option<String>> {
+///   let exif = read_exif(path)?;
+///
+///   Ok(exif.and_then(|exif| exif.model))
+/// }
+/// ```
+pub fn read_exif(path: &Path) -> DataResponse<Option<ExifData>> {
+    let read_error = |e: io::Error| {
+        create_error(
+            format!("Unable to read the EXIF metadata of '{}'", path.display()),
+            e.to_string(),
+            ERR_SRC,
+        )
+    };
+
+    let file = File::open(path).map_err(read_error)?;
+    let mut reader = BufReader::new(file);
+
+    let mut exif_reader = Reader::new();
+    exif_reader.continue_on_error(true);
+
+    let embedded = match container::embedded_metadata(&mut reader).map_err(read_error)? {
+        Some(embedded) => embedded,
+        None => return Ok(parse(exif_reader.read_from_container(&mut reader))),
+    };
+
+    let mut data: Option<ExifData> = None;
+    for block in embedded {
+        let found = match block {
+            Embedded::Tiff(tiff) => parse(exif_reader.read_raw(tiff)),
+            Embedded::Jpeg(jpeg) => parse(exif_reader.read_from_container(&mut Cursor::new(jpeg))),
+        };
+
+        data = match (data, found) {
+            (Some(data), Some(found)) => Some(data.or(found)),
+            (data, found) => data.or(found),
+        };
+    }
+
+    Ok(data)
+}
+
+impl ExifData {
+    /// Fills every missing property from `other`
+    fn or(self, other: Self) -> Self {
+        Self {
+            date_taken: self.date_taken.or(other.date_taken),
+            make: self.make.or(other.make),
+            model: self.model.or(other.model),
+            lens: self.lens.or(other.lens),
+            iso: self.iso.or(other.iso),
+            exposure_time: self.exposure_time.or(other.exposure_time),
+            f_number: self.f_number.or(other.f_number),
+            focal_length: self.focal_length.or(other.focal_length),
+            orientation: self.orientation.or(other.orientation),
+        }
+    }
+}
+
+fn parse(result: Result<exif::Exif, exif::Error>) -> Option<ExifData> {
+    let exif = result
+        .or_else(|e| e.distill_partial_result(|_errors| {}))
+        .ok()?;
+
+    // Matched by number rather than by `Tag`, as the EXIF fields of a CR3 are
+    // stored in the first IFD of their own TIFF block
+    let field = |tag: Tag| {
+        exif.fields()
+            .find(|field| field.ifd_num == In::PRIMARY && field.tag.number() == tag.number())
+            .map(|field| &field.value)
+    };
+
+    let text = |tag: Tag| match field(tag) {
+        Some(Value::Ascii(values)) => values
+            .first()
+            .map(|value| String::from_utf8_lossy(value).trim().to_string())
+            .filter(|value| !value.is_empty()),
+        _ => None,
+    };
+
+    let rational = |tag: Tag| match field(tag) {
+        Some(Value::Rational(values)) => values
+            .first()
+            .filter(|value| value.denom != 0)
+            .map(|value| value.to_f64()),
+        _ => None,
+    };
+
+    let uint = |tag: Tag| field(tag).and_then(|value| value.get_uint(0));
+
+    let date = |tag: Tag| match field(tag) {
+        Some(Value::Ascii(values)) => values
+            .first()
+            .and_then(|value| exif::DateTime::from_ascii(value).ok())
+            .and_then(|date| {
+                chrono::NaiveDate::from_ymd_opt(
+                    date.year as i32,
+                    date.month as u32,
+                    date.day as u32,
+                )?
+                .and_hms_opt(
+                    date.hour as u32,
+                    date.minute as u32,
+                    date.second as u32,
+                )
+            }),
+        _ => None,
+    };
+
+    let data = ExifData {
+        date_taken: date(Tag::DateTimeOriginal)
+            .or_else(|| date(Tag::DateTimeDigitized))
+            .or_else(|| date(Tag::DateTime)),
+        make: text(Tag::Make),
+        model: text(Tag::Model),
+        lens: text(Tag::LensModel),
+        iso: uint(Tag::PhotographicSensitivity),
+        exposure_time: rational(Tag::ExposureTime),
+        f_number: rational(Tag::FNumber),
+        focal_length: rational(Tag::FocalLength),
+        orientation: uint(Tag::Orientation)
+            .filter(|orientation| (1..=8).contains(orientation))
+            .map(|orientation| orientation as u16),
+    };
+
+    match data == ExifData::default() {
+        true => None,
+        false => Some(data),
+    }
+}
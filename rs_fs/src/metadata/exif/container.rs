@@ -0,0 +1,352 @@
+use crate::metadata::{isobmff, read_up_to};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// The UUID of the box holding the TIFF metadata blocks of a Canon CR3
+const CANON_CR3_UUID: [u8; 16] = [
+    0x85, 0xc0, 0xb6, 0x87, 0x82, 0x0f, 0x11, 0xe0, 0x81, 0x11, 0xf4, 0xce, 0x46, 0x2b, 0x6a, 0x48,
+];
+
+/// The largest metadata block read from a CR3
+const MAX_BLOCK_SIZE: u64 = 16 * 1024 * 1024;
+
+/// How far into a TIFF file its metadata is read from. Values stored past
+/// it are skipped
+const MAX_TIFF_OFFSET: u64 = 4 * 1024 * 1024;
+
+/// The largest single value copied from a TIFF file
+const MAX_TIFF_VALUE: u64 = 64 * 1024;
+
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_MAKER_NOTE: u16 = 0x927c;
+
+/// Metadata found inside a RAW container
+pub enum Embedded {
+    /// A TIFF structure that holds EXIF fields in its first IFD
+    Tiff(Vec<u8>),
+    /// An embedded JPEG with its own EXIF segment
+    Jpeg(Vec<u8>),
+}
+
+/// Extracts the metadata of TIFF files, including the RAW formats based on
+/// TIFF, and of the RAW formats that are not TIFF files under the hood
+///
+/// **NOTE:** Only the metadata of TIFF files is read, never their image data
+///
+/// **NOTE:** Returns `None` for every other format, with the reader
+/// rewound to the start of the file
+pub fn embedded_metadata<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Vec<Embedded>>> {
+    let mut header = [0u8; 16];
+    let header_len = read_up_to(reader, &mut header)?;
+    let header = &header[..header_len];
+    reader.seek(SeekFrom::Start(0))?;
+
+    // Olympus ORF and Panasonic RW2 are TIFF files with a different magic number
+    let byte_order = match header.get(..4) {
+        Some(b"II*\0") | Some(b"IIRO") | Some(b"IIRS") | Some(b"IIU\0") => Some(ByteOrder::Little),
+        Some(b"MM\0*") | Some(b"MMOR") => Some(ByteOrder::Big),
+        _ => None,
+    };
+    if let Some(order) = byte_order {
+        let data = tiff_metadata(reader, order)?;
+
+        return Ok(Some(vec![Embedded::Tiff(data)]));
+    }
+
+    // Fujifilm RAF stores a full JPEG preview, offset and length at bytes 84 and 88
+    if header.starts_with(b"FUJIFILMCCD-RAW") {
+        let mut pointers = [0u8; 8];
+        reader.seek(SeekFrom::Start(84))?;
+        if read_up_to(reader, &mut pointers)? < pointers.len() {
+            // A truncated file, with no preview to read
+            return Ok(Some(Vec::new()));
+        }
+
+        let offset = u32::from_be_bytes([pointers[0], pointers[1], pointers[2], pointers[3]]);
+        let length = u32::from_be_bytes([pointers[4], pointers[5], pointers[6], pointers[7]]);
+
+        let mut jpeg = Vec::new();
+        reader.seek(SeekFrom::Start(offset as u64))?;
+        reader.take(length as u64).read_to_end(&mut jpeg)?;
+
+        return Ok(Some(vec![Embedded::Jpeg(jpeg)]));
+    }
+
+    // Canon CR3 is an ISO media file with TIFF blocks in a Canon box of 'moov'
+    if header.get(4..12) == Some(b"ftypcrx ") {
+        let mut blocks = Vec::new();
+
+        let moov = match isobmff::find_path(reader, &[b"moov"])? {
+            Some(moov) => moov,
+            None => return Ok(Some(blocks)),
+        };
+
+        for uuid in isobmff::children(reader, moov.start, moov.end)? {
+            if &uuid.kind != b"uuid" || uuid.len() < 16 {
+                continue;
+            }
+
+            let mut user_type = [0u8; 16];
+            reader.seek(SeekFrom::Start(uuid.start))?;
+            reader.read_exact(&mut user_type)?;
+
+            if user_type != CANON_CR3_UUID {
+                continue;
+            }
+
+            for kind in [b"CMT1", b"CMT2"] {
+                if let Some(cmt) = isobmff::find(reader, kind, uuid.start + 16, uuid.end)? {
+                    blocks.push(Embedded::Tiff(cmt.read(reader, MAX_BLOCK_SIZE)?));
+                }
+            }
+            break;
+        }
+
+        return Ok(Some(blocks));
+    }
+
+    Ok(None)
+}
+
+#[derive(Clone, Copy)]
+enum ByteOrder {
+    Little,
+    Big,
+}
+impl ByteOrder {
+    fn u16(self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        match self {
+            Self::Little => u16::from_le_bytes(bytes),
+            Self::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    fn u32(self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
+    }
+}
+
+/// Copies the header, the first IFD and the EXIF IFD of a TIFF file, along
+/// with their values, at their original offsets
+///
+/// **NOTE:** The result is a TIFF structure with the standard magic number,
+/// where everything that was not copied, such as the image data, is zeroed
+fn tiff_metadata<R: Read + Seek>(reader: &mut R, order: ByteOrder) -> io::Result<Vec<u8>> {
+    let mut data = vec![0u8; 8];
+    read_up_to(reader, &mut data)?;
+    data[2..4].copy_from_slice(match order {
+        ByteOrder::Little => &[0x2a, 0x00],
+        ByteOrder::Big => &[0x00, 0x2a],
+    });
+
+    let ifd0 = order.u32(&data[4..8]) as u64;
+    if let Some(exif_ifd) = copy_ifd(reader, order, ifd0, &mut data)? {
+        copy_ifd(reader, order, exif_ifd, &mut data)?;
+    }
+
+    Ok(data)
+}
+
+/// Copies an IFD and its values into `data`, and returns the offset of the
+/// EXIF IFD when the IFD points to one
+///
+/// **NOTE:** The offset of the next IFD is cleared, so thumbnails and
+/// previews are never read
+fn copy_ifd<R: Read + Seek>(
+    reader: &mut R,
+    order: ByteOrder,
+    offset: u64,
+    data: &mut Vec<u8>,
+) -> io::Result<Option<u64>> {
+    let count = match copy_region(reader, offset, 2, data)? {
+        true => order.u16(&data[offset as usize..]) as u64,
+        false => return Ok(None),
+    };
+
+    let entries_len = count * 12;
+    if !copy_region(reader, offset + 2, entries_len + 4, data)? {
+        return Ok(None);
+    }
+    let next = (offset + 2 + entries_len) as usize;
+    data[next..next + 4].fill(0);
+
+    let mut exif_ifd = None;
+    for index in 0..count {
+        let entry = (offset + 2 + index * 12) as usize;
+        let tag = order.u16(&data[entry..]);
+        let value_len = type_size(order.u16(&data[entry + 2..]))
+            .and_then(|size| size.checked_mul(order.u32(&data[entry + 4..]) as u64));
+        let value = order.u32(&data[entry + 8..]) as u64;
+
+        if tag == TAG_EXIF_IFD {
+            exif_ifd = Some(value);
+            continue;
+        }
+
+        match value_len {
+            Some(len) if len > 4 && len <= MAX_TIFF_VALUE && tag != TAG_MAKER_NOTE => {
+                copy_region(reader, value, len, data)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(exif_ifd)
+}
+
+/// Copies `len` bytes at `offset` from the file into `data`, growing it as
+/// needed. Returns `false` when the region is too far into the file, or past
+/// its end
+fn copy_region<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    len: u64,
+    data: &mut Vec<u8>,
+) -> io::Result<bool> {
+    let end = match offset
+        .checked_add(len)
+        .filter(|end| *end <= MAX_TIFF_OFFSET)
+    {
+        Some(end) => end as usize,
+        None => return Ok(false),
+    };
+
+    let mut region = vec![0u8; len as usize];
+    reader.seek(SeekFrom::Start(offset))?;
+    if read_up_to(reader, &mut region)? < region.len() {
+        return Ok(false);
+    }
+
+    if data.len() < end {
+        data.resize(end, 0);
+    }
+    data[offset as usize..end].copy_from_slice(&region);
+
+    Ok(true)
+}
+
+/// The size of a single value of a TIFF field type, in bytes
+fn type_size(field_type: u16) -> Option<u64> {
+    match field_type {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 | 13 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse;
+    use super::*;
+    use std::io::Cursor;
+
+    /// A little-endian TIFF with a camera maker, a model and an EXIF IFD
+    /// holding the date it was taken, followed by `padding` bytes of image data
+    fn tiff(magic: &[u8; 4], padding: usize) -> Vec<u8> {
+        let mut data = magic.to_vec();
+        data.extend(8u32.to_le_bytes());
+
+        // IFD0, from 8 to 50: Make at 50, an inline Model and the EXIF IFD at 56
+        data.extend(3u16.to_le_bytes());
+        for (tag, field_type, count, value) in [
+            (0x010fu16, 2u16, 6u32, 50u32),
+            (0x0110, 2, 4, u32::from_le_bytes(*b"EM1\0")),
+            (TAG_EXIF_IFD, 4, 1, 56),
+        ] {
+            data.extend(tag.to_le_bytes());
+            data.extend(field_type.to_le_bytes());
+            data.extend(count.to_le_bytes());
+            data.extend(value.to_le_bytes());
+        }
+        data.extend(0u32.to_le_bytes());
+        data.extend(b"Canon\0");
+
+        // EXIF IFD, from 56 to 74: DateTimeOriginal at 74
+        data.extend(1u16.to_le_bytes());
+        data.extend(0x9003u16.to_le_bytes());
+        data.extend(2u16.to_le_bytes());
+        data.extend(20u32.to_le_bytes());
+        data.extend(74u32.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend(b"2023:04:01 11:30:00\0");
+
+        data.extend(vec![0xffu8; padding]);
+        data
+    }
+
+    /// Reads a TIFF block like `read_exif` does
+    fn read(data: Vec<u8>) -> Option<super::super::ExifData> {
+        let mut reader = exif::Reader::new();
+        reader.continue_on_error(true);
+        parse(reader.read_raw(data))
+    }
+
+    fn tiff_block(file: Vec<u8>) -> Vec<u8> {
+        match embedded_metadata(&mut Cursor::new(file)).unwrap() {
+            Some(mut blocks) => match blocks.pop() {
+                Some(Embedded::Tiff(data)) => data,
+                _ => panic!("no TIFF block was found"),
+            },
+            None => panic!("the file was not recognized"),
+        }
+    }
+
+    #[test]
+    fn reads_the_metadata_of_an_orf_without_its_image_data() {
+        let data = tiff_block(tiff(b"IIRO", 1024 * 1024));
+
+        assert!(data.len() < 100);
+        let exif = read(data).unwrap();
+        assert_eq!(exif.make.as_deref(), Some("Canon"));
+        assert_eq!(exif.model.as_deref(), Some("EM1"));
+        assert_eq!(
+            exif.date_taken.map(|date| date.to_string()).as_deref(),
+            Some("2023-04-01 11:30:00")
+        );
+    }
+
+    #[test]
+    fn skips_regions_past_the_end_of_a_truncated_tiff() {
+        let mut file = tiff(b"II*\0", 0);
+        file.truncate(60);
+
+        let data = tiff_block(file);
+
+        let exif = read(data).unwrap();
+        assert_eq!(exif.make.as_deref(), Some("Canon"));
+        assert_eq!(exif.date_taken, None);
+    }
+
+    #[test]
+    fn ignores_hostile_offsets() {
+        let mut file = tiff(b"II*\0", 0);
+        file[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let data = tiff_block(file);
+
+        assert!(read(data).is_none());
+    }
+
+    #[test]
+    fn reads_nothing_from_a_truncated_raf() {
+        let mut file = b"FUJIFILMCCD-RAW 0201FF383501".to_vec();
+        file.resize(86, 0);
+
+        let blocks = embedded_metadata(&mut Cursor::new(file)).unwrap();
+
+        assert!(blocks.is_some_and(|blocks| blocks.is_empty()));
+    }
+
+    #[test]
+    fn leaves_other_formats_alone() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+
+        assert!(embedded_metadata(&mut Cursor::new(png)).unwrap().is_none());
+    }
+}
@@ -0,0 +1,476 @@
+mod tiff;
+
+use crate::error_factory::create_error;
+use crate::metadata::read_up_to;
+use rs_response::{DataResponse, ErrorRepsonse};
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const ERR_SRC: &str = "metadata::image::probe_image()";
+
+/// The largest metadata segment or chunk read, such as an EXIF block
+const MAX_CHUNK_SIZE: u64 = 1024 * 1024;
+
+/// The number of PNG chunks or WebP chunks looked through for EXIF metadata
+const MAX_CHUNKS: usize = 64;
+
+/// The properties of an image, read from its header
+///
+/// # Properties:
+/// - `width`: `u32` - The width in pixels, as stored
+/// - `height`: `u32` - The height in pixels, as stored
+/// - `bit_depth`: `Option<u16>` - The number of bits per pixel, e.g. `24`
+/// - `orientation`: `Option<u16>` - The EXIF orientation, from `1` to `8`
+///
+/// **NOTE:** The width and height are not swapped for orientations `5` to
+/// `8`, which are displayed rotated by a quarter turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    pub bit_depth: Option<u16>,
+    pub orientation: Option<u16>,
+}
+
+/// Reads the size, bit depth and orientation of an image from its header
+///
+/// Supports PNG, JPEG, GIF, WebP, BMP and TIFF. Only the header bytes are
+/// read, the image itself is never decoded
+///
+/// **NOTE:** Returns `None` for any other file. The orientation is read from
+/// the EXIF metadata of JPEG, PNG, WebP and TIFF images
+///
+/// # Arguments:
+/// - `path`: `&Path` - The file to read
+///
+/// # Example:
+/// ```
+/// use rs_fs::probe_image;
+/// use rs_response::DataResponse;
+/// use std::path::Path;
+///
+/// fn is_portrait(path: &Path) -> DataResponse<bool> {
+///   Ok(match probe_image(path)? {
+///     Some(image) => image.height > image.width,
+///     None => false,
+///   })
+/// }
+/// ```
+pub fn probe_image(path: &Path) -> DataResponse<Option<ImageInfo>> {
+    let file = File::open(path).map_err(|e| read_error(path, e))?;
+    let mut reader = BufReader::new(file);
+
+    probe(&mut reader).map_err(|e| read_error(path, e))
+}
+
+fn probe<R: Read + Seek>(reader: &mut R) -> io::Result<Option<ImageInfo>> {
+    let mut header = [0u8; 30];
+    let header_len = read_up_to(reader, &mut header)?;
+    let header = &header[..header_len];
+    reader.seek(SeekFrom::Start(0))?;
+
+    if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+        png(reader)
+    } else if header.starts_with(b"\xff\xd8") {
+        jpeg(reader)
+    } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+        Ok(gif(header))
+    } else if header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WEBP") {
+        webp(reader)
+    } else if header.starts_with(b"BM") {
+        Ok(bmp(header))
+    } else if header.starts_with(b"II*\0") || header.starts_with(b"MM\0*") {
+        Ok(tiff::read_ifd0(reader)?.and_then(|ifd| {
+            Some(ImageInfo {
+                width: ifd.width?,
+                height: ifd.height?,
+                bit_depth: ifd.bit_depth(),
+                orientation: ifd.orientation,
+            })
+        }))
+    } else {
+        Ok(None)
+    }
+}
+
+fn png<R: Read + Seek>(reader: &mut R) -> io::Result<Option<ImageInfo>> {
+    let mut ihdr = [0u8; 33];
+    if read_up_to(reader, &mut ihdr)? < 33 || &ihdr[12..16] != b"IHDR" {
+        return Ok(None);
+    }
+
+    let channels = match ihdr[25] {
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => 1,
+    };
+
+    let mut info = ImageInfo {
+        width: be_u32(&ihdr[16..20]),
+        height: be_u32(&ihdr[20..24]),
+        bit_depth: Some(ihdr[24] as u16 * channels),
+        orientation: None,
+    };
+
+    // The EXIF chunk, when there is one, comes before the image data
+    for _ in 0..MAX_CHUNKS {
+        let mut chunk = [0u8; 8];
+        if read_up_to(reader, &mut chunk)? < 8 {
+            break;
+        }
+
+        let len = be_u32(&chunk[..4]) as u64;
+        match &chunk[4..] {
+            b"eXIf" if len <= MAX_CHUNK_SIZE => {
+                info.orientation = exif_orientation(&read_len(reader, len)?);
+                break;
+            }
+            b"IDAT" | b"IEND" => break,
+            _ => {
+                // The chunk data, followed by its CRC
+                reader.seek(SeekFrom::Current(len as i64 + 4))?;
+            }
+        }
+    }
+
+    Ok(Some(info))
+}
+
+fn jpeg<R: Read + Seek>(reader: &mut R) -> io::Result<Option<ImageInfo>> {
+    reader.seek(SeekFrom::Start(2))?;
+    let mut orientation = None;
+
+    loop {
+        let mut marker = [0u8; 2];
+        if read_up_to(reader, &mut marker)? < 2 || marker[0] != 0xff {
+            return Ok(None);
+        }
+
+        // Fill bytes, and markers without a segment
+        match marker[1] {
+            0xff => {
+                reader.seek(SeekFrom::Current(-1))?;
+                continue;
+            }
+            0x01 | 0xd0..=0xd7 => continue,
+            // Start of scan, the image data follows without a frame header
+            0xd9 | 0xda => return Ok(None),
+            _ => {}
+        }
+
+        let mut len = [0u8; 2];
+        reader.read_exact(&mut len)?;
+        let len = u16::from_be_bytes(len).saturating_sub(2) as u64;
+
+        match marker[1] {
+            // Start of frame, except DHT, JPG and DAC which share the range
+            0xc0..=0xcf if !matches!(marker[1], 0xc4 | 0xc8 | 0xcc) => {
+                let mut frame = [0u8; 6];
+                reader.read_exact(&mut frame)?;
+
+                return Ok(Some(ImageInfo {
+                    width: u16::from_be_bytes([frame[3], frame[4]]) as u32,
+                    height: u16::from_be_bytes([frame[1], frame[2]]) as u32,
+                    bit_depth: Some(frame[0] as u16 * frame[5] as u16),
+                    orientation,
+                }));
+            }
+            0xe1 if orientation.is_none() => {
+                let segment = read_len(reader, len)?;
+                if let Some(tiff) = segment.strip_prefix(b"Exif\0\0") {
+                    orientation = exif_orientation(tiff);
+                }
+            }
+            _ => {
+                reader.seek(SeekFrom::Current(len as i64))?;
+            }
+        }
+    }
+}
+
+fn gif(header: &[u8]) -> Option<ImageInfo> {
+    let screen = header.get(6..11)?;
+
+    Some(ImageInfo {
+        width: u16::from_le_bytes([screen[0], screen[1]]) as u32,
+        height: u16::from_le_bytes([screen[2], screen[3]]) as u32,
+        bit_depth: Some((screen[4] & 0x07) as u16 + 1),
+        orientation: None,
+    })
+}
+
+fn webp<R: Read + Seek>(reader: &mut R) -> io::Result<Option<ImageInfo>> {
+    reader.seek(SeekFrom::Start(12))?;
+
+    let mut info: Option<ImageInfo> = None;
+    let mut has_exif = false;
+
+    for _ in 0..MAX_CHUNKS {
+        let mut chunk = [0u8; 8];
+        if read_up_to(reader, &mut chunk)? < 8 {
+            break;
+        }
+
+        let len = le_u32(&chunk[4..]) as u64;
+        // Chunks are padded to an even size
+        let padded = len + len % 2;
+
+        match &chunk[..4] {
+            b"VP8X" if len == 10 => {
+                let data = read_len(reader, padded)?;
+                has_exif = data[0] & 0x08 != 0;
+
+                info = Some(ImageInfo {
+                    width: le_u24(&data[4..7]) + 1,
+                    height: le_u24(&data[7..10]) + 1,
+                    bit_depth: Some(if data[0] & 0x10 != 0 { 32 } else { 24 }),
+                    orientation: None,
+                });
+            }
+            b"VP8 " if len >= 10 && info.is_none() => {
+                let mut data = [0u8; 10];
+                reader.read_exact(&mut data)?;
+                if data[3..6] != [0x9d, 0x01, 0x2a] {
+                    return Ok(None);
+                }
+
+                return Ok(Some(ImageInfo {
+                    width: (u16::from_le_bytes([data[6], data[7]]) & 0x3fff) as u32,
+                    height: (u16::from_le_bytes([data[8], data[9]]) & 0x3fff) as u32,
+                    bit_depth: Some(24),
+                    orientation: None,
+                }));
+            }
+            b"VP8L" if len >= 5 && info.is_none() => {
+                let mut data = [0u8; 5];
+                reader.read_exact(&mut data)?;
+                if data[0] != 0x2f {
+                    return Ok(None);
+                }
+
+                // 14 bits of width minus one, 14 bits of height minus one, then the alpha hint
+                let bits = le_u32(&data[1..5]);
+                return Ok(Some(ImageInfo {
+                    width: (bits & 0x3fff) + 1,
+                    height: ((bits >> 14) & 0x3fff) + 1,
+                    bit_depth: Some(if bits & (1 << 28) != 0 { 32 } else { 24 }),
+                    orientation: None,
+                }));
+            }
+            b"EXIF" if len <= MAX_CHUNK_SIZE => {
+                let data = read_len(reader, padded)?;
+                let tiff = data.strip_prefix(b"Exif\0\0").unwrap_or(&data);
+
+                if let Some(info) = info.as_mut() {
+                    info.orientation = exif_orientation(tiff);
+                }
+                break;
+            }
+            _ => {
+                reader.seek(SeekFrom::Current(padded as i64))?;
+            }
+        }
+
+        if info.is_some() && !has_exif {
+            break;
+        }
+    }
+
+    Ok(info)
+}
+
+fn bmp(header: &[u8]) -> Option<ImageInfo> {
+    let dib = header.get(14..)?;
+    let dib_len = le_u32(dib.get(..4)?);
+
+    // The original OS/2 header stores 16 bit sizes
+    let (width, height, bit_depth) = match dib_len {
+        12 => (
+            u16::from_le_bytes([*dib.get(4)?, *dib.get(5)?]) as u32,
+            u16::from_le_bytes([*dib.get(6)?, *dib.get(7)?]) as u32,
+            u16::from_le_bytes([*dib.get(10)?, *dib.get(11)?]),
+        ),
+        _ => (
+            (le_u32(dib.get(4..8)?) as i32).unsigned_abs(),
+            // A negative height marks a top-down bitmap
+            (le_u32(dib.get(8..12)?) as i32).unsigned_abs(),
+            u16::from_le_bytes([*dib.get(14)?, *dib.get(15)?]),
+        ),
+    };
+
+    Some(ImageInfo {
+        width,
+        height,
+        bit_depth: Some(bit_depth).filter(|depth| *depth > 0),
+        orientation: None,
+    })
+}
+
+/// Reads the orientation of an EXIF block, which is a TIFF structure
+///
+/// **NOTE:** Damaged metadata has no orientation, rather than failing the probe
+fn exif_orientation(tiff: &[u8]) -> Option<u16> {
+    tiff::read_ifd0(&mut Cursor::new(tiff))
+        .ok()
+        .flatten()
+        .and_then(|ifd| ifd.orientation)
+}
+
+fn read_len<R: Read>(reader: &mut R, len: u64) -> io::Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(len.min(MAX_CHUNK_SIZE) as usize);
+    reader.take(len).read_to_end(&mut data)?;
+
+    match data.len() as u64 == len {
+        true => Ok(data),
+        false => Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+    }
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn le_u24(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+}
+
+fn read_error(path: &Path, e: io::Error) -> ErrorRepsonse {
+    create_error(
+        format!("Unable to read the image header of '{}'", path.display()),
+        e.to_string(),
+        ERR_SRC,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A big endian EXIF block with an orientation of `6`
+    const EXIF: &[u8] = b"MM\0*\0\0\0\x08\0\x01\x01\x12\0\x03\0\0\0\x01\0\x06\0\0\0\0\0\0";
+
+    fn probe_bytes(data: Vec<u8>) -> io::Result<Option<ImageInfo>> {
+        probe(&mut Cursor::new(data))
+    }
+
+    fn png_chunk(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = (payload.len() as u32).to_be_bytes().to_vec();
+        data.extend(kind);
+        data.extend(payload);
+        data.extend([0u8; 4]);
+        data
+    }
+
+    fn png() -> Vec<u8> {
+        let mut ihdr = 640u32.to_be_bytes().to_vec();
+        ihdr.extend(480u32.to_be_bytes());
+        ihdr.extend([8, 6, 0, 0, 0]);
+
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+        data.extend(png_chunk(b"IHDR", &ihdr));
+        data.extend(png_chunk(b"tEXt", b"Comment\0text"));
+        data.extend(png_chunk(b"eXIf", EXIF));
+        data.extend(png_chunk(b"IEND", b""));
+        data
+    }
+
+    fn jpeg() -> Vec<u8> {
+        let mut data = b"\xff\xd8\xff\xe1".to_vec();
+        data.extend(((EXIF.len() + 8) as u16).to_be_bytes());
+        data.extend(b"Exif\0\0");
+        data.extend(EXIF);
+        data.extend(b"\xff\xff\xc0\0\x11\x08");
+        data.extend(480u16.to_be_bytes());
+        data.extend(640u16.to_be_bytes());
+        data.extend([3u8; 10]);
+        data
+    }
+
+    fn webp(chunk: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = b"RIFF".to_vec();
+        data.extend(((payload.len() + 12) as u32).to_le_bytes());
+        data.extend(b"WEBP");
+        data.extend(chunk);
+        data.extend((payload.len() as u32).to_le_bytes());
+        data.extend(payload);
+        data
+    }
+
+    fn bmp(bit_depth: u16) -> Vec<u8> {
+        let mut data = b"BM".to_vec();
+        data.extend([0u8; 12]);
+        data.extend(40u32.to_le_bytes());
+        data.extend(640i32.to_le_bytes());
+        data.extend((-480i32).to_le_bytes());
+        data.extend(1u16.to_le_bytes());
+        data.extend(bit_depth.to_le_bytes());
+        data.extend([0u8; 24]);
+        data
+    }
+
+    fn info(bit_depth: u16, orientation: Option<u16>) -> Option<ImageInfo> {
+        Some(ImageInfo {
+            width: 640,
+            height: 480,
+            bit_depth: Some(bit_depth),
+            orientation,
+        })
+    }
+
+    #[test]
+    fn reads_image_headers() {
+        assert_eq!(probe_bytes(png()).unwrap(), info(32, Some(6)));
+        assert_eq!(probe_bytes(jpeg()).unwrap(), info(24, Some(6)));
+        assert_eq!(probe_bytes(bmp(24)).unwrap(), info(24, None));
+
+        let bits = 639 | (479 << 14) | (1 << 28);
+        let mut vp8l = vec![0x2f];
+        vp8l.extend((bits as u32).to_le_bytes());
+        assert_eq!(probe_bytes(webp(b"VP8L", &vp8l)).unwrap(), info(32, None));
+
+        let mut vp8 = vec![0, 0, 0, 0x9d, 0x01, 0x2a];
+        vp8.extend(640u16.to_le_bytes());
+        vp8.extend(480u16.to_le_bytes());
+        assert_eq!(probe_bytes(webp(b"VP8 ", &vp8)).unwrap(), info(24, None));
+    }
+
+    #[test]
+    fn refuses_truncated_or_damaged_headers() {
+        let mut data = png();
+        data.truncate(30);
+        assert_eq!(probe_bytes(data).unwrap(), None);
+
+        let mut data = jpeg();
+        data.truncate(data.len() - 12);
+        assert!(probe_bytes(data).is_err());
+
+        let mut data = webp(b"VP8L", &[0x2f, 0, 0, 0, 0]);
+        data.truncate(22);
+        assert!(probe_bytes(data).is_err());
+
+        assert_eq!(probe_bytes(bmp(24)[..20].to_vec()).unwrap(), None);
+        assert_eq!(probe_bytes(bmp(0)).unwrap().unwrap().bit_depth, None);
+
+        // A damaged EXIF block has no orientation
+        let mut data = png();
+        let exif = data.windows(4).position(|w| w == b"MM\0*").unwrap();
+        data[exif + 7] = 0xff;
+        assert_eq!(probe_bytes(data).unwrap(), info(32, None));
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        assert_eq!(
+            probe_bytes(b"%PDF-1.7\n%\xe2\xe3\xcf\xd3".to_vec()).unwrap(),
+            None
+        );
+        assert_eq!(probe_bytes(b"RIFF\0\0\0\0WAVEfmt ".to_vec()).unwrap(), None);
+        assert_eq!(probe_bytes(Vec::new()).unwrap(), None);
+    }
+}
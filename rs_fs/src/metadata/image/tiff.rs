@@ -0,0 +1,155 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// The number of entries read from an IFD, to bound damaged files
+const MAX_ENTRIES: u16 = 1024;
+
+/// The largest number of bits per pixel kept, as larger ones come from
+/// damaged files
+const MAX_BIT_DEPTH: u32 = 1024;
+
+const IMAGE_WIDTH: u16 = 0x0100;
+const IMAGE_LENGTH: u16 = 0x0101;
+const BITS_PER_SAMPLE: u16 = 0x0102;
+const ORIENTATION: u16 = 0x0112;
+const SAMPLES_PER_PIXEL: u16 = 0x0115;
+
+/// The fields of the first IFD of a TIFF structure that describe the image
+#[derive(Debug, Default)]
+pub struct Ifd0 {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub bits_per_sample: Option<u16>,
+    pub samples_per_pixel: Option<u16>,
+    pub orientation: Option<u16>,
+}
+impl Ifd0 {
+    /// The number of bits per pixel
+    ///
+    /// **NOTE:** `None` when it is `0`, or larger than any real image
+    pub fn bit_depth(&self) -> Option<u16> {
+        let bits = self.bits_per_sample? as u32 * self.samples_per_pixel.unwrap_or(1) as u32;
+
+        match (1..=MAX_BIT_DEPTH).contains(&bits) {
+            true => Some(bits as u16),
+            false => None,
+        }
+    }
+}
+
+/// Reads the first IFD of a TIFF structure starting at the reader's start
+///
+/// **NOTE:** Only the IFD entries are read, wherever they are in the file
+pub fn read_ifd0<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Ifd0>> {
+    let mut header = [0u8; 8];
+    reader.seek(SeekFrom::Start(0))?;
+    reader.read_exact(&mut header)?;
+
+    let big_endian = match &header[..4] {
+        b"II*\0" => false,
+        b"MM\0*" => true,
+        _ => return Ok(None),
+    };
+    let u16_at = |bytes: &[u8]| match big_endian {
+        true => u16::from_be_bytes([bytes[0], bytes[1]]),
+        false => u16::from_le_bytes([bytes[0], bytes[1]]),
+    };
+    let u32_at = |bytes: &[u8]| match big_endian {
+        true => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        false => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    };
+
+    reader.seek(SeekFrom::Start(u32_at(&header[4..]) as u64))?;
+    let mut count = [0u8; 2];
+    reader.read_exact(&mut count)?;
+
+    let mut ifd = Ifd0::default();
+    for _ in 0..u16_at(&count).min(MAX_ENTRIES) {
+        let mut entry = [0u8; 12];
+        reader.read_exact(&mut entry)?;
+
+        // SHORT values are stored first in the value field, LONG ones fill it
+        let value = match u16_at(&entry[2..4]) {
+            3 => u16_at(&entry[8..10]) as u32,
+            4 => u32_at(&entry[8..12]),
+            _ => continue,
+        };
+        let count = u32_at(&entry[4..8]);
+
+        match u16_at(&entry[..2]) {
+            IMAGE_WIDTH => ifd.width = Some(value),
+            IMAGE_LENGTH => ifd.height = Some(value),
+            // More than two SHORT values are stored elsewhere, but all samples
+            // of an image usually have the same size
+            BITS_PER_SAMPLE if count <= 2 => ifd.bits_per_sample = Some(value as u16),
+            BITS_PER_SAMPLE => ifd.bits_per_sample = Some(8),
+            SAMPLES_PER_PIXEL => ifd.samples_per_pixel = Some(value as u16),
+            ORIENTATION if (1..=8).contains(&value) => ifd.orientation = Some(value as u16),
+            _ => {}
+        }
+    }
+
+    Ok(Some(ifd))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A little endian TIFF header followed by IFD0, with SHORT entries
+    fn tiff(entries: &[(u16, u16)]) -> Vec<u8> {
+        let mut data = b"II*\0".to_vec();
+        data.extend(8u32.to_le_bytes());
+        data.extend((entries.len() as u16).to_le_bytes());
+        for (tag, value) in entries {
+            data.extend(tag.to_le_bytes());
+            data.extend(3u16.to_le_bytes());
+            data.extend(1u32.to_le_bytes());
+            data.extend(value.to_le_bytes());
+            data.extend([0, 0]);
+        }
+        data.extend(0u32.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn reads_ifd0() {
+        let data = tiff(&[
+            (IMAGE_WIDTH, 640),
+            (IMAGE_LENGTH, 480),
+            (BITS_PER_SAMPLE, 8),
+            (SAMPLES_PER_PIXEL, 3),
+            (ORIENTATION, 6),
+        ]);
+
+        let ifd = read_ifd0(&mut Cursor::new(data)).unwrap().unwrap();
+
+        assert_eq!(ifd.width, Some(640));
+        assert_eq!(ifd.height, Some(480));
+        assert_eq!(ifd.bit_depth(), Some(24));
+        assert_eq!(ifd.orientation, Some(6));
+    }
+
+    #[test]
+    fn refuses_damaged_values() {
+        let data = tiff(&[
+            (BITS_PER_SAMPLE, u16::MAX),
+            (SAMPLES_PER_PIXEL, u16::MAX),
+            (ORIENTATION, 9),
+        ]);
+        let ifd = read_ifd0(&mut Cursor::new(data)).unwrap().unwrap();
+        assert_eq!(ifd.bit_depth(), None);
+        assert_eq!(ifd.orientation, None);
+
+        let mut data = tiff(&[(IMAGE_WIDTH, 640), (IMAGE_LENGTH, 480)]);
+        data.truncate(20);
+        assert!(read_ifd0(&mut Cursor::new(data)).is_err());
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+
+        assert!(read_ifd0(&mut Cursor::new(png)).unwrap().is_none());
+    }
+}
@@ -0,0 +1,226 @@
+use super::read_up_to;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// A box of an ISO base media file, such as MP4, MOV, HEIC or CR3
+///
+/// # Properties:
+/// - `kind`: `[u8; 4]` - The box type, such as `moov`
+/// - `start`: `u64` - The offset of the box payload, after its header
+/// - `end`: `u64` - The offset right after the box
+#[derive(Debug, Clone, Copy)]
+pub struct IsoBox {
+    pub kind: [u8; 4],
+    pub start: u64,
+    pub end: u64,
+}
+impl IsoBox {
+    /// The size of the payload, in bytes
+    pub fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// Reads the whole payload
+    ///
+    /// **NOTE:** Refuses payloads larger than `limit` bytes
+    pub fn read<R: Read + Seek>(&self, reader: &mut R, limit: u64) -> io::Result<Vec<u8>> {
+        if self.len() > limit {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "The '{}' box is too large",
+                    String::from_utf8_lossy(&self.kind)
+                ),
+            ));
+        }
+
+        let mut data = Vec::with_capacity(self.len() as usize);
+        reader.seek(SeekFrom::Start(self.start))?;
+        reader.take(self.len()).read_to_end(&mut data)?;
+
+        Ok(data)
+    }
+}
+
+/// Lists the boxes found between two offsets
+///
+/// **NOTE:** Use `0` and the file size for the top level boxes, or the
+/// `start` and `end` of a box for its children. Listing stops at the first
+/// malformed box
+pub fn children<R: Read + Seek>(reader: &mut R, start: u64, end: u64) -> io::Result<Vec<IsoBox>> {
+    let mut boxes = Vec::new();
+    let mut pos = start;
+
+    while pos
+        .checked_add(8)
+        .is_some_and(|header_end| header_end <= end)
+    {
+        let mut header = [0u8; 8];
+        reader.seek(SeekFrom::Start(pos))?;
+        if read_up_to(reader, &mut header)? < header.len() {
+            break;
+        }
+
+        let mut header_len = 8;
+        let size = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+            0 => end - pos,
+            1 => {
+                let mut large = [0u8; 8];
+                if read_up_to(reader, &mut large)? < large.len() {
+                    break;
+                }
+                header_len = 16;
+                u64::from_be_bytes(large)
+            }
+            size => size as u64,
+        };
+
+        // Sizes come from the file, so they are never trusted to fit
+        let box_end = match pos.checked_add(size).filter(|box_end| *box_end <= end) {
+            Some(box_end) if size >= header_len => box_end,
+            _ => break,
+        };
+
+        boxes.push(IsoBox {
+            kind: [header[4], header[5], header[6], header[7]],
+            start: pos + header_len,
+            end: box_end,
+        });
+
+        pos = box_end;
+    }
+
+    Ok(boxes)
+}
+
+/// Finds the first child box of a type between two offsets
+pub fn find<R: Read + Seek>(
+    reader: &mut R,
+    kind: &[u8; 4],
+    start: u64,
+    end: u64,
+) -> io::Result<Option<IsoBox>> {
+    Ok(children(reader, start, end)?
+        .into_iter()
+        .find(|child| &child.kind == kind))
+}
+
+/// Follows a path of box types from the top level, such as `moov/udta/meta`
+///
+/// **NOTE:** `meta` boxes are full boxes, with 4 bytes of version and flags
+/// before their children. Those are skipped when the path goes through them
+pub fn find_path<R: Read + Seek>(reader: &mut R, path: &[&[u8; 4]]) -> io::Result<Option<IsoBox>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+
+    follow(reader, path, 0, end)
+}
+
+/// Follows a path of box types from a box, such as `mdia/hdlr` from a `trak`
+pub fn find_path_in<R: Read + Seek>(
+    reader: &mut R,
+    parent: &IsoBox,
+    path: &[&[u8; 4]],
+) -> io::Result<Option<IsoBox>> {
+    follow(reader, path, parent.start, parent.end)
+}
+
+fn follow<R: Read + Seek>(
+    reader: &mut R,
+    path: &[&[u8; 4]],
+    mut start: u64,
+    mut end: u64,
+) -> io::Result<Option<IsoBox>> {
+    let mut found = None;
+
+    for kind in path {
+        let child = match find(reader, kind, start, end)? {
+            Some(child) => child,
+            None => return Ok(None),
+        };
+
+        start = match &child.kind {
+            b"meta" => child.start + 4,
+            _ => child.start,
+        };
+        end = child.end;
+        found = Some(child);
+    }
+
+    Ok(found)
+}
+
+/// Whether a file starts with an `ftyp` box
+pub fn is_isobmff(header: &[u8]) -> bool {
+    header.get(4..8) == Some(b"ftyp")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn iso_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend(kind);
+        data.extend(payload);
+        data
+    }
+
+    fn sample() -> Vec<u8> {
+        let mut meta = vec![0u8; 4];
+        meta.extend(iso_box(b"ilst", b"tags"));
+        let udta = iso_box(b"udta", &iso_box(b"meta", &meta));
+
+        let mut data = iso_box(b"ftyp", b"isom\0\0\0\0");
+        data.extend(iso_box(b"moov", &udta));
+        data
+    }
+
+    #[test]
+    fn follows_paths_through_meta_boxes() {
+        let mut reader = Cursor::new(sample());
+
+        let ilst = find_path(&mut reader, &[b"moov", b"udta", b"meta", b"ilst"])
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(ilst.read(&mut reader, 1024).unwrap(), b"tags");
+        assert!(is_isobmff(&sample()));
+    }
+
+    #[test]
+    fn stops_at_truncated_boxes() {
+        let mut data = sample();
+        data.truncate(data.len() - 2);
+        let end = data.len() as u64;
+
+        let boxes = children(&mut Cursor::new(data), 0, end).unwrap();
+
+        assert_eq!(boxes.len(), 1);
+        assert_eq!(&boxes[0].kind, b"ftyp");
+    }
+
+    #[test]
+    fn stops_at_hostile_large_sizes() {
+        let mut data = sample();
+        data.extend(1u32.to_be_bytes());
+        data.extend(b"mdat");
+        data.extend(u64::MAX.to_be_bytes());
+        data.extend(b"payload");
+        let end = data.len() as u64;
+
+        let boxes = children(&mut Cursor::new(data), 0, end).unwrap();
+
+        assert_eq!(boxes.len(), 2);
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        let data = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        let end = data.len() as u64;
+
+        assert!(!is_isobmff(&data));
+        assert!(find(&mut Cursor::new(data), b"moov", 0, end)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,185 @@
+mod flac;
+mod mp3;
+mod mp4;
+mod wav;
+
+use crate::error_factory::create_error;
+use crate::metadata::{isobmff, read_up_to};
+use rs_response::{DataResponse, ErrorRepsonse};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const ERR_SRC: &str = "metadata::media::read_media_info()";
+
+/// The technical properties of an audio or video file
+///
+/// **NOTE:** Every property is `None` when it does not apply to the file, or
+/// cannot be read from its headers
+///
+/// # Properties:
+/// - `duration`: `Option<f64>` - The duration, in seconds
+/// - `bitrate`: `Option<u32>` - The average bitrate, in kilobits per second
+/// - `sample_rate`: `Option<u32>` - The audio sample rate, in Hz
+/// - `channels`: `Option<u16>` - The number of audio channels
+/// - `width`: `Option<u32>` - The width of the video, in pixels
+/// - `height`: `Option<u32>` - The height of the video, in pixels
+/// - `frame_rate`: `Option<f64>` - The average number of frames per second of the video
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct MediaInfo {
+    pub duration: Option<f64>,
+    pub bitrate: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+}
+
+/// Reads the technical properties of an audio or video file from its headers
+///
+/// Supports WAV, FLAC and MP3 (duration, bitrate, sample rate and channels)
+/// and MP4/MOV (duration from the `mvhd` box, resolution from the `tkhd` box
+/// of the video track, and frame rate from its sample table)
+///
+/// **NOTE:** Returns `None` for any other file. Files are only read as MP3
+/// when they have an ID3 tag or start with two frames in a row, and the
+/// duration of a VBR MP3 without a Xing or VBRI header is estimated from its
+/// first frame
+///
+/// # Arguments:
+/// - `path`: `&Path` - The file to read
+///
+/// # Example:
+/// ```
+/// use rs_fs::read_media_info;
+/// use rs_response::DataResponse;
+/// use std::path::Path;
+///
+/// fn is_full_hd(path: &Path) -> DataResponse<bool> {
+///   let info = read_media_info(path)?.unwrap_or_default();
+///
+///   Ok(info.width == Some(1920) && info.height == Some(1080))
+/// }
+/// ```
+pub fn read_media_info(path: &Path) -> DataResponse<Option<MediaInfo>> {
+    let file = File::open(path).map_err(|e| read_error(path, e))?;
+    let mut reader = BufReader::new(file);
+
+    read_info(&mut reader).map_err(|e| read_error(path, e))
+}
+
+fn read_info<R: Read + Seek>(reader: &mut R) -> io::Result<Option<MediaInfo>> {
+    let file_len = reader.seek(SeekFrom::End(0))?;
+    let start = id3v2_end(reader)?;
+
+    let mut header = [0u8; 12];
+    reader.seek(SeekFrom::Start(start))?;
+    let header_len = read_up_to(reader, &mut header)?;
+    let header = &header[..header_len];
+    reader.seek(SeekFrom::Start(start))?;
+
+    let info = if header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WAVE") {
+        wav::read(reader)?
+    } else if header.starts_with(b"fLaC") {
+        flac::read(reader, file_len)?
+    } else if isobmff::is_isobmff(header) || is_quicktime(header) {
+        mp4::read(reader, file_len)?
+    } else {
+        mp3::read(reader, start, file_len)?
+    };
+
+    Ok(info.and_then(MediaInfo::found))
+}
+
+impl MediaInfo {
+    /// `None` when nothing was found
+    fn found(self) -> Option<Self> {
+        match self == Self::default() {
+            true => None,
+            false => Some(self),
+        }
+    }
+}
+
+/// The offset right after the ID3v2 tag at the start of a file, or `0`
+fn id3v2_end<R: Read + Seek>(reader: &mut R) -> io::Result<u64> {
+    let mut header = [0u8; 10];
+    reader.seek(SeekFrom::Start(0))?;
+
+    if read_up_to(reader, &mut header)? < 10 || !header.starts_with(b"ID3") {
+        return Ok(0);
+    }
+
+    let size = header[6..10]
+        .iter()
+        .fold(0u64, |size, b| (size << 7) | (*b as u64 & 0x7f));
+    let footer = match header[3] == 4 && header[5] & 0x10 != 0 {
+        true => 10,
+        false => 0,
+    };
+
+    Ok(10 + size + footer)
+}
+
+/// Whether a file is an older QuickTime movie, without an `ftyp` box
+fn is_quicktime(header: &[u8]) -> bool {
+    matches!(
+        header.get(4..8),
+        Some(b"moov") | Some(b"mdat") | Some(b"wide") | Some(b"free")
+    )
+}
+
+/// The average bitrate of `bytes` played over `duration` seconds
+fn bitrate(bytes: u64, duration: f64) -> Option<u32> {
+    match duration > 0.0 {
+        true => Some((bytes as f64 * 8.0 / duration / 1000.0).round() as u32),
+        false => None,
+    }
+}
+
+fn read_error(path: &Path, e: io::Error) -> ErrorRepsonse {
+    create_error(
+        format!(
+            "Unable to read the media properties of '{}'",
+            path.display()
+        ),
+        e.to_string(),
+        ERR_SRC,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_tagged_mp3_files() {
+        let mut data = b"ID3\x04\0\0\0\0\0\x02\0\0".to_vec();
+        let mut frame = vec![0u8; 417];
+        frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0x64]);
+        data.extend(frame.repeat(2));
+
+        let info = read_info(&mut Cursor::new(data)).unwrap().unwrap();
+
+        assert_eq!(info.sample_rate, Some(44_100));
+    }
+
+    #[test]
+    fn reads_nothing_from_truncated_files() {
+        let data = b"ID3\x04\0\0\x7f\x7f\x7f\x7f".to_vec();
+
+        assert!(read_info(&mut Cursor::new(data)).unwrap().is_none());
+    }
+
+    #[test]
+    fn reads_nothing_from_other_files() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend([0xff, 0xfb, 0x90, 0x64]);
+        png.extend([0u8; 2000]);
+
+        assert!(read_info(&mut Cursor::new(png)).unwrap().is_none());
+        assert!(read_info(&mut Cursor::new(Vec::new())).unwrap().is_none());
+    }
+}
@@ -0,0 +1,111 @@
+use super::{bitrate, MediaInfo};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Reads the `STREAMINFO` block of a FLAC file
+///
+/// **NOTE:** The reader must be at the `fLaC` marker. The bitrate is
+/// averaged over the audio frames, after the metadata blocks
+pub fn read<R: Read + Seek>(reader: &mut R, file_len: u64) -> io::Result<Option<MediaInfo>> {
+    reader.seek(SeekFrom::Current(4))?;
+
+    let mut header = [0u8; 4];
+    reader.read_exact(&mut header)?;
+
+    // STREAMINFO is always the first block
+    if header[0] & 0x7f != 0 {
+        return Ok(None);
+    }
+
+    let mut info = [0u8; 34];
+    reader.read_exact(&mut info)?;
+
+    // 20 bits of sample rate, 3 bits of channels minus one, 5 bits of bits
+    // per sample minus one, then 36 bits of total samples
+    let sample_rate = (info[10] as u32) << 12 | (info[11] as u32) << 4 | (info[12] as u32) >> 4;
+    let channels = ((info[12] >> 1) & 0x07) as u16 + 1;
+    let samples = ((info[13] & 0x0f) as u64) << 32
+        | u32::from_be_bytes([info[14], info[15], info[16], info[17]]) as u64;
+
+    // An encoder that could not seek back leaves the stream info empty
+    if sample_rate == 0 {
+        return Ok(None);
+    }
+
+    let duration = match samples > 0 {
+        true => Some(samples as f64 / sample_rate as f64),
+        false => None,
+    };
+
+    let mut last = header[0] & 0x80 != 0;
+    while !last {
+        reader.read_exact(&mut header)?;
+        last = header[0] & 0x80 != 0;
+
+        let size = u32::from_be_bytes([0, header[1], header[2], header[3]]);
+        reader.seek(SeekFrom::Current(size as i64))?;
+    }
+    let audio_len = file_len.saturating_sub(reader.stream_position()?);
+
+    Ok(Some(MediaInfo {
+        duration,
+        bitrate: duration.and_then(|duration| bitrate(audio_len, duration)),
+        sample_rate: Some(sample_rate),
+        channels: Some(channels),
+        ..MediaInfo::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A `STREAMINFO` block of 10 seconds of stereo at 44.1 kHz
+    fn stream_info(last: bool) -> Vec<u8> {
+        let mut data = b"fLaC".to_vec();
+        data.extend([if last { 0x80 } else { 0 }, 0, 0, 34]);
+
+        let mut info = [0u8; 34];
+        let packed = (44_100u64 << 44) | (1 << 41) | (15 << 36) | 441_000;
+        info[10..18].copy_from_slice(&packed.to_be_bytes());
+        data.extend(info);
+        data
+    }
+
+    #[test]
+    fn reads_stream_info() {
+        let mut data = stream_info(false);
+        data.extend([0x81, 0, 0, 4]);
+        data.extend(b"pad!");
+        data.extend([0u8; 1000]);
+        let len = data.len() as u64;
+
+        let info = read(&mut Cursor::new(data), len).unwrap().unwrap();
+
+        assert_eq!(info.duration, Some(10.0));
+        assert_eq!(info.bitrate, Some(1));
+        assert_eq!(info.sample_rate, Some(44_100));
+        assert_eq!(info.channels, Some(2));
+    }
+
+    #[test]
+    fn fails_on_truncated_blocks() {
+        let mut data = stream_info(true);
+        data.truncate(20);
+
+        assert!(read(&mut Cursor::new(data), 20).is_err());
+
+        // The stream info left empty by an encoder that could not seek back
+        let mut data = b"fLaC\x80\0\0\x22".to_vec();
+        data.extend([0u8; 34]);
+        assert!(read(&mut Cursor::new(data), 42).unwrap().is_none());
+    }
+
+    #[test]
+    fn finds_nothing_in_other_blocks() {
+        let mut data = b"fLaC\x84\0\0\x04".to_vec();
+        data.extend(b"tags");
+
+        assert!(read(&mut Cursor::new(data), 12).unwrap().is_none());
+    }
+}
@@ -0,0 +1,272 @@
+use super::{bitrate, MediaInfo};
+use crate::metadata::read_up_to;
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// How far after the ID3v2 tag the first frame is looked for
+const MAX_SYNC_SEARCH: usize = 64 * 1024;
+
+/// Bitrates in kbit/s by index: MPEG-1 layers I, II and III, then MPEG-2
+/// and 2.5 layer I, and layers II and III
+const BITRATES: [[u32; 15]; 5] = [
+    [
+        0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+    ],
+    [
+        0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+    ],
+    [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ],
+    [
+        0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+    ],
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+];
+
+/// The header of an MPEG audio frame
+struct Frame {
+    mpeg1: bool,
+    layer: u8,
+    bitrate: u32,
+    sample_rate: u32,
+    channels: u16,
+    len: usize,
+}
+impl Frame {
+    fn parse(header: &[u8]) -> Option<Self> {
+        if header.len() < 4 || header[0] != 0xff || header[1] & 0xe0 != 0xe0 {
+            return None;
+        }
+
+        // 0: MPEG-2.5, 2: MPEG-2, 3: MPEG-1
+        let version = (header[1] >> 3) & 0x03;
+        let layer = match (header[1] >> 1) & 0x03 {
+            0 => return None,
+            bits => 4 - bits,
+        };
+        let bitrate_index = (header[2] >> 4) as usize;
+        let rate_index = ((header[2] >> 2) & 0x03) as usize;
+
+        if version == 1 || bitrate_index == 0 || bitrate_index == 15 || rate_index == 3 {
+            return None;
+        }
+
+        let mpeg1 = version == 3;
+        let table = match (mpeg1, layer) {
+            (true, layer) => layer as usize - 1,
+            (false, 1) => 3,
+            (false, _) => 4,
+        };
+        let bitrate = BITRATES[table][bitrate_index];
+        let sample_rate = [44_100, 48_000, 32_000][rate_index]
+            >> match version {
+                3 => 0,
+                2 => 1,
+                _ => 2,
+            };
+        let padding = ((header[2] >> 1) & 0x01) as u32;
+        let channels = match header[3] >> 6 {
+            3 => 1,
+            _ => 2,
+        };
+
+        let len = match layer {
+            1 => (12 * bitrate * 1000 / sample_rate + padding) * 4,
+            3 if !mpeg1 => 72 * bitrate * 1000 / sample_rate + padding,
+            _ => 144 * bitrate * 1000 / sample_rate + padding,
+        } as usize;
+
+        Some(Self {
+            mpeg1,
+            layer,
+            bitrate,
+            sample_rate,
+            channels,
+            len,
+        })
+    }
+
+    /// Whether `other` can be the next frame of the same stream
+    fn is_same_stream(&self, other: &Self) -> bool {
+        self.mpeg1 == other.mpeg1
+            && self.layer == other.layer
+            && self.sample_rate == other.sample_rate
+    }
+
+    fn samples(&self) -> u32 {
+        match (self.layer, self.mpeg1) {
+            (1, _) => 384,
+            (3, false) => 576,
+            _ => 1152,
+        }
+    }
+
+    /// The offset of a Xing or Info header, after the side information
+    fn xing_offset(&self) -> usize {
+        4 + match (self.mpeg1, self.channels) {
+            (true, 1) => 17,
+            (true, _) => 32,
+            (false, 1) => 9,
+            (false, _) => 17,
+        }
+    }
+}
+
+/// Reads the first frame of an MP3 file, with its Xing, Info or VBRI header
+///
+/// **NOTE:** A frame is only trusted when another one of the same stream
+/// follows it. Without an ID3 tag, the first frame must start the file, and
+/// a file of a single frame must have an ID3 tag. Without a Xing, Info or
+/// VBRI header, the file is assumed to be a constant bitrate stream
+pub fn read<R: Read + Seek>(
+    reader: &mut R,
+    start: u64,
+    file_len: u64,
+) -> io::Result<Option<MediaInfo>> {
+    let id3v1_len = {
+        let mut tag = [0u8; 3];
+        reader.seek(SeekFrom::Start(file_len.saturating_sub(128)))?;
+        match file_len >= 128 && read_up_to(reader, &mut tag)? == 3 && &tag == b"TAG" {
+            true => 128,
+            false => 0,
+        }
+    };
+    let tagged = start > 0 || id3v1_len > 0;
+    let audio_end = file_len.saturating_sub(id3v1_len);
+
+    let mut data = vec![0u8; MAX_SYNC_SEARCH + 256];
+    reader.seek(SeekFrom::Start(start))?;
+    let data_len = read_up_to(reader, &mut data)?;
+    let data = &data[..data_len];
+
+    // Random bytes, or any other file, easily contain a sync word, but
+    // rarely two frames in a row
+    let search_len = match tagged {
+        true => data.len().min(MAX_SYNC_SEARCH),
+        false => data.len().min(1),
+    };
+    let (offset, frame) = match (0..search_len).find_map(|offset| {
+        let frame = Frame::parse(&data[offset..])?;
+        let frame_end = offset.checked_add(frame.len)?;
+        let follows = match data.get(frame_end..) {
+            Some(next) if next.len() >= 4 => {
+                Frame::parse(next).is_some_and(|next| next.is_same_stream(&frame))
+            }
+            _ => tagged && start + frame_end as u64 == audio_end,
+        };
+
+        match follows {
+            true => Some((offset, frame)),
+            false => None,
+        }
+    }) {
+        Some(found) => found,
+        None => return Ok(None),
+    };
+
+    let audio_len = audio_end.saturating_sub(start + offset as u64);
+
+    let frames = vbr_frames(&data[offset..], &frame);
+    let duration = match frames {
+        Some(frames) => frames as f64 * frame.samples() as f64 / frame.sample_rate as f64,
+        None => audio_len as f64 * 8.0 / (frame.bitrate as f64 * 1000.0),
+    };
+
+    Ok(Some(MediaInfo {
+        duration: Some(duration),
+        bitrate: match frames {
+            Some(_) => bitrate(audio_len, duration),
+            None => Some(frame.bitrate),
+        },
+        sample_rate: Some(frame.sample_rate),
+        channels: Some(frame.channels),
+        ..MediaInfo::default()
+    }))
+}
+
+/// The number of frames recorded in a Xing, Info or VBRI header
+fn vbr_frames(data: &[u8], frame: &Frame) -> Option<u32> {
+    let be_u32 = |at: usize| {
+        data.get(at..at + 4)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+
+    let xing = frame.xing_offset();
+    if let Some(b"Xing") | Some(b"Info") = data.get(xing..xing + 4) {
+        let flags = be_u32(xing + 4)?;
+        return match flags & 0x01 != 0 {
+            true => be_u32(xing + 8).filter(|frames| *frames > 0),
+            false => None,
+        };
+    }
+
+    // VBRI always follows 32 bytes of side information
+    if data.get(36..40) == Some(b"VBRI") {
+        return be_u32(36 + 14).filter(|frames| *frames > 0);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// An MPEG-1 layer III frame, at 128 kbit/s and 44.1 kHz
+    fn frame() -> Vec<u8> {
+        let mut frame = vec![0u8; 417];
+        frame[..4].copy_from_slice(&[0xff, 0xfb, 0x90, 0x64]);
+        frame
+    }
+
+    fn read_bytes(data: Vec<u8>, start: u64) -> Option<MediaInfo> {
+        let len = data.len() as u64;
+        read(&mut Cursor::new(data), start, len).unwrap()
+    }
+
+    #[test]
+    fn reads_consecutive_frames() {
+        let data = frame().repeat(10);
+
+        let info = read_bytes(data, 0).unwrap();
+
+        assert_eq!(info.bitrate, Some(128));
+        assert_eq!(info.sample_rate, Some(44_100));
+        assert_eq!(info.channels, Some(2));
+        assert!((info.duration.unwrap() - 0.26).abs() < 0.01);
+    }
+
+    #[test]
+    fn refuses_lone_or_broken_frames() {
+        assert!(read_bytes(frame(), 0).is_none());
+
+        // The next frame is of another sample rate
+        let mut data = frame();
+        data.extend([0xff, 0xfb, 0x94, 0x64]);
+        data.extend([0u8; 400]);
+        assert!(read_bytes(data, 0).is_none());
+
+        // A lone frame is kept after an ID3v2 tag
+        let mut data = b"ID3\x03\0\0\0\0\0\0".to_vec();
+        data.extend(frame());
+        assert!(read_bytes(data, 10).is_some());
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend(frame().repeat(2));
+        assert!(read_bytes(png, 0).is_none());
+
+        // A simple linear congruential generator, as random bytes
+        let mut seed = 1u32;
+        let random: Vec<u8> = (0..MAX_SYNC_SEARCH)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (seed >> 16) as u8
+            })
+            .collect();
+        assert!(read_bytes(random, 0).is_none());
+    }
+}
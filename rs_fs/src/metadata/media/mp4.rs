@@ -0,0 +1,209 @@
+use super::{bitrate, MediaInfo};
+use crate::metadata::isobmff::{self, IsoBox};
+use std::io::{self, Read, Seek};
+
+/// The largest box read whole, such as a sample table
+const MAX_BOX_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Reads the movie header and the tracks of an MP4 or MOV file
+///
+/// **NOTE:** The duration comes from `mvhd`, the resolution from the `tkhd`
+/// of the first video track, and its frame rate from its `mdhd` time scale
+/// and `stts` sample durations
+pub fn read<R: Read + Seek>(reader: &mut R, file_len: u64) -> io::Result<Option<MediaInfo>> {
+    let moov = match isobmff::find_path(reader, &[b"moov"])? {
+        Some(moov) => moov,
+        None => return Ok(None),
+    };
+
+    let mut info = MediaInfo::default();
+
+    if let Some(mvhd) = isobmff::find_path_in(reader, &moov, &[b"mvhd"])? {
+        let mvhd = mvhd.read(reader, MAX_BOX_SIZE)?;
+        info.duration = timed(&mvhd).and_then(|(scale, duration)| seconds(duration, scale));
+    }
+
+    for trak in isobmff::children(reader, moov.start, moov.end)? {
+        if &trak.kind != b"trak" {
+            continue;
+        }
+
+        match handler(reader, &trak)? {
+            Some(handler) if &handler == b"vide" && info.width.is_none() => {
+                read_video(reader, &trak, &mut info)?
+            }
+            Some(handler) if &handler == b"soun" && info.sample_rate.is_none() => {
+                read_audio(reader, &trak, &mut info)?
+            }
+            _ => {}
+        }
+    }
+
+    info.bitrate = info
+        .duration
+        .and_then(|duration| bitrate(file_len, duration));
+
+    Ok(Some(info))
+}
+
+/// The handler type of a track, such as `vide` or `soun`
+fn handler<R: Read + Seek>(reader: &mut R, trak: &IsoBox) -> io::Result<Option<[u8; 4]>> {
+    let hdlr = match isobmff::find_path_in(reader, trak, &[b"mdia", b"hdlr"])? {
+        Some(hdlr) => hdlr.read(reader, MAX_BOX_SIZE)?,
+        None => return Ok(None),
+    };
+
+    Ok(hdlr
+        .get(8..12)
+        .map(|kind| [kind[0], kind[1], kind[2], kind[3]]))
+}
+
+fn read_video<R: Read + Seek>(
+    reader: &mut R,
+    trak: &IsoBox,
+    info: &mut MediaInfo,
+) -> io::Result<()> {
+    // The display size ends the track header, as 16.16 fixed point numbers
+    if let Some(tkhd) = isobmff::find_path_in(reader, trak, &[b"tkhd"])? {
+        let tkhd = tkhd.read(reader, MAX_BOX_SIZE)?;
+
+        if tkhd.len() >= 8 {
+            let size = &tkhd[tkhd.len() - 8..];
+            info.width = Some(be_u32(&size[..4]) >> 16).filter(|width| *width > 0);
+            info.height = Some(be_u32(&size[4..]) >> 16).filter(|height| *height > 0);
+        }
+    }
+
+    let scale = match isobmff::find_path_in(reader, trak, &[b"mdia", b"mdhd"])? {
+        Some(mdhd) => timed(&mdhd.read(reader, MAX_BOX_SIZE)?).map(|(scale, _)| scale),
+        None => None,
+    };
+
+    let stts = isobmff::find_path_in(reader, trak, &[b"mdia", b"minf", b"stbl", b"stts"])?;
+    if let (Some(scale), Some(stts)) = (scale, stts) {
+        let stts = stts.read(reader, MAX_BOX_SIZE)?;
+
+        // Entries are pairs of a sample count and a sample duration
+        let (frames, duration) = stts.get(8..).unwrap_or_default().chunks_exact(8).fold(
+            (0u64, 0u64),
+            |(frames, duration), entry| {
+                let count = be_u32(&entry[..4]) as u64;
+                (
+                    frames.saturating_add(count),
+                    duration.saturating_add(count * be_u32(&entry[4..]) as u64),
+                )
+            },
+        );
+
+        if frames > 0 && duration > 0 {
+            info.frame_rate = Some(frames as f64 * scale as f64 / duration as f64);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_audio<R: Read + Seek>(
+    reader: &mut R,
+    trak: &IsoBox,
+    info: &mut MediaInfo,
+) -> io::Result<()> {
+    let stsd = match isobmff::find_path_in(reader, trak, &[b"mdia", b"minf", b"stbl", b"stsd"])? {
+        Some(stsd) => stsd.read(reader, MAX_BOX_SIZE)?,
+        None => return Ok(()),
+    };
+
+    // The first sample entry, after the version, flags and entry count:
+    // its channel count is at byte 24, and its 16.16 sample rate at byte 32
+    if let Some(entry) = stsd.get(8..) {
+        if entry.len() >= 36 {
+            info.channels = Some(u16::from_be_bytes([entry[24], entry[25]])).filter(|c| *c > 0);
+            info.sample_rate = Some(be_u32(&entry[32..36]) >> 16).filter(|rate| *rate > 0);
+        }
+    }
+
+    Ok(())
+}
+
+/// The time scale and duration of a `mvhd` or `mdhd` box
+fn timed(data: &[u8]) -> Option<(u32, u64)> {
+    match data.first()? {
+        1 => Some((
+            be_u32(data.get(20..24)?),
+            u64::from_be_bytes(data.get(24..32)?.try_into().ok()?),
+        )),
+        _ => Some((be_u32(data.get(12..16)?), be_u32(data.get(16..20)?) as u64)),
+    }
+}
+
+fn seconds(duration: u64, scale: u32) -> Option<f64> {
+    match scale > 0 && duration > 0 && duration != u32::MAX as u64 && duration != u64::MAX {
+        true => Some(duration as f64 / scale as f64),
+        false => None,
+    }
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn iso_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend(kind);
+        data.extend(payload);
+        data
+    }
+
+    /// A version 0 `mvhd` of 90 seconds
+    fn mvhd() -> Vec<u8> {
+        let mut mvhd = vec![0u8; 12];
+        mvhd.extend(1000u32.to_be_bytes());
+        mvhd.extend(90_000u32.to_be_bytes());
+        mvhd.extend([0u8; 80]);
+        iso_box(b"mvhd", &mvhd)
+    }
+
+    fn movie(moov: &[u8]) -> Vec<u8> {
+        let mut data = iso_box(b"ftyp", b"isom\0\0\0\0");
+        data.extend(iso_box(b"moov", moov));
+        data
+    }
+
+    #[test]
+    fn reads_the_movie_header() {
+        let data = movie(&mvhd());
+        let len = data.len() as u64;
+
+        let info = read(&mut Cursor::new(data), len).unwrap().unwrap();
+
+        assert_eq!(info.duration, Some(90.0));
+        assert_eq!(info.width, None);
+    }
+
+    #[test]
+    fn skips_truncated_headers() {
+        let mut moov = mvhd();
+        moov.truncate(20);
+        moov[..4].copy_from_slice(&20u32.to_be_bytes());
+        let data = movie(&moov);
+        let len = data.len() as u64;
+
+        let info = read(&mut Cursor::new(data), len).unwrap().unwrap();
+
+        assert_eq!(info, MediaInfo::default());
+        assert_eq!(timed(&[1, 0, 0, 0]), None);
+        assert_eq!(seconds(u32::MAX as u64, 1000), None);
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+
+        assert!(read(&mut Cursor::new(png), 16).unwrap().is_none());
+    }
+}
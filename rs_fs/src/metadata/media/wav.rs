@@ -0,0 +1,129 @@
+use super::{bitrate, MediaInfo};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// Reads the `fmt` and `data` chunks of a WAV file
+///
+/// **NOTE:** The reader must be at the start of the `RIFF` header
+pub fn read<R: Read + Seek>(reader: &mut R) -> io::Result<Option<MediaInfo>> {
+    reader.seek(SeekFrom::Current(12))?;
+
+    let mut format: Option<(u16, u32, u32)> = None;
+    let mut data_len = None;
+
+    while format.is_none() || data_len.is_none() {
+        let mut header = [0u8; 8];
+        if reader.read_exact(&mut header).is_err() {
+            break;
+        }
+
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        // Chunks are padded to an even size
+        let padded = size as i64 + (size % 2) as i64;
+
+        match &header[..4] {
+            b"fmt " if size >= 16 => {
+                let mut fmt = [0u8; 16];
+                reader.read_exact(&mut fmt)?;
+                reader.seek(SeekFrom::Current(padded - 16))?;
+
+                format = Some((
+                    u16::from_le_bytes([fmt[2], fmt[3]]),
+                    u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]),
+                    u32::from_le_bytes([fmt[8], fmt[9], fmt[10], fmt[11]]),
+                ));
+            }
+            b"data" => {
+                data_len = Some(size as u64);
+                reader.seek(SeekFrom::Current(padded))?;
+            }
+            _ => {
+                reader.seek(SeekFrom::Current(padded))?;
+            }
+        }
+    }
+
+    let (channels, sample_rate, byte_rate) = match format {
+        Some(format) => format,
+        None => return Ok(None),
+    };
+
+    let duration = match (data_len, byte_rate) {
+        (Some(data_len), byte_rate) if byte_rate > 0 => Some(data_len as f64 / byte_rate as f64),
+        _ => None,
+    };
+
+    Ok(Some(MediaInfo {
+        duration,
+        bitrate: duration.and_then(|duration| bitrate(data_len.unwrap_or(0), duration)),
+        sample_rate: Some(sample_rate).filter(|rate| *rate > 0),
+        channels: Some(channels).filter(|channels| *channels > 0),
+        ..MediaInfo::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn chunk(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut data = kind.to_vec();
+        data.extend((payload.len() as u32).to_le_bytes());
+        data.extend(payload);
+        data
+    }
+
+    /// 16 bit stereo at 44.1 kHz
+    fn fmt() -> Vec<u8> {
+        let mut fmt = 1u16.to_le_bytes().to_vec();
+        fmt.extend(2u16.to_le_bytes());
+        fmt.extend(44_100u32.to_le_bytes());
+        fmt.extend(176_400u32.to_le_bytes());
+        fmt.extend(4u16.to_le_bytes());
+        fmt.extend(16u16.to_le_bytes());
+        chunk(b"fmt ", &fmt)
+    }
+
+    fn wave(chunks: &[u8]) -> Vec<u8> {
+        let mut data = b"RIFF".to_vec();
+        data.extend(((chunks.len() + 4) as u32).to_le_bytes());
+        data.extend(b"WAVE");
+        data.extend(chunks);
+        data
+    }
+
+    #[test]
+    fn reads_fmt_and_data_chunks() {
+        let mut chunks = chunk(b"LIST", b"odd");
+        chunks.push(0);
+        chunks.extend(fmt());
+        chunks.extend(chunk(b"data", &[0u8; 17_640]));
+
+        let info = read(&mut Cursor::new(wave(&chunks))).unwrap().unwrap();
+
+        assert_eq!(info.duration, Some(0.1));
+        assert_eq!(info.bitrate, Some(1411));
+        assert_eq!(info.sample_rate, Some(44_100));
+        assert_eq!(info.channels, Some(2));
+    }
+
+    #[test]
+    fn reads_truncated_files() {
+        let mut chunks = fmt();
+        chunks.extend(b"data");
+        let info = read(&mut Cursor::new(wave(&chunks))).unwrap().unwrap();
+        assert_eq!(info.sample_rate, Some(44_100));
+        assert_eq!(info.duration, None);
+
+        let mut data = wave(&fmt());
+        data.truncate(30);
+        assert!(read(&mut Cursor::new(data)).is_err());
+    }
+
+    #[test]
+    fn finds_nothing_in_other_files() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".repeat(4);
+
+        assert!(read(&mut Cursor::new(png)).unwrap().is_none());
+    }
+}
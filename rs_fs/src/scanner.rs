@@ -0,0 +1,377 @@
+mod entry;
+mod filter;
+
+pub use entry::{FileEntry, FileKind};
+
+use crate::error_factory::{create_error, create_warning};
+use filter::GlobFilter;
+use rs_response::{DataResponse, OkResponse};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ERR_SRC: &str = "scanner::scan()";
+
+/// Options for scanning a directory with `scan`
+///
+/// # Properties:
+/// - `root`: `PathBuf` - The directory to scan
+/// - `max_depth`: `Option<usize>` - How many levels of folders to enter. `Some(1)` only
+///   lists the root's own files, and `None` has no limit. Defaults to `Some(1)`
+/// - `include_hidden`: `bool` - Whether to list hidden files and enter hidden folders. Defaults to `false`
+/// - `follow_symlinks`: `bool` - Whether symbolic links are followed. When `false`, links are
+///   listed as `FileKind::Symlink` entries and never entered. Defaults to `false`
+/// - `include_directories`: `bool` - Whether folders are listed as entries. Defaults to `false`
+/// - `include`: `Vec<String>` - Glob patterns a file must match to be listed, such as `*.jpg`.
+///   An empty list matches every file. Defaults to `[]`
+/// - `exclude`: `Vec<String>` - Glob patterns of files and folders to skip. Defaults to `[]`
+///
+/// **NOTE:** Glob patterns are matched against the path relative to the
+/// `root`, using `/` as the separator, so `*.jpg` also matches `2023/a.jpg`
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    pub root: PathBuf,
+    pub max_depth: Option<usize>,
+    pub include_hidden: bool,
+    pub follow_symlinks: bool,
+    pub include_directories: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+impl ScanOptions {
+    /// Creates a new `ScanOptions` item with default values
+    ///
+    /// # Arguments:
+    /// - `root`: `impl Into<PathBuf>` - The directory to scan
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_depth: Some(1),
+            include_hidden: false,
+            follow_symlinks: false,
+            include_directories: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+/// The output of `scan`
+///
+/// # Properties:
+/// - `entries`: `Vec<FileEntry>` - The files found, sorted by path
+/// - `warnings`: `Vec<OkResponse>` - A *Warning* for every folder or file that could not be read
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ScanResults {
+    pub entries: Vec<FileEntry>,
+    pub warnings: Vec<OkResponse>,
+}
+
+/// Lists the files in a directory and its subdirectories
+///
+/// **NOTE:** Only an unreadable `root` or an invalid glob pattern fail the
+/// scan. Folders and files that cannot be read are skipped and reported
+/// in `ScanResults::warnings`
+///
+/// # Arguments:
+/// - `options`: `&ScanOptions` - The directory to scan and how to filter it
+///
+/// # Example:
+/// ```
+/// use rs_fs::{scan, ScanOptions};
+/// use rs_response::DataResponse;
+///
+/// fn list_photos(folder: &str) -> DataResponse<usize> {
+///   let mut options = ScanOptions::new(folder);
+///   options.max_depth = None;
+///   options.include = vec![String::from("*.jpg"), String::from("*.jpeg")];
+///   options.exclude = vec![String::from("**/thumbnails")];
+///
+///   let results = scan(&options)?;
+///
+///   Ok(results.entries.len())
+/// }
+/// ```
+pub fn scan(options: &ScanOptions) -> DataResponse<ScanResults> {
+    let include = GlobFilter::new(&options.include)?;
+    let exclude = GlobFilter::new(&options.exclude)?;
+
+    let root_meta = fs::metadata(&options.root).map_err(|e| {
+        create_error(
+            format!("Unable to scan '{}'", options.root.display()),
+            e.to_string(),
+            ERR_SRC,
+        )
+    })?;
+
+    if !root_meta.is_dir() {
+        return Err(create_error(
+            format!("Unable to scan '{}'", options.root.display()),
+            "The path is not a folder",
+            ERR_SRC,
+        ));
+    }
+
+    let mut results = ScanResults::default();
+    let mut visited = HashSet::new();
+    let mut pending = vec![(options.root.clone(), 1)];
+
+    if let Ok(canonical) = fs::canonicalize(&options.root) {
+        visited.insert(canonical);
+    }
+
+    while let Some((dir, depth)) = pending.pop() {
+        let read_dir = match fs::read_dir(&dir) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                results.warnings.push(create_warning(
+                    format!("Skipped the folder '{}'", dir.display()),
+                    e.to_string(),
+                    ERR_SRC,
+                ));
+                continue;
+            }
+        };
+
+        for item in read_dir {
+            let path = match item {
+                Ok(item) => item.path(),
+                Err(e) => {
+                    results.warnings.push(create_warning(
+                        format!("Skipped an entry of '{}'", dir.display()),
+                        e.to_string(),
+                        ERR_SRC,
+                    ));
+                    continue;
+                }
+            };
+
+            let entry = match FileEntry::read(&path, options.follow_symlinks) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    results.warnings.push(create_warning(
+                        format!("Skipped '{}'", path.display()),
+                        e.to_string(),
+                        ERR_SRC,
+                    ));
+                    continue;
+                }
+            };
+
+            if !options.include_hidden && entry.hidden {
+                continue;
+            }
+
+            let relative = relative_path(&options.root, &path);
+            if exclude.is_match(&relative) {
+                continue;
+            }
+
+            if entry.kind == FileKind::Directory {
+                let can_descend = match options.max_depth {
+                    Some(max) => depth < max,
+                    None => true,
+                };
+                if can_descend && first_visit(&mut visited, &path) {
+                    pending.push((path.clone(), depth + 1));
+                }
+
+                if !options.include_directories {
+                    continue;
+                }
+            }
+
+            if include.is_empty() || include.is_match(&relative) {
+                results.entries.push(entry);
+            }
+        }
+    }
+
+    results.entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(results)
+}
+
+/// The path relative to the scan's root, using `/` as the separator
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Whether a folder has not been entered yet, so that symbolic link
+/// loops are only followed once
+fn first_visit(visited: &mut HashSet<PathBuf>, dir: &Path) -> bool {
+    match fs::canonicalize(dir) {
+        Ok(canonical) => visited.insert(canonical),
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A folder with hidden, nested and filtered files
+    fn temp_tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("rs_fs-scan-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        for file in [
+            "a.jpg",
+            "b.txt",
+            ".hidden.jpg",
+            ".secret/c.jpg",
+            "sub/d.JPG",
+            "sub/deep/e.jpg",
+            "thumbnails/f.jpg",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, file).unwrap();
+        }
+
+        root
+    }
+
+    /// The relative paths of the entries found
+    fn scan_names(options: &ScanOptions) -> Vec<String> {
+        scan(options)
+            .unwrap()
+            .entries
+            .iter()
+            .map(|entry| relative_path(&options.root, &entry.path))
+            .collect()
+    }
+
+    #[test]
+    fn limits_the_depth() {
+        let root = temp_tree("depth");
+        let mut options = ScanOptions::new(&root);
+
+        let first_level = scan_names(&options);
+        options.max_depth = Some(2);
+        let two_levels = scan_names(&options);
+        options.max_depth = None;
+        let every_level = scan_names(&options);
+        options.include_directories = true;
+        let with_folders = scan_names(&options);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(first_level, ["a.jpg", "b.txt"]);
+        assert_eq!(
+            two_levels,
+            ["a.jpg", "b.txt", "sub/d.JPG", "thumbnails/f.jpg"]
+        );
+        assert_eq!(
+            every_level,
+            [
+                "a.jpg",
+                "b.txt",
+                "sub/d.JPG",
+                "sub/deep/e.jpg",
+                "thumbnails/f.jpg"
+            ]
+        );
+        assert_eq!(
+            with_folders,
+            [
+                "a.jpg",
+                "b.txt",
+                "sub",
+                "sub/d.JPG",
+                "sub/deep",
+                "sub/deep/e.jpg",
+                "thumbnails",
+                "thumbnails/f.jpg"
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_hidden_files_when_asked() {
+        let root = temp_tree("hidden");
+        let mut options = ScanOptions::new(&root);
+        options.max_depth = None;
+        options.include_hidden = true;
+
+        let names = scan_names(&options);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(names.contains(&String::from(".hidden.jpg")));
+        assert!(names.contains(&String::from(".secret/c.jpg")));
+        assert_eq!(names.len(), 7);
+    }
+
+    #[test]
+    fn filters_with_globs() {
+        let root = temp_tree("globs");
+        let mut options = ScanOptions::new(&root);
+        options.max_depth = None;
+        options.include = vec![String::from("*.jpg")];
+        options.exclude = vec![String::from("**/thumbnails"), String::from("sub/deep/*")];
+
+        let names = scan_names(&options);
+        options.include = vec![String::from("[")];
+        let invalid = scan(&options);
+        fs::remove_dir_all(&root).unwrap();
+
+        // Matching ignores letter case, and an excluded folder is not entered
+        assert_eq!(names, ["a.jpg", "sub/d.JPG"]);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn fails_on_an_unreadable_root() {
+        let root = temp_tree("root");
+        let missing = scan(&ScanOptions::new(root.join("missing")));
+        let file = scan(&ScanOptions::new(root.join("a.jpg")));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(missing.is_err());
+        assert_eq!(file.err().unwrap().cause, "The path is not a folder");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn warns_about_unreadable_entries() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let root = temp_tree("unreadable");
+        symlink(root.join("missing"), root.join("broken")).unwrap();
+        let locked = root.join("sub");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        // Permissions do not apply to the superuser
+        let can_lock = fs::read_dir(&locked).is_err();
+
+        let mut options = ScanOptions::new(&root);
+        options.max_depth = None;
+        options.follow_symlinks = true;
+        let results = scan(&options).unwrap();
+
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let warnings: Vec<String> = results
+            .warnings
+            .iter()
+            .map(|warning| match warning {
+                OkResponse::WARN(warning) => warning.message.clone(),
+                OkResponse::INFO(info) => info.message.clone(),
+            })
+            .collect();
+
+        assert!(warnings.contains(&format!("Skipped '{}'", root.join("broken").display())));
+        if can_lock {
+            assert!(warnings.contains(&format!("Skipped the folder '{}'", locked.display())));
+        }
+        assert!(results
+            .entries
+            .iter()
+            .any(|entry| entry.path == root.join("thumbnails/f.jpg")));
+    }
+}
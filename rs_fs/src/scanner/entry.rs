@@ -0,0 +1,113 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// The type of a `FileEntry`
+///
+/// - `File`: A regular file
+/// - `Directory`: A folder
+/// - `Symlink`: A symbolic link that was not followed
+/// - `Other`: Anything else, such as a socket or a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum FileKind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+/// A single file found by `scan`
+///
+/// # Properties:
+/// - `path`: `PathBuf` - The full path of the file
+/// - `stem`: `String` - The file name without its extension
+/// - `extension`: `Option<String>` - The extension, without the leading dot
+/// - `size`: `u64` - The size in bytes
+/// - `modified`: `Option<SystemTime>` - The last modification time, if the platform provides it
+/// - `created`: `Option<SystemTime>` - The creation time, if the platform provides it
+/// - `accessed`: `Option<SystemTime>` - The last access time, if the platform provides it
+/// - `kind`: `FileKind` - The type of the file
+/// - `hidden`: `bool` - Whether the file is hidden
+///
+/// # Methods:
+/// - `read` - Reads the metadata of a path into a `FileEntry`
+/// - `file_name` - The full file name, including the extension
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileEntry {
+    pub path: PathBuf,
+    pub stem: String,
+    pub extension: Option<String>,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    pub created: Option<SystemTime>,
+    pub accessed: Option<SystemTime>,
+    pub kind: FileKind,
+    pub hidden: bool,
+}
+impl FileEntry {
+    /// Reads the metadata of a path into a `FileEntry`
+    ///
+    /// # Arguments:
+    /// - `path`: `&Path` - The path to read
+    /// - `follow_symlinks`: `bool` - Whether to describe the target of a symbolic link
+    ///   rather than the link itself
+    pub fn read(path: &Path, follow_symlinks: bool) -> io::Result<Self> {
+        let meta = match follow_symlinks {
+            true => fs::metadata(path)?,
+            false => fs::symlink_metadata(path)?,
+        };
+
+        let file_type = meta.file_type();
+        let kind = if file_type.is_symlink() {
+            FileKind::Symlink
+        } else if file_type.is_dir() {
+            FileKind::Directory
+        } else if file_type.is_file() {
+            FileKind::File
+        } else {
+            FileKind::Other
+        };
+
+        let os_str_to_string = |s: &std::ffi::OsStr| s.to_string_lossy().to_string();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            stem: path.file_stem().map(os_str_to_string).unwrap_or_default(),
+            extension: path.extension().map(os_str_to_string),
+            size: meta.len(),
+            modified: meta.modified().ok(),
+            created: meta.created().ok(),
+            accessed: meta.accessed().ok(),
+            kind,
+            hidden: is_hidden(path, &meta),
+        })
+    }
+
+    /// The full file name, including the extension
+    pub fn file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(windows)]
+fn is_hidden(path: &Path, meta: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+
+    meta.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0 || has_dot_prefix(path)
+}
+
+#[cfg(not(windows))]
+fn is_hidden(path: &Path, _meta: &fs::Metadata) -> bool {
+    has_dot_prefix(path)
+}
+
+fn has_dot_prefix(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().starts_with('.'))
+        .unwrap_or(false)
+}
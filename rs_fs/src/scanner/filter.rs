@@ -0,0 +1,52 @@
+use crate::error_factory::create_error;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use rs_response::DataResponse;
+
+const ERR_SRC: &str = "scanner::filter::GlobFilter";
+
+/// A compiled list of glob patterns
+pub struct GlobFilter {
+    set: GlobSet,
+    len: usize,
+}
+impl GlobFilter {
+    /// Compiles a list of glob patterns
+    ///
+    /// **NOTE:** Patterns are matched ignoring letter case
+    pub fn new(patterns: &[String]) -> DataResponse<Self> {
+        let mut builder = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            let glob = GlobBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| {
+                    create_error(
+                        format!("Invalid glob pattern '{}'", pattern),
+                        e.to_string(),
+                        ERR_SRC,
+                    )
+                })?;
+            builder.add(glob);
+        }
+
+        let set = builder
+            .build()
+            .map_err(|e| create_error("Invalid glob patterns", e.to_string(), ERR_SRC))?;
+
+        Ok(Self {
+            set,
+            len: patterns.len(),
+        })
+    }
+
+    /// Whether the list has no patterns
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether a relative path matches any of the patterns
+    pub fn is_match(&self, relative: &str) -> bool {
+        self.set.is_match(relative)
+    }
+}
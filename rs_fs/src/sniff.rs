@@ -0,0 +1,237 @@
+mod magic;
+
+use crate::error_factory::create_error;
+use crate::metadata::{read_up_to, ZipPackage};
+use rs_response::{DataResponse, ErrorRepsonse};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+const ERR_SRC: &str = "sniff::sniff()";
+
+/// How many leading bytes are read to find the type of a file
+const HEADER_SIZE: usize = 512;
+
+/// The broad category of a `FileType`
+///
+/// - `Image`: A picture, including camera raw files
+/// - `Audio`: A sound file
+/// - `Video`: A movie, which may hold only audio in container formats such as MP4
+/// - `Archive`: A compressed or packed archive
+/// - `Document`: A PDF, an office document or an e-book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum FileCategory {
+    Image,
+    Audio,
+    Video,
+    Archive,
+    Document,
+}
+
+/// A file type found by `sniff`
+///
+/// # Properties:
+/// - `name`: `&'static str` - A name to display to the user, e.g. `PNG image`
+/// - `mime`: `&'static str` - The media type, e.g. `image/png`
+/// - `category`: `FileCategory` - The broad category of the type
+/// - `extensions`: `&'static [&'static str]` - The extensions files of this type may use,
+///   the preferred one first
+///
+/// # Methods:
+/// - `extension` - The preferred extension, e.g. `jpg`
+/// - `has_extension` - Whether an extension is used by this type, ignoring letter case
+/// - `is_generic` - Whether other formats are based on this type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct FileType {
+    pub name: &'static str,
+    pub mime: &'static str,
+    pub category: FileCategory,
+    pub extensions: &'static [&'static str],
+}
+impl FileType {
+    /// The preferred extension, e.g. `jpg`
+    pub fn extension(&self) -> &'static str {
+        self.extensions[0]
+    }
+
+    /// Whether an extension is used by this type, ignoring letter case
+    ///
+    /// # Arguments:
+    /// - `extension`: `&str` - The extension, without the leading dot
+    pub fn has_extension(&self, extension: &str) -> bool {
+        self.extensions
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(extension))
+    }
+
+    /// Whether other formats are based on this type, so a file detected as
+    /// such may really be of a format `sniff` does not know, e.g. a Keynote
+    /// file is a zip archive and many camera raw formats are TIFF files
+    ///
+    /// **NOTE:** Those are zip archives, TIFF images, MPEG-4 videos and Ogg media
+    pub fn is_generic(&self) -> bool {
+        magic::GENERIC.contains(self)
+    }
+}
+
+/// Finds the real type of a file from its leading bytes, whatever its
+/// extension
+///
+/// Detects the common images (including camera raw files), audio and video
+/// formats, archives, PDF files, EPUB books, Office Open XML files (.docx,
+/// .xlsx, .pptx) and OpenDocument files (.odt, .ods, .odp)
+///
+/// **NOTE:** Returns `None` for unknown types, including text files. Legacy
+/// Office files (.doc, .xls, .ppt) are not detected either, as they all share
+/// the same leading bytes
+///
+/// # Arguments:
+/// - `path`: `&Path` - The file to read
+///
+/// # Example:
+/// ```
+/// use rs_fs::sniff;
+/// use rs_response::DataResponse;
+/// use std::path::Path;
+///
+/// fn has_wrong_extension(path: &Path) -> DataResponse<bool> {
+///   let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+///
+///   Ok(match sniff(path)? {
+///     Some(file_type) => !file_type.has_extension(extension),
+///     None => false,
+///   })
+/// }
+/// ```
+pub fn sniff(path: &Path) -> DataResponse<Option<FileType>> {
+    sniff_file(path).map_err(|e| read_error(path, e))
+}
+
+/// Whether an extension is used by any type `sniff` detects, ignoring letter
+/// case
+///
+/// # Arguments:
+/// - `extension`: `&str` - The extension, without the leading dot
+///
+/// # Example:
+/// ```
+/// use rs_fs::is_known_extension;
+///
+/// assert!(is_known_extension("JPEG"));
+/// assert!(!is_known_extension("final"));
+/// ```
+pub fn is_known_extension(extension: &str) -> bool {
+    magic::ALL
+        .iter()
+        .any(|file_type| file_type.has_extension(extension))
+}
+
+fn sniff_file(path: &Path) -> io::Result<Option<FileType>> {
+    let mut file = File::open(path)?;
+    let mut header = [0u8; HEADER_SIZE];
+    let header_len = read_up_to(&mut file, &mut header)?;
+    let header = &header[..header_len];
+
+    match magic::detect(header) {
+        Some(file_type) if file_type == magic::ZIP => {
+            drop(file);
+            zip_type(path)
+        }
+        file_type => Ok(file_type),
+    }
+}
+
+/// Tells the formats based on zip archives apart from their content
+fn zip_type(path: &Path) -> io::Result<Option<FileType>> {
+    let mut package = match ZipPackage::open(path)? {
+        Some(package) => package,
+        // A damaged archive, or one that only starts like a zip archive
+        None => return Ok(Some(magic::ZIP)),
+    };
+
+    if let Some(mimetype) = package.mimetype()? {
+        if let Some(file_type) = magic::by_mime(&mimetype) {
+            return Ok(Some(file_type));
+        }
+    }
+
+    let file_type = if package.has("word/document.xml") {
+        magic::DOCX
+    } else if package.has("xl/workbook.xml") {
+        magic::XLSX
+    } else if package.has("ppt/presentation.xml") {
+        magic::PPTX
+    } else {
+        magic::ZIP
+    };
+
+    Ok(Some(file_type))
+}
+
+fn read_error(path: &Path, e: io::Error) -> ErrorRepsonse {
+    create_error(
+        format!("Unable to read the file type of '{}'", path.display()),
+        e.to_string(),
+        ERR_SRC,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::temp_zip;
+
+    fn zip_file_type(name: &str, entries: &[(&str, &str)]) -> Option<FileType> {
+        let path = temp_zip(name, entries);
+        let file_type = sniff(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        file_type
+    }
+
+    #[test]
+    fn sniffs_zip_based_types() {
+        assert_eq!(
+            zip_file_type(
+                "sniff-epub",
+                &[("mimetype", "application/epub+zip"), ("content.opf", "")]
+            ),
+            Some(magic::EPUB)
+        );
+        assert_eq!(
+            zip_file_type("sniff-docx", &[("word/document.xml", "<w:document/>")]),
+            Some(magic::DOCX)
+        );
+        assert_eq!(
+            zip_file_type("sniff-zip", &[("notes.txt", "plain")]),
+            Some(magic::ZIP)
+        );
+    }
+
+    #[test]
+    fn sniffs_damaged_zip_archives_as_zip() {
+        let path =
+            std::env::temp_dir().join(format!("rs_fs-sniff-damaged-{}.zip", std::process::id()));
+        std::fs::write(&path, b"PK\x03\x04junk").unwrap();
+
+        assert_eq!(sniff(&path).unwrap(), Some(magic::ZIP));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sniffs_other_files_as_unknown() {
+        let path =
+            std::env::temp_dir().join(format!("rs_fs-sniff-text-{}.txt", std::process::id()));
+        std::fs::write(&path, b"plain text").unwrap();
+
+        assert_eq!(sniff(&path).unwrap(), None);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(sniff(&path).is_err());
+    }
+
+    #[test]
+    fn knows_extensions() {
+        assert!(is_known_extension("PNG"));
+        assert!(!is_known_extension("key"));
+    }
+}
@@ -0,0 +1,433 @@
+use super::{FileCategory, FileType};
+use FileCategory::{Archive, Audio, Document, Image, Video};
+
+macro_rules! file_type {
+    ($name:literal, $mime:literal, $category:expr, [$($extension:literal),+]) => {
+        FileType {
+            name: $name,
+            mime: $mime,
+            category: $category,
+            extensions: &[$($extension),+],
+        }
+    };
+}
+
+pub const PNG: FileType = file_type!("PNG image", "image/png", Image, ["png"]);
+pub const JPEG: FileType = file_type!(
+    "JPEG image",
+    "image/jpeg",
+    Image,
+    ["jpg", "jpeg", "jpe", "jfif"]
+);
+pub const GIF: FileType = file_type!("GIF image", "image/gif", Image, ["gif"]);
+pub const WEBP: FileType = file_type!("WebP image", "image/webp", Image, ["webp"]);
+pub const BMP: FileType = file_type!("BMP image", "image/bmp", Image, ["bmp", "dib"]);
+pub const ICO: FileType = file_type!("Icon", "image/x-icon", Image, ["ico"]);
+pub const PSD: FileType = file_type!(
+    "Photoshop image",
+    "image/vnd.adobe.photoshop",
+    Image,
+    ["psd", "psb"]
+);
+pub const HEIC: FileType = file_type!("HEIF image", "image/heic", Image, ["heic", "heif", "hif"]);
+pub const AVIF: FileType = file_type!("AVIF image", "image/avif", Image, ["avif"]);
+/// Many camera raw formats are TIFF files, and keep their own extension
+pub const TIFF: FileType = file_type!(
+    "TIFF image",
+    "image/tiff",
+    Image,
+    [
+        "tif", "tiff", "dng", "nef", "nrw", "arw", "srf", "sr2", "pef", "3fr", "erf", "mef", "mos",
+        "iiq", "kdc"
+    ]
+);
+pub const CR2: FileType = file_type!("Canon raw image", "image/x-canon-cr2", Image, ["cr2"]);
+pub const CR3: FileType = file_type!("Canon raw image", "image/x-canon-cr3", Image, ["cr3"]);
+pub const ORF: FileType = file_type!("Olympus raw image", "image/x-olympus-orf", Image, ["orf"]);
+pub const RW2: FileType = file_type!(
+    "Panasonic raw image",
+    "image/x-panasonic-rw2",
+    Image,
+    ["rw2", "raw"]
+);
+pub const RAF: FileType = file_type!("Fujifilm raw image", "image/x-fuji-raf", Image, ["raf"]);
+
+pub const MP3: FileType = file_type!("MP3 audio", "audio/mpeg", Audio, ["mp3"]);
+pub const AAC: FileType = file_type!("AAC audio", "audio/aac", Audio, ["aac"]);
+pub const FLAC: FileType = file_type!("FLAC audio", "audio/flac", Audio, ["flac"]);
+pub const OGG: FileType = file_type!(
+    "Ogg media",
+    "audio/ogg",
+    Audio,
+    ["ogg", "oga", "ogv", "opus", "spx"]
+);
+pub const OPUS: FileType = file_type!("Opus audio", "audio/opus", Audio, ["opus", "ogg", "oga"]);
+pub const WAV: FileType = file_type!("WAV audio", "audio/wav", Audio, ["wav", "wave"]);
+pub const AIFF: FileType = file_type!("AIFF audio", "audio/aiff", Audio, ["aiff", "aif", "aifc"]);
+pub const M4A: FileType = file_type!(
+    "MPEG-4 audio",
+    "audio/mp4",
+    Audio,
+    ["m4a", "m4b", "m4p", "mp4"]
+);
+pub const MIDI: FileType = file_type!("MIDI audio", "audio/midi", Audio, ["mid", "midi"]);
+pub const AMR: FileType = file_type!("AMR audio", "audio/amr", Audio, ["amr"]);
+
+pub const MP4: FileType = file_type!(
+    "MPEG-4 video",
+    "video/mp4",
+    Video,
+    ["mp4", "m4v", "m4a", "m4b"]
+);
+pub const MOV: FileType = file_type!(
+    "QuickTime video",
+    "video/quicktime",
+    Video,
+    ["mov", "qt", "mp4"]
+);
+pub const THREE_GP: FileType = file_type!("3GPP video", "video/3gpp", Video, ["3gp", "3g2", "mp4"]);
+pub const MKV: FileType = file_type!(
+    "Matroska video",
+    "video/x-matroska",
+    Video,
+    ["mkv", "mka", "mk3d"]
+);
+pub const WEBM: FileType = file_type!("WebM video", "video/webm", Video, ["webm"]);
+pub const AVI: FileType = file_type!("AVI video", "video/x-msvideo", Video, ["avi"]);
+pub const FLV: FileType = file_type!("Flash video", "video/x-flv", Video, ["flv"]);
+pub const MPEG: FileType = file_type!("MPEG video", "video/mpeg", Video, ["mpg", "mpeg", "vob"]);
+pub const ASF: FileType = file_type!(
+    "Windows Media file",
+    "video/x-ms-asf",
+    Video,
+    ["wmv", "wma", "asf"]
+);
+
+pub const ZIP: FileType = file_type!(
+    "Zip archive",
+    "application/zip",
+    Archive,
+    ["zip", "jar", "apk", "cbz", "xpi", "ipa", "kmz", "whl", "nupkg", "war", "aar", "vsix"]
+);
+pub const RAR: FileType = file_type!(
+    "RAR archive",
+    "application/vnd.rar",
+    Archive,
+    ["rar", "cbr"]
+);
+pub const SEVEN_ZIP: FileType = file_type!(
+    "7-Zip archive",
+    "application/x-7z-compressed",
+    Archive,
+    ["7z"]
+);
+pub const GZIP: FileType = file_type!("Gzip archive", "application/gzip", Archive, ["gz", "tgz"]);
+pub const BZIP2: FileType = file_type!(
+    "Bzip2 archive",
+    "application/x-bzip2",
+    Archive,
+    ["bz2", "tbz2", "tbz"]
+);
+pub const XZ: FileType = file_type!("XZ archive", "application/x-xz", Archive, ["xz", "txz"]);
+pub const ZSTD: FileType = file_type!(
+    "Zstandard archive",
+    "application/zstd",
+    Archive,
+    ["zst", "tzst"]
+);
+pub const TAR: FileType = file_type!("Tar archive", "application/x-tar", Archive, ["tar"]);
+
+/// Adobe Illustrator files are PDF files
+pub const PDF: FileType = file_type!("PDF document", "application/pdf", Document, ["pdf", "ai"]);
+pub const RTF: FileType = file_type!("RTF document", "application/rtf", Document, ["rtf"]);
+pub const EPUB: FileType = file_type!("EPUB book", "application/epub+zip", Document, ["epub"]);
+pub const DOCX: FileType = file_type!(
+    "Word document",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    Document,
+    ["docx", "docm", "dotx", "dotm"]
+);
+pub const XLSX: FileType = file_type!(
+    "Excel workbook",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    Document,
+    ["xlsx", "xlsm", "xltx", "xltm"]
+);
+pub const PPTX: FileType = file_type!(
+    "PowerPoint presentation",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    Document,
+    ["pptx", "pptm", "potx", "ppsx"]
+);
+pub const ODT: FileType = file_type!(
+    "OpenDocument text",
+    "application/vnd.oasis.opendocument.text",
+    Document,
+    ["odt", "ott"]
+);
+pub const ODS: FileType = file_type!(
+    "OpenDocument spreadsheet",
+    "application/vnd.oasis.opendocument.spreadsheet",
+    Document,
+    ["ods", "ots"]
+);
+pub const ODP: FileType = file_type!(
+    "OpenDocument presentation",
+    "application/vnd.oasis.opendocument.presentation",
+    Document,
+    ["odp", "otp"]
+);
+pub const ODG: FileType = file_type!(
+    "OpenDocument drawing",
+    "application/vnd.oasis.opendocument.graphics",
+    Document,
+    ["odg", "otg"]
+);
+
+/// Every detected type
+pub const ALL: &[FileType] = &[
+    PNG, JPEG, GIF, WEBP, BMP, ICO, PSD, HEIC, AVIF, TIFF, CR2, CR3, ORF, RW2, RAF, MP3, AAC, FLAC,
+    OGG, OPUS, WAV, AIFF, M4A, MIDI, AMR, MP4, MOV, THREE_GP, MKV, WEBM, AVI, FLV, MPEG, ASF, ZIP,
+    RAR, SEVEN_ZIP, GZIP, BZIP2, XZ, ZSTD, TAR, PDF, RTF, EPUB, DOCX, XLSX, PPTX, ODT, ODS, ODP,
+    ODG,
+];
+
+/// The types other formats are based on, see `FileType::is_generic`
+pub const GENERIC: &[FileType] = &[ZIP, TIFF, MP4, OGG];
+
+/// Finds the type of a file from its leading bytes
+///
+/// **NOTE:** Every zip archive is `ZIP`, its content tells the formats based
+/// on it apart
+pub fn detect(header: &[u8]) -> Option<FileType> {
+    let at = |offset: usize, magic: &[u8]| header.get(offset..offset + magic.len()) == Some(magic);
+
+    let file_type = if at(0, b"\x89PNG\r\n\x1a\n") {
+        PNG
+    } else if at(0, b"\xff\xd8\xff") {
+        JPEG
+    } else if at(0, b"GIF87a") || at(0, b"GIF89a") {
+        GIF
+    } else if at(0, b"RIFF") && at(8, b"WEBP") {
+        WEBP
+    } else if at(0, b"RIFF") && at(8, b"WAVE") {
+        WAV
+    } else if at(0, b"RIFF") && at(8, b"AVI ") {
+        AVI
+    } else if at(0, b"BM")
+        && header.len() >= 18
+        && matches!(header[14], 12 | 40 | 52 | 56 | 64 | 108 | 124)
+    {
+        BMP
+    } else if at(0, b"\0\0\x01\0")
+        && header.get(4).is_some_and(|count| *count > 0)
+        && header.get(9) == Some(&0)
+    {
+        ICO
+    } else if at(0, b"8BPS") {
+        PSD
+    } else if at(0, b"II*\0") && at(8, b"CR\x02") {
+        CR2
+    } else if at(0, b"II*\0") || at(0, b"MM\0*") {
+        TIFF
+    } else if at(0, b"IIRO") || at(0, b"IIRS") || at(0, b"MMOR") {
+        ORF
+    } else if at(0, b"IIU\0") {
+        RW2
+    } else if at(0, b"FUJIFILMCCD-RAW") {
+        RAF
+    } else if at(4, b"ftyp") {
+        iso_bmff(header)
+    } else if at(4, b"moov") || at(4, b"mdat") || at(4, b"wide") || at(4, b"pnot") {
+        // QuickTime files written before the `ftyp` box existed
+        MOV
+    } else if at(0, b"ID3") {
+        MP3
+    } else if at(0, b"fLaC") {
+        FLAC
+    } else if at(0, b"OggS") {
+        match at(28, b"OpusHead") {
+            true => OPUS,
+            false => OGG,
+        }
+    } else if at(0, b"FORM") && (at(8, b"AIFF") || at(8, b"AIFC")) {
+        AIFF
+    } else if at(0, b"MThd") {
+        MIDI
+    } else if at(0, b"#!AMR") {
+        AMR
+    } else if at(0, b"\x1a\x45\xdf\xa3") {
+        // The EBML header names the document type
+        match header.windows(4).any(|window| window == b"webm") {
+            true => WEBM,
+            false => MKV,
+        }
+    } else if at(0, b"FLV\x01") {
+        FLV
+    } else if at(0, b"\0\0\x01\xba") || at(0, b"\0\0\x01\xb3") {
+        MPEG
+    } else if at(0, b"\x30\x26\xb2\x75\x8e\x66\xcf\x11") {
+        ASF
+    } else if at(0, b"PK\x03\x04") || at(0, b"PK\x05\x06") {
+        ZIP
+    } else if at(0, b"Rar!\x1a\x07") {
+        RAR
+    } else if at(0, b"7z\xbc\xaf\x27\x1c") {
+        SEVEN_ZIP
+    } else if at(0, b"\x1f\x8b") {
+        GZIP
+    } else if at(0, b"BZh") {
+        BZIP2
+    } else if at(0, b"\xfd7zXZ\0") {
+        XZ
+    } else if at(0, b"\x28\xb5\x2f\xfd") {
+        ZSTD
+    } else if at(257, b"ustar") {
+        TAR
+    } else if at(0, b"%PDF-") {
+        PDF
+    } else if at(0, b"{\\rtf") {
+        RTF
+    } else {
+        return mpeg_audio(header);
+    };
+
+    Some(file_type)
+}
+
+/// Finds the type of a zip based file from its `mimetype` entry
+pub fn by_mime(mimetype: &str) -> Option<FileType> {
+    // Templates have their own media type, e.g. `...opendocument.text-template`
+    let mimetype = mimetype.trim_end_matches("-template");
+
+    [EPUB, ODT, ODS, ODP, ODG]
+        .into_iter()
+        .find(|file_type| file_type.mime == mimetype)
+}
+
+/// Tells the formats based on ISO BMFF apart from their brands
+fn iso_bmff(header: &[u8]) -> FileType {
+    let box_len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let major = header.get(8..12).unwrap_or_default();
+    let compatible = header
+        .get(16..box_len.min(header.len()))
+        .unwrap_or_default();
+    let has_brand = |brand: &[u8]| compatible.chunks(4).any(|chunk| chunk == brand);
+
+    match major {
+        b"crx " => CR3,
+        b"avif" | b"avis" => AVIF,
+        b"mif1" | b"msf1" if has_brand(b"avif") => AVIF,
+        b"heic" | b"heix" | b"hevc" | b"hevx" | b"heim" | b"heis" | b"mif1" | b"msf1" => HEIC,
+        b"M4A " | b"M4B " | b"M4P " => M4A,
+        b"qt  " => MOV,
+        brand if brand.starts_with(b"3g") => THREE_GP,
+        _ => MP4,
+    }
+}
+
+/// Finds an MP3 or AAC file without an ID3 tag from its first frame header
+fn mpeg_audio(header: &[u8]) -> Option<FileType> {
+    let frame = header.get(..4)?;
+    if frame[0] != 0xff || frame[1] & 0xe0 != 0xe0 {
+        return None;
+    }
+
+    // ADTS headers use the reserved layer 0
+    if frame[1] & 0xf6 == 0xf0 {
+        return Some(AAC);
+    }
+
+    let version = (frame[1] >> 3) & 0x03;
+    let layer = (frame[1] >> 1) & 0x03;
+    let bitrate = frame[2] >> 4;
+    let sample_rate = (frame[2] >> 2) & 0x03;
+
+    match version != 1 && layer == 1 && bitrate != 0x0f && sample_rate != 0x03 {
+        true => Some(MP3),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An ISO BMFF header with a major brand and compatible brands
+    fn ftyp(major: &[u8], compatible: &[&[u8]]) -> Vec<u8> {
+        let mut header = ((16 + 4 * compatible.len()) as u32).to_be_bytes().to_vec();
+        header.extend_from_slice(b"ftyp");
+        header.extend_from_slice(major);
+        header.extend_from_slice(&[0; 4]);
+        compatible
+            .iter()
+            .for_each(|brand| header.extend_from_slice(brand));
+        header
+    }
+
+    #[test]
+    fn detects_types() {
+        assert_eq!(detect(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some(PNG));
+        assert_eq!(detect(b"\xff\xd8\xff\xe0\0\x10JFIF"), Some(JPEG));
+        assert_eq!(detect(b"II*\0\x10\0\0\0CR\x02\0"), Some(CR2));
+        assert_eq!(detect(b"II*\0\x08\0\0\0\0\0"), Some(TIFF));
+        assert_eq!(detect(b"MM\0*\0\0\0\x08"), Some(TIFF));
+        assert_eq!(detect(b"RIFF\0\0\0\0WAVEfmt "), Some(WAV));
+        assert_eq!(detect(b"PK\x03\x04\x14\0"), Some(ZIP));
+        assert_eq!(detect(b"%PDF-1.7\n"), Some(PDF));
+
+        assert_eq!(detect(&ftyp(b"crx ", &[])), Some(CR3));
+        assert_eq!(detect(&ftyp(b"mif1", &[b"mif1", b"avif"])), Some(AVIF));
+        assert_eq!(detect(&ftyp(b"mif1", &[b"mif1", b"heic"])), Some(HEIC));
+        assert_eq!(detect(&ftyp(b"M4A ", &[b"isom"])), Some(M4A));
+        assert_eq!(detect(&ftyp(b"3gp4", &[])), Some(THREE_GP));
+        assert_eq!(detect(&ftyp(b"isom", &[b"mp41"])), Some(MP4));
+
+        let mut ogg = b"OggS".to_vec();
+        ogg.resize(28, 0);
+        assert_eq!(detect(&ogg), Some(OGG));
+        ogg.extend_from_slice(b"OpusHead");
+        assert_eq!(detect(&ogg), Some(OPUS));
+
+        // MPEG-1 layer III at 128 kbit/s and 44.1 kHz, then an ADTS header
+        assert_eq!(detect(b"\xff\xfb\x90\x00"), Some(MP3));
+        assert_eq!(detect(b"\xff\xf1\x50\x80"), Some(AAC));
+    }
+
+    #[test]
+    fn ignores_truncated_headers() {
+        assert_eq!(detect(b""), None);
+        assert_eq!(detect(b"\x89PNG"), None);
+        assert_eq!(detect(b"RIFF"), None);
+        assert_eq!(detect(b"GIF8"), None);
+        assert_eq!(detect(b"\xff\xfb"), None);
+        // A reserved sample rate
+        assert_eq!(detect(b"\xff\xfb\x9c\x00"), None);
+    }
+
+    #[test]
+    fn ignores_other_files() {
+        assert_eq!(detect(b"Hello, world!\n"), None);
+        assert_eq!(detect(b"<?xml version=\"1.0\"?>"), None);
+        assert_eq!(detect(&[0; 512]), None);
+    }
+
+    #[test]
+    fn tells_generic_types_apart() {
+        assert!(ZIP.is_generic());
+        assert!(TIFF.is_generic());
+        assert!(!PNG.is_generic());
+        assert!(!EPUB.is_generic());
+        assert!(!CR2.is_generic());
+    }
+
+    #[test]
+    fn finds_types_by_mime() {
+        assert_eq!(by_mime("application/epub+zip"), Some(EPUB));
+        assert_eq!(
+            by_mime("application/vnd.oasis.opendocument.text-template"),
+            Some(ODT)
+        );
+        assert_eq!(by_mime("application/zip"), None);
+    }
+}
@@ -0,0 +1,362 @@
+use crate::error_factory::create_error;
+use crate::plan::RenamePlan;
+use rs_response::DataResponse;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ERR_SRC: &str = "conflicts::ensure_no_conflicts()";
+
+/// The reason a file's new name is unsafe
+///
+/// - `DuplicateTarget`: Another file of the batch would get the same path
+/// - `ExistingFile`: A file outside of the batch already has this path
+/// - `CaseOnly`: Another path differs only in letter case, so the files
+///   would collide on case-insensitive volumes (Windows, macOS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum ConflictKind {
+    DuplicateTarget,
+    ExistingFile,
+    CaseOnly,
+}
+
+/// A single unsafe rename found by `find_conflicts`
+///
+/// # Properties:
+/// - `source`: `PathBuf` - The current path of the file
+/// - `target`: `PathBuf` - The path the file would be renamed to
+/// - `kind`: `ConflictKind` - The reason the rename is unsafe
+/// - `with`: `Vec<PathBuf>` - The other batch sources or existing files involved
+///
+/// # Methods:
+/// - `reason` - A description of the conflict to display to the user
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Conflict {
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub kind: ConflictKind,
+    pub with: Vec<PathBuf>,
+}
+impl Conflict {
+    /// A description of the conflict to display to the user
+    pub fn reason(&self) -> String {
+        let with = self
+            .with
+            .iter()
+            .map(|path| format!("'{}'", path.display()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.kind {
+            ConflictKind::DuplicateTarget => format!(
+                "'{}' would also be the new path of {}",
+                self.target.display(),
+                with
+            ),
+            ConflictKind::ExistingFile => format!(
+                "'{}' already exists and is not part of the batch",
+                self.target.display()
+            ),
+            ConflictKind::CaseOnly => format!(
+                "'{}' differs only in letter case from {}",
+                self.target.display(),
+                with
+            ),
+        }
+    }
+}
+
+/// Finds every rename of a `RenamePlan` that could overwrite another file
+///
+/// **NOTE:** Files outside of the batch are found by listing the target
+/// folders, so the result is the same on case-sensitive and
+/// case-insensitive volumes. Files of the batch that are renamed away
+/// do not count as existing files
+///
+/// # Arguments:
+/// - `plan`: `&RenamePlan` - The renames to check
+///
+/// # Example:
+/// ```
+/// use rs_rename::{find_conflicts, ConflictKind, PlannedRename, RenamePlan};
+///
+/// let plan = RenamePlan::new(vec![
+///   PlannedRename::new("photos/a.jpg", "holiday.jpg"),
+///   PlannedRename::new("photos/b.jpg", "holiday.jpg"),
+///   PlannedRename::new("photos/c.jpg", "Holiday.JPG"),
+/// ]);
+///
+/// let conflicts = find_conflicts(&plan);
+///
+/// assert_eq!(conflicts[0].kind, ConflictKind::DuplicateTarget);
+/// assert_eq!(conflicts[2].kind, ConflictKind::CaseOnly);
+/// ```
+pub fn find_conflicts(plan: &RenamePlan) -> Vec<Conflict> {
+    let targets: Vec<PathBuf> = plan.items.iter().map(|item| item.target()).collect();
+    let sources: HashSet<&Path> = plan
+        .items
+        .iter()
+        .map(|item| item.source.as_path())
+        .collect();
+
+    let mut exact: HashMap<&Path, Vec<usize>> = HashMap::new();
+    let mut folded: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+    for (idx, target) in targets.iter().enumerate() {
+        exact.entry(target.as_path()).or_default().push(idx);
+        folded.entry(fold_case(target)).or_default().push(idx);
+    }
+
+    let mut listings = FolderListings::default();
+    let mut conflicts = Vec::new();
+
+    for (idx, item) in plan.items.iter().enumerate() {
+        let target = &targets[idx];
+        let conflict = |kind, with| Conflict {
+            source: item.source.clone(),
+            target: target.clone(),
+            kind,
+            with,
+        };
+
+        let duplicates = others(&exact[target.as_path()], idx, plan);
+        if !duplicates.is_empty() {
+            conflicts.push(conflict(ConflictKind::DuplicateTarget, duplicates));
+            continue;
+        }
+
+        let case_duplicates = others(&folded[&fold_case(target)], idx, plan);
+        if !case_duplicates.is_empty() {
+            conflicts.push(conflict(ConflictKind::CaseOnly, case_duplicates));
+            continue;
+        }
+
+        if item.is_unchanged() {
+            continue;
+        }
+
+        let existing: Vec<PathBuf> = listings
+            .case_matches(target)
+            .into_iter()
+            .filter(|path| !sources.contains(path.as_path()))
+            .collect();
+
+        if existing.iter().any(|path| path == target) {
+            conflicts.push(conflict(ConflictKind::ExistingFile, vec![target.clone()]));
+        } else if !existing.is_empty() {
+            conflicts.push(conflict(ConflictKind::CaseOnly, existing));
+        }
+    }
+
+    conflicts
+}
+
+/// Fails if any rename of a `RenamePlan` could overwrite another file
+///
+/// **NOTE:** The error's `cause` lists the first few conflicts
+///
+/// # Arguments:
+/// - `plan`: `&RenamePlan` - The renames to check
+pub fn ensure_no_conflicts(plan: &RenamePlan) -> DataResponse<()> {
+    const SHOWN: usize = 5;
+
+    let conflicts = find_conflicts(plan);
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+
+    let mut details: Vec<String> = conflicts
+        .iter()
+        .take(SHOWN)
+        .map(|conflict| conflict.reason())
+        .collect();
+    if conflicts.len() > SHOWN {
+        details.push(format!("...and {} more", conflicts.len() - SHOWN));
+    }
+
+    Err(create_error(
+        format!("{} files would overwrite another file", conflicts.len()),
+        details.join("\n"),
+        ERR_SRC,
+    ))
+}
+
+/// The sources of the entries at `indexes`, other than the entry at `idx`
+fn others(indexes: &[usize], idx: usize, plan: &RenamePlan) -> Vec<PathBuf> {
+    indexes
+        .iter()
+        .filter(|&&other| other != idx)
+        .map(|&other| plan.items[other].source.clone())
+        .collect()
+}
+
+fn fold_case(path: &Path) -> PathBuf {
+    PathBuf::from(path.to_string_lossy().to_lowercase())
+}
+
+/// Caches the contents of every target folder, indexed by lowercase file name
+#[derive(Default)]
+struct FolderListings {
+    folders: HashMap<PathBuf, Option<HashMap<String, Vec<PathBuf>>>>,
+}
+impl FolderListings {
+    /// The existing paths whose file name matches the target's, ignoring letter case
+    fn case_matches(&mut self, target: &Path) -> Vec<PathBuf> {
+        let folder = target
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .to_path_buf();
+        let name = target
+            .file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        let listing = self
+            .folders
+            .entry(folder.clone())
+            .or_insert_with(|| list_folder(&folder));
+
+        match listing {
+            Some(listing) => listing.get(&name).cloned().unwrap_or_default(),
+            // The folder could not be listed, so only the exact path can be checked
+            None => match fs::symlink_metadata(target) {
+                Ok(_) => vec![target.to_path_buf()],
+                Err(_) => Vec::new(),
+            },
+        }
+    }
+}
+
+fn list_folder(folder: &Path) -> Option<HashMap<String, Vec<PathBuf>>> {
+    let read_from = match folder.as_os_str().is_empty() {
+        true => Path::new("."),
+        false => folder,
+    };
+
+    let mut listing: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for entry in fs::read_dir(read_from).ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        listing
+            .entry(name.to_lowercase())
+            .or_default()
+            .push(folder.join(name));
+    }
+
+    Some(listing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlannedRename;
+
+    fn temp_folder(name: &str, files: &[&str]) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!(
+            "rs_rename-conflicts-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        for file in files {
+            fs::write(folder.join(file), file).unwrap();
+        }
+        folder
+    }
+
+    fn file_name(path: &Path) -> String {
+        path.file_name().unwrap().to_string_lossy().to_string()
+    }
+
+    fn kinds(folder: &Path, renames: &[(&str, &str)]) -> Vec<(String, ConflictKind)> {
+        let plan = RenamePlan::new(
+            renames
+                .iter()
+                .map(|(from, to)| PlannedRename::new(folder.join(from), *to))
+                .collect(),
+        );
+
+        find_conflicts(&plan)
+            .into_iter()
+            .map(|conflict| (file_name(&conflict.source), conflict.kind))
+            .collect()
+    }
+
+    #[test]
+    fn finds_duplicate_targets() {
+        let folder = temp_folder("duplicate", &["a.txt", "b.txt", "c.txt"]);
+        let found = kinds(
+            &folder,
+            &[
+                ("a.txt", "same.txt"),
+                ("b.txt", "same.txt"),
+                ("c.txt", "other.txt"),
+            ],
+        );
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(
+            found,
+            [
+                (String::from("a.txt"), ConflictKind::DuplicateTarget),
+                (String::from("b.txt"), ConflictKind::DuplicateTarget),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_existing_files_outside_of_the_batch() {
+        let folder = temp_folder("existing", &["a.txt", "b.txt", "taken.txt", "Upper.txt"]);
+        let found = kinds(&folder, &[("a.txt", "taken.txt"), ("b.txt", "upper.txt")]);
+
+        let plan = RenamePlan::new(vec![PlannedRename::new(folder.join("a.txt"), "taken.txt")]);
+        let error = ensure_no_conflicts(&plan).err().unwrap();
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(
+            found,
+            [
+                (String::from("a.txt"), ConflictKind::ExistingFile),
+                (String::from("b.txt"), ConflictKind::CaseOnly),
+            ]
+        );
+        assert_eq!(error.message, "1 files would overwrite another file");
+        assert!(error.cause.contains("already exists"));
+    }
+
+    #[test]
+    fn finds_targets_differing_only_in_case() {
+        let folder = temp_folder("case", &["a.txt", "b.txt"]);
+        let found = kinds(&folder, &[("a.txt", "Photo.jpg"), ("b.txt", "photo.JPG")]);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(
+            found,
+            [
+                (String::from("a.txt"), ConflictKind::CaseOnly),
+                (String::from("b.txt"), ConflictKind::CaseOnly),
+            ]
+        );
+    }
+
+    #[test]
+    fn allows_swaps_and_renames_within_the_batch() {
+        let folder = temp_folder("swap", &["a.txt", "b.txt", "c.txt", "d.txt"]);
+        let found = kinds(
+            &folder,
+            &[
+                ("a.txt", "b.txt"),
+                ("b.txt", "a.txt"),
+                // A chain and a change of letter case of the file itself
+                ("c.txt", "d.txt"),
+                ("d.txt", "D2.txt"),
+            ],
+        );
+        let unchanged = kinds(&folder, &[("a.txt", "a.txt")]);
+        let case_change = kinds(&folder, &[("a.txt", "A.txt")]);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(found, []);
+        assert_eq!(unchanged, []);
+        assert_eq!(case_change, []);
+    }
+}
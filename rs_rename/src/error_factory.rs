@@ -0,0 +1,27 @@
+use rs_response::{ErrorRepsonse, OkResponse};
+
+pub fn create_error(
+    message: impl Into<String>,
+    details: impl Into<String>,
+    source: &str,
+) -> ErrorRepsonse {
+    ErrorRepsonse::new_error(
+        "Rename",
+        message,
+        details,
+        String::from("rs_rename::") + source,
+    )
+}
+
+pub fn create_warning(
+    message: impl Into<String>,
+    details: impl Into<String>,
+    source: &str,
+) -> OkResponse {
+    OkResponse::new_warning(
+        "Rename",
+        message,
+        details,
+        String::from("rs_rename::") + source,
+    )
+}
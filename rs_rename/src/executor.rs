@@ -0,0 +1,372 @@
+mod history;
+mod order;
+mod undo;
+
+pub use history::{execute_and_record, undo_batch};
+
+pub use order::{order_steps, RenameStep};
+pub use undo::{undo_renames, RecordedRename};
+
+use crate::conflicts::ensure_no_conflicts;
+use crate::error_factory::create_warning;
+use crate::plan::RenamePlan;
+use rs_fs::{Journal, JournalEntry};
+use rs_response::{ErrorRepsonse, OkDataResponse, OkResponse, ResponseWithData};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+const ERR_SRC: &str = "executor::execute()";
+
+/// What `execute` does with the completed renames when a rename fails
+///
+/// - `Rollback`: Undo every completed rename, so the batch is all or nothing
+/// - `KeepPartial`: Keep the completed renames. Files that were moved to a
+///   temporary name are still moved back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnFailure {
+    #[default]
+    Rollback,
+    KeepPartial,
+}
+
+/// The outcome of `execute`
+///
+/// # Properties:
+/// - `applied`: `Vec<RenameStep>` - The renames that are in place, in the order they were done
+/// - `rolled_back`: `Vec<RenameStep>` - The renames that were undone, in the order they were undone
+/// - `failure`: `Option<ErrorRepsonse>` - The error that stopped the batch
+/// - `rollback_failures`: `Vec<ErrorRepsonse>` - The errors of renames that could not be undone
+///
+/// # Methods:
+/// - `renamed` - The original and final path of every file that is renamed
+/// - `recorded` - Every file that is renamed, ready to be undone with `undo_renames`
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ExecutionReport {
+    pub applied: Vec<RenameStep>,
+    pub rolled_back: Vec<RenameStep>,
+    pub failure: Option<ErrorRepsonse>,
+    pub rollback_failures: Vec<ErrorRepsonse>,
+}
+impl ExecutionReport {
+    /// The original and final path of every file that is renamed,
+    /// following the file through any temporary names
+    ///
+    /// **NOTE:** Renames that were rolled back are not included
+    pub fn renamed(&self) -> Vec<(PathBuf, PathBuf)> {
+        let mut order: Vec<PathBuf> = Vec::new();
+        let mut origins: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+        for step in self.applied.iter() {
+            let origin = match origins.remove(&step.from) {
+                Some(origin) => origin,
+                None => {
+                    order.push(step.from.clone());
+                    step.from.clone()
+                }
+            };
+            origins.insert(step.to.clone(), origin);
+        }
+
+        let mut current: HashMap<PathBuf, PathBuf> = origins
+            .into_iter()
+            .map(|(current, origin)| (origin, current))
+            .collect();
+
+        order
+            .into_iter()
+            .filter_map(|origin| current.remove(&origin).map(|path| (origin, path)))
+            .filter(|(origin, path)| origin != path)
+            .collect()
+    }
+
+    /// Every file that is renamed, with the size and modification time it
+    /// has now, so the renames can be undone with `undo_renames`
+    ///
+    /// **NOTE:** Call it right after `execute`, before the files can change
+    pub fn recorded(&self) -> Vec<RecordedRename> {
+        self.renamed()
+            .into_iter()
+            .map(|(old_path, new_path)| RecordedRename::capture(old_path, new_path))
+            .collect()
+    }
+}
+
+/// Renames every file of a `RenamePlan` on disk
+///
+/// **NOTE:** The plan is checked with `ensure_no_conflicts` first, and the
+/// renames are run in the order given by `order_steps`, so swaps and
+/// rotations of names never overwrite a file
+///
+/// **NOTE:** Every completed rename is recorded in a `Journal`. When a
+/// rename fails, the completed renames are undone in reverse order
+/// according to `on_failure`, and a *Warning* response is returned with
+/// the failure in the `ExecutionReport`
+///
+/// # Arguments:
+/// - `plan`: `&RenamePlan` - The renames to run
+/// - `on_failure`: `OnFailure` - Whether to undo the completed renames when one fails
+///
+/// # Example:
+/// ```
+/// use rs_rename::{execute, ExecutionReport, OnFailure, RenamePlan};
+/// use rs_response::ResponseWithData;
+///
+/// fn rename_files(plan: &RenamePlan) -> ResponseWithData<ExecutionReport> {
+///   execute(plan, OnFailure::Rollback)
+/// }
+/// ```
+pub fn execute(plan: &RenamePlan, on_failure: OnFailure) -> ResponseWithData<ExecutionReport> {
+    ensure_no_conflicts(plan)?;
+
+    let steps = order_steps(plan)?;
+    let sources: HashSet<PathBuf> = plan.changed().map(|item| item.source.clone()).collect();
+    let targets: HashSet<PathBuf> = plan.changed().map(|item| item.target()).collect();
+
+    let mut journal = Journal::new();
+    // The number of completed steps after which no file is at a temporary name
+    let mut settled = 0;
+    let mut temporary: usize = 0;
+    let mut failure = None;
+
+    for step in steps.iter() {
+        if let Err(e) = journal.rename(&step.from, &step.to) {
+            failure = Some(e);
+            break;
+        }
+
+        // Steps into a temporary name have no target in the plan, and
+        // steps out of one have no source in the plan
+        if !targets.contains(&step.to) {
+            temporary += 1;
+        }
+        if !sources.contains(&step.from) {
+            temporary -= 1;
+        }
+        if temporary == 0 {
+            settled = journal.entries().len();
+        }
+    }
+
+    let failure = match failure {
+        None => {
+            let report = ExecutionReport {
+                applied: to_steps(journal.entries()),
+                ..Default::default()
+            };
+            let message = format!("{} files have been renamed", plan.changed().count());
+
+            return Ok(OkDataResponse::new_info("Rename", message, report));
+        }
+        Some(failure) => failure,
+    };
+
+    let keep = match on_failure {
+        OnFailure::Rollback => 0,
+        OnFailure::KeepPartial => settled,
+    };
+    let (undone, rollback_failures) = journal.rollback(keep);
+
+    let report = ExecutionReport {
+        applied: to_steps(journal.entries()),
+        rolled_back: to_steps(&undone),
+        failure: Some(failure),
+        rollback_failures,
+    };
+
+    Ok(failure_response(&report).add_data(report))
+}
+
+fn failure_response(report: &ExecutionReport) -> OkResponse {
+    let cause = report
+        .failure
+        .as_ref()
+        .map(|e| format!("{}: {}", e.message, e.cause))
+        .unwrap_or_default();
+
+    let message = match (report.rollback_failures.len(), report.applied.len()) {
+        (0, 0) => String::from("The rename failed and every file has been restored"),
+        (0, applied) => format!(
+            "The rename failed after {} files were renamed. They have been kept",
+            applied
+        ),
+        (failed, _) => format!(
+            "The rename failed and {} files could not be restored",
+            failed
+        ),
+    };
+
+    create_warning(message, cause, ERR_SRC)
+}
+
+fn to_steps(entries: &[JournalEntry]) -> Vec<RenameStep> {
+    entries
+        .iter()
+        .map(|entry| RenameStep {
+            from: entry.from.clone(),
+            to: entry.to.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlannedRename;
+    use std::fs;
+    use std::path::Path;
+
+    fn temp_folder(name: &str, files: &[&str]) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("rs_rename-execute-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        for file in files {
+            fs::write(folder.join(file), file).unwrap();
+        }
+        folder
+    }
+
+    fn run(folder: &Path, renames: &[(&str, &str)], on_failure: OnFailure) -> ExecutionReport {
+        let plan = RenamePlan::new(
+            renames
+                .iter()
+                .map(|(from, to)| PlannedRename::new(folder.join(from), *to))
+                .collect(),
+        );
+
+        match execute(&plan, on_failure).unwrap() {
+            OkDataResponse::INFOData(info) => info.data,
+            OkDataResponse::WARNData(warning) => warning.data,
+        }
+    }
+
+    /// The file names in the folder, with their contents
+    fn listing(folder: &Path) -> Vec<(String, String)> {
+        let mut files: Vec<(String, String)> = fs::read_dir(folder)
+            .unwrap()
+            .flatten()
+            .map(|entry| {
+                (
+                    entry.file_name().to_string_lossy().to_string(),
+                    fs::read_to_string(entry.path()).unwrap(),
+                )
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn names(steps: &[RenameStep], folder: &Path) -> Vec<(String, String)> {
+        let relative = |path: &PathBuf| {
+            path.strip_prefix(folder)
+                .unwrap()
+                .to_string_lossy()
+                .to_string()
+        };
+        steps
+            .iter()
+            .map(|step| (relative(&step.from), relative(&step.to)))
+            .collect()
+    }
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn renames_a_batch_with_a_swap() {
+        let folder = temp_folder("swap", &["a", "b", "c"]);
+        let report = run(
+            &folder,
+            &[("a", "b"), ("b", "a"), ("c", "d")],
+            OnFailure::Rollback,
+        );
+        let files = listing(&folder);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert!(report.failure.is_none());
+        assert_eq!(report.applied.len(), 4);
+        assert_eq!(files, pairs(&[("a", "b"), ("b", "a"), ("d", "c")]));
+        assert_eq!(
+            report.renamed(),
+            [
+                (folder.join("c"), folder.join("d")),
+                (folder.join("a"), folder.join("b")),
+                (folder.join("b"), folder.join("a")),
+            ]
+        );
+    }
+
+    #[test]
+    fn rolls_back_in_reverse_order() {
+        let folder = temp_folder("rollback", &["a", "c"]);
+        let report = run(
+            &folder,
+            &[("a", "a2"), ("missing", "m2"), ("c", "c2")],
+            OnFailure::Rollback,
+        );
+        let files = listing(&folder);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert!(report.failure.is_some());
+        assert!(report.rollback_failures.is_empty());
+        assert_eq!(report.applied, []);
+        assert_eq!(names(&report.rolled_back, &folder), pairs(&[("a", "a2")]));
+        assert_eq!(files, pairs(&[("a", "a"), ("c", "c")]));
+    }
+
+    #[test]
+    fn rolls_back_everything_after_a_failure_in_a_cycle() {
+        let folder = temp_folder("cycle", &["a", "c"]);
+        // 'c' is renamed first, then 'a' is moved to a temporary name to
+        // make room for 'b', which fails
+        let report = run(
+            &folder,
+            &[("a", "b"), ("b", "a"), ("c", "c2")],
+            OnFailure::Rollback,
+        );
+        let files = listing(&folder);
+        fs::remove_dir_all(&folder).unwrap();
+
+        let rolled_back = names(&report.rolled_back, &folder);
+        assert_eq!(report.applied, []);
+        assert_eq!(rolled_back.len(), 2);
+        assert_eq!(rolled_back[0].0, "a");
+        assert_eq!(rolled_back[1], (String::from("c"), String::from("c2")));
+        assert_eq!(files, pairs(&[("a", "a"), ("c", "c")]));
+    }
+
+    #[test]
+    fn keeps_settled_renames_and_restores_temporary_names() {
+        let folder = temp_folder("partial", &["a", "c"]);
+        let report = run(
+            &folder,
+            &[("a", "b"), ("b", "a"), ("c", "c2")],
+            OnFailure::KeepPartial,
+        );
+        let files = listing(&folder);
+        fs::remove_dir_all(&folder).unwrap();
+
+        // The rename of 'c' is kept, while 'a' leaves its temporary name
+        assert_eq!(names(&report.applied, &folder), pairs(&[("c", "c2")]));
+        assert_eq!(names(&report.rolled_back, &folder).len(), 1);
+        assert_eq!(report.rolled_back[0].from, folder.join("a"));
+        assert_eq!(report.renamed(), [(folder.join("c"), folder.join("c2"))]);
+        assert_eq!(files, pairs(&[("a", "a"), ("c2", "c")]));
+    }
+
+    #[test]
+    fn refuses_conflicting_plans() {
+        let folder = temp_folder("conflict", &["a", "b"]);
+        let plan = RenamePlan::new(vec![PlannedRename::new(folder.join("a"), "b")]);
+        let result = execute(&plan, OnFailure::Rollback);
+        let files = listing(&folder);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(files, pairs(&[("a", "a"), ("b", "b")]));
+    }
+}
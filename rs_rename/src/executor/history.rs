@@ -0,0 +1,306 @@
+use super::{execute, undo_renames, ExecutionReport, OnFailure, RecordedRename};
+use crate::error_factory::{create_error, create_warning};
+use crate::pipeline::RulePipeline;
+use crate::plan::RenamePlan;
+use rs_db::history_db::{HistoryBatch, HistoryRename};
+use rs_db::DbManager;
+use rs_response::{OkDataResponse, ResponseWithData};
+use std::path::Path;
+
+const ERR_SRC: &str = "executor::history";
+
+impl From<HistoryRename> for RecordedRename {
+    fn from(rename: HistoryRename) -> Self {
+        Self {
+            old_path: rename.old_path,
+            new_path: rename.new_path,
+            size: rename.size,
+            modified: rename.modified,
+        }
+    }
+}
+
+impl From<RecordedRename> for HistoryRename {
+    fn from(rename: RecordedRename) -> Self {
+        Self {
+            old_path: rename.old_path,
+            new_path: rename.new_path,
+            size: rename.size,
+            modified: rename.modified,
+        }
+    }
+}
+
+/// Renames every file of a `RenamePlan` with `execute`, and records the
+/// renamed files as a batch in the 'History' database
+///
+/// **NOTE:** Nothing is recorded when no file was renamed. When some
+/// files were kept after a failure (`OnFailure::KeepPartial`), only they
+/// are recorded
+///
+/// **NOTE:** The files stay renamed when the batch cannot be recorded, and
+/// a *Warning* response is returned with the report
+///
+/// # Arguments:
+/// - `db_manager`: `&DbManager` - The shared database connections
+/// - `root`: `&Path` - The folder the batch was run on
+/// - `pipeline`: `&RulePipeline` - The rules that created the plan
+/// - `plan`: `&RenamePlan` - The renames to run
+/// - `on_failure`: `OnFailure` - Whether to undo the completed renames when one fails
+///
+/// # Example:
+/// ```
+/// use rs_db::DbManager;
+/// use rs_rename::{execute_and_record, ExecutionReport, OnFailure, RenamePlan, RulePipeline};
+/// use rs_response::ResponseWithData;
+///
+/// fn rename_photos(
+///   db_manager: &DbManager,
+///   pipeline: &RulePipeline,
+///   plan: &RenamePlan,
+/// ) -> ResponseWithData<ExecutionReport> {
+///   execute_and_record(db_manager, "photos".as_ref(), pipeline, plan, OnFailure::Rollback)
+/// }
+/// ```
+pub fn execute_and_record(
+    db_manager: &DbManager,
+    root: &Path,
+    pipeline: &RulePipeline,
+    plan: &RenamePlan,
+    on_failure: OnFailure,
+) -> ResponseWithData<ExecutionReport> {
+    let response = execute(plan, on_failure)?;
+
+    let recorded = match &response {
+        OkDataResponse::INFOData(info) => info.data.recorded(),
+        OkDataResponse::WARNData(warning) => warning.data.recorded(),
+    };
+    if recorded.is_empty() {
+        return Ok(response);
+    }
+
+    let renames: Vec<HistoryRename> = recorded.into_iter().map(HistoryRename::from).collect();
+    if let Err(e) = HistoryBatch::record(db_manager, root, &pipeline.describe(), &renames) {
+        let report = match response {
+            OkDataResponse::INFOData(info) => info.data,
+            OkDataResponse::WARNData(warning) => warning.data,
+        };
+
+        return Ok(create_warning(
+            "The files have been renamed, but the batch could not be saved in the history",
+            format!("{}: {}", e.message, e.cause),
+            ERR_SRC,
+        )
+        .add_data(report));
+    }
+
+    Ok(response)
+}
+
+/// Reverts a batch recorded in the 'History' database
+///
+/// **NOTE:** The files are renamed back with `undo_renames`, so every file
+/// must still be where the batch left it, otherwise nothing is reverted
+///
+/// **NOTE:** The batch is only marked as undone when every file was reverted
+///
+/// # Arguments:
+/// - `db_manager`: `&DbManager` - The shared database connections
+/// - `id`: `i64` - The id of the batch
+///
+/// # Example:
+/// ```
+/// use rs_db::DbManager;
+/// use rs_rename::{undo_batch, ExecutionReport};
+/// use rs_response::ResponseWithData;
+///
+/// fn undo_rename(db_manager: &DbManager, id: i64) -> ResponseWithData<ExecutionReport> {
+///   undo_batch(db_manager, id)
+/// }
+/// ```
+pub fn undo_batch(db_manager: &DbManager, id: i64) -> ResponseWithData<ExecutionReport> {
+    let message = format!("Unable to undo rename batch #{}", id);
+
+    let batch = HistoryBatch::read(db_manager, id)?.ok_or_else(|| {
+        create_error(
+            message.clone(),
+            "The batch was not found in the history",
+            ERR_SRC,
+        )
+    })?;
+
+    if batch.undone {
+        return Err(create_error(
+            message,
+            "The batch has already been undone",
+            ERR_SRC,
+        ));
+    }
+
+    let renames: Vec<RecordedRename> = batch
+        .renames
+        .into_iter()
+        .map(RecordedRename::from)
+        .collect();
+
+    let report =
+        match undo_renames(&renames).map_err(|e| create_error(message, e.cause, ERR_SRC))? {
+            OkDataResponse::INFOData(info) => info.data,
+            warning => return Ok(warning),
+        };
+
+    HistoryBatch::mark_undone(db_manager, id)?;
+
+    Ok(OkDataResponse::new_info(
+        "Rename",
+        format!("Rename batch #{} has been undone", id),
+        report,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlannedRename;
+    use crate::rules::{CaseOptions, CaseRule};
+    use rs_db::history_db::initialize_history_db;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("rs_rename-history-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        for file in ["A.txt", "B.txt"] {
+            fs::write(folder.join(file), file).unwrap();
+        }
+        folder
+    }
+
+    /// Renames the files of the folder to lowercase and records the batch
+    fn run_batch(db_manager: &DbManager, folder: &Path) -> i64 {
+        let mut pipeline = RulePipeline::new();
+        pipeline.push(CaseRule::new(CaseOptions::default()));
+        let plan = RenamePlan::new(vec![
+            PlannedRename::new(folder.join("A.txt"), "a.txt"),
+            PlannedRename::new(folder.join("B.txt"), "b.txt"),
+        ]);
+
+        let response =
+            execute_and_record(db_manager, folder, &pipeline, &plan, OnFailure::Rollback).unwrap();
+        assert!(matches!(response, OkDataResponse::INFOData(_)));
+
+        let batch = HistoryBatch::list(db_manager, 1).unwrap().pop().unwrap();
+        assert_eq!(batch.rename_count, 2);
+        assert_eq!(batch.pipeline, pipeline.describe());
+        batch.id
+    }
+
+    fn database() -> DbManager {
+        let db_manager = DbManager::in_memory().unwrap();
+        initialize_history_db(&db_manager).unwrap();
+        db_manager
+    }
+
+    fn file_names(folder: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(folder)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn undoes_a_recorded_batch() {
+        let db_manager = database();
+        let folder = temp_folder("undo");
+        let id = run_batch(&db_manager, &folder);
+        let renamed = file_names(&folder);
+
+        let response = undo_batch(&db_manager, id).unwrap();
+        let reverted = file_names(&folder);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(renamed, ["a.txt", "b.txt"]);
+        assert_eq!(reverted, ["A.txt", "B.txt"]);
+        assert!(matches!(response, OkDataResponse::INFOData(_)));
+        assert!(HistoryBatch::read(&db_manager, id).unwrap().unwrap().undone);
+    }
+
+    #[test]
+    fn refuses_a_batch_whose_files_moved() {
+        let db_manager = database();
+        let folder = temp_folder("moved");
+        let id = run_batch(&db_manager, &folder);
+        fs::rename(folder.join("b.txt"), folder.join("elsewhere.txt")).unwrap();
+
+        let error = undo_batch(&db_manager, id).err().unwrap();
+        let names = file_names(&folder);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(
+            error.message,
+            format!("Unable to undo rename batch #{}", id)
+        );
+        assert!(error.cause.contains("no longer where the batch left them"));
+        // Nothing is reverted, and the batch can still be undone later
+        assert_eq!(names, ["a.txt", "elsewhere.txt"]);
+        assert!(!HistoryBatch::read(&db_manager, id).unwrap().unwrap().undone);
+    }
+
+    #[test]
+    fn refuses_batches_that_are_undone_or_unknown() {
+        let db_manager = database();
+        let folder = temp_folder("twice");
+        let id = run_batch(&db_manager, &folder);
+
+        let first = undo_batch(&db_manager, id);
+        let second = undo_batch(&db_manager, id);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert!(first.is_ok());
+        assert_eq!(
+            second.err().unwrap().cause,
+            "The batch has already been undone"
+        );
+        assert_eq!(
+            undo_batch(&db_manager, id + 1).err().unwrap().cause,
+            "The batch was not found in the history"
+        );
+    }
+
+    #[test]
+    fn records_nothing_when_no_file_is_renamed() {
+        let db_manager = database();
+        let folder = temp_folder("unchanged");
+        let plan = RenamePlan::new(vec![PlannedRename::new(folder.join("A.txt"), "A.txt")]);
+
+        let response = execute_and_record(
+            &db_manager,
+            &folder,
+            &RulePipeline::new(),
+            &plan,
+            OnFailure::Rollback,
+        );
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert!(response.is_ok());
+        assert!(HistoryBatch::list(&db_manager, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn converts_recorded_renames() {
+        let recorded = RecordedRename {
+            old_path: PathBuf::from("a"),
+            new_path: PathBuf::from("b"),
+            size: Some(3),
+            modified: None,
+        };
+
+        let history = HistoryRename::from(recorded.clone());
+        assert_eq!(RecordedRename::from(history), recorded);
+    }
+}
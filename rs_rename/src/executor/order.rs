@@ -0,0 +1,313 @@
+use crate::error_factory::create_error;
+use crate::plan::RenamePlan;
+use rs_response::DataResponse;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ERR_SRC: &str = "executor::order::order_steps()";
+
+/// A single move on disk
+///
+/// # Properties:
+/// - `from`: `PathBuf` - The path the file is moved from
+/// - `to`: `PathBuf` - The path the file is moved to
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RenameStep {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Orders the renames of a `RenamePlan` so that no file is ever moved
+/// onto a path that is still in use
+///
+/// A rename whose target is the source of another rename waits for that
+/// rename to happen first. Cycles, such as `a -> b` and `b -> a`, are
+/// broken by first moving one file to a unique temporary name in the same
+/// folder
+///
+/// **NOTE:** Paths in folders on case-insensitive volumes are compared
+/// ignoring letter case, so a change of letter case only (`a.jpg -> A.jpg`)
+/// also goes through a temporary name there. On case-sensitive volumes,
+/// `A.txt` and `a.txt` are different files
+///
+/// **NOTE:** The plan is expected to be free of conflicts, see `ensure_no_conflicts`
+///
+/// # Arguments:
+/// - `plan`: `&RenamePlan` - The renames to order
+///
+/// # Example:
+/// ```
+/// use rs_rename::{order_steps, PlannedRename, RenamePlan};
+///
+/// let plan = RenamePlan::new(vec![
+///   PlannedRename::new("a.txt", "b.txt"),
+///   PlannedRename::new("b.txt", "c.txt"),
+/// ]);
+///
+/// let steps = order_steps(&plan).unwrap();
+///
+/// // 'b.txt' is moved out of the way before 'a.txt' takes its name
+/// assert_eq!(steps[0].from.to_str(), Some("b.txt"));
+/// assert_eq!(steps[1].from.to_str(), Some("a.txt"));
+/// ```
+pub fn order_steps(plan: &RenamePlan) -> DataResponse<Vec<RenameStep>> {
+    let moves: Vec<(PathBuf, PathBuf)> = plan
+        .changed()
+        .map(|item| (item.source.clone(), item.target()))
+        .collect();
+
+    // `waits_for[i] = j` when move `i` targets the source of move `j`
+    let mut case_insensitive = HashMap::new();
+    let by_source: HashMap<PathBuf, usize> = moves
+        .iter()
+        .enumerate()
+        .map(|(idx, (source, _))| (path_key(source, &mut case_insensitive), idx))
+        .collect();
+    let waits_for: Vec<Option<usize>> = moves
+        .iter()
+        .map(|(_, target)| {
+            by_source
+                .get(&path_key(target, &mut case_insensitive))
+                .copied()
+        })
+        .collect();
+
+    // Targets are unique, so at most one move waits for each move
+    let mut unblocks: Vec<Option<usize>> = vec![None; moves.len()];
+    for (idx, waits) in waits_for.iter().enumerate() {
+        if let Some(&other) = waits.as_ref() {
+            if other != idx {
+                if unblocks[other].is_some() {
+                    return Err(create_error(
+                        "Unable to order the renames",
+                        format!(
+                            "More than one file would be renamed to '{}'",
+                            moves[other].0.display()
+                        ),
+                        ERR_SRC,
+                    ));
+                }
+                unblocks[other] = Some(idx);
+            }
+        }
+    }
+
+    let mut steps = Vec::with_capacity(moves.len());
+    let mut done = vec![false; moves.len()];
+
+    // Chains: start with the moves whose target is free
+    for (start, waits) in waits_for.iter().enumerate() {
+        if waits.is_some() {
+            continue;
+        }
+
+        let mut current = Some(start);
+        while let Some(idx) = current {
+            let (from, to) = &moves[idx];
+            steps.push(RenameStep {
+                from: from.clone(),
+                to: to.clone(),
+            });
+            done[idx] = true;
+            current = unblocks[idx];
+        }
+    }
+
+    // Cycles: what is left only waits on itself, one move at a time
+    let mut reserved = HashSet::new();
+    for start in 0..moves.len() {
+        if done[start] {
+            continue;
+        }
+
+        let (from, to) = &moves[start];
+        let temp = temp_path(from, &mut reserved)?;
+        steps.push(RenameStep {
+            from: from.clone(),
+            to: temp.clone(),
+        });
+        done[start] = true;
+
+        let mut current = unblocks[start];
+        while let Some(idx) = current.filter(|&idx| !done[idx]) {
+            let (from, to) = &moves[idx];
+            steps.push(RenameStep {
+                from: from.clone(),
+                to: to.clone(),
+            });
+            done[idx] = true;
+            current = unblocks[idx];
+        }
+
+        steps.push(RenameStep {
+            from: temp,
+            to: to.clone(),
+        });
+    }
+
+    Ok(steps)
+}
+
+/// A path in the same folder as `path` that does not exist yet
+fn temp_path(path: &Path, reserved: &mut HashSet<PathBuf>) -> DataResponse<PathBuf> {
+    let folder = path.parent().unwrap_or_else(|| Path::new(""));
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    for attempt in 0..1000 {
+        let candidate = folder.join(format!(
+            ".{}.renaming-{}-{}",
+            name,
+            std::process::id(),
+            attempt
+        ));
+
+        if fs::symlink_metadata(&candidate).is_err() && reserved.insert(candidate.clone()) {
+            return Ok(candidate);
+        }
+    }
+
+    Err(create_error(
+        "Unable to order the renames",
+        format!("No temporary name is available for '{}'", path.display()),
+        ERR_SRC,
+    ))
+}
+
+/// The path used to find which moves wait for each other, folded to
+/// lowercase when its folder ignores letter case
+fn path_key(path: &Path, case_insensitive: &mut HashMap<PathBuf, bool>) -> PathBuf {
+    let folder = path.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+    let fold = *case_insensitive
+        .entry(folder)
+        .or_insert_with_key(|folder| is_case_insensitive(folder));
+
+    match fold {
+        true => PathBuf::from(path.to_string_lossy().to_lowercase()),
+        false => path.to_path_buf(),
+    }
+}
+
+/// Whether a folder is on a case-insensitive volume, found by looking up one
+/// of its files with the letter case of its name swapped
+///
+/// **NOTE:** When the folder cannot be read or has no file with letters in
+/// its name, the usual behavior of the platform is assumed
+fn is_case_insensitive(folder: &Path) -> bool {
+    let default = cfg!(any(windows, target_os = "macos"));
+    let dir = match folder.as_os_str().is_empty() {
+        true => Path::new("."),
+        false => folder,
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return default,
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let swapped = swap_case(&name);
+        if swapped == name {
+            continue;
+        }
+
+        let original = match fs::symlink_metadata(entry.path()) {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        return match fs::symlink_metadata(dir.join(&swapped)) {
+            Ok(metadata) => is_same_file(&original, &metadata),
+            Err(_) => false,
+        };
+    }
+
+    default
+}
+
+fn swap_case(name: &str) -> String {
+    name.chars()
+        .flat_map(|c| match c.is_lowercase() {
+            true => c.to_uppercase().collect::<Vec<_>>(),
+            false => c.to_lowercase().collect::<Vec<_>>(),
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &fs::Metadata, b: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+/// Both names reached a file, which on these platforms means the volume
+/// ignores letter case
+#[cfg(not(unix))]
+fn is_same_file(_a: &fs::Metadata, _b: &fs::Metadata) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlannedRename;
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("rs_rename-order-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    #[test]
+    fn orders_a_chain() {
+        let plan = RenamePlan::new(vec![
+            PlannedRename::new("a.txt", "b.txt"),
+            PlannedRename::new("b.txt", "c.txt"),
+        ]);
+
+        let steps = order_steps(&plan).unwrap();
+
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].from, PathBuf::from("b.txt"));
+        assert_eq!(steps[1].from, PathBuf::from("a.txt"));
+    }
+
+    #[test]
+    fn breaks_a_swap_with_a_temporary_name() {
+        let plan = RenamePlan::new(vec![
+            PlannedRename::new("a.txt", "b.txt"),
+            PlannedRename::new("b.txt", "a.txt"),
+        ]);
+
+        let steps = order_steps(&plan).unwrap();
+
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[2].to, PathBuf::from("b.txt"));
+    }
+
+    #[test]
+    fn keeps_case_variants_apart_on_case_sensitive_volumes() {
+        let folder = temp_folder("case");
+        fs::write(folder.join("A.txt"), "upper").unwrap();
+        fs::write(folder.join("a.txt"), "lower").unwrap();
+        let case_sensitive = fs::read_to_string(folder.join("A.txt")).unwrap() == "upper"
+            && fs::read_to_string(folder.join("a.txt")).unwrap() == "lower";
+
+        let plan = RenamePlan::new(vec![
+            PlannedRename::new(folder.join("A.txt"), "b.txt"),
+            PlannedRename::new(folder.join("a.txt"), "A.txt"),
+        ]);
+        let steps = order_steps(&plan).unwrap();
+        fs::remove_dir_all(&folder).unwrap();
+
+        if case_sensitive {
+            // `A.txt` is moved away first, with no temporary name
+            assert_eq!(steps.len(), 2);
+            assert_eq!(steps[0].from, folder.join("A.txt"));
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use super::{execute, ExecutionReport, OnFailure};
+use crate::error_factory::create_error;
+use crate::plan::{PlannedRename, RenamePlan};
+use rs_response::ResponseWithData;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const ERR_SRC: &str = "executor::undo::undo_renames()";
+
+/// A renamed file, along with the size and modification time it had right
+/// after the rename, so it can be recognized when it is renamed back
+///
+/// # Properties:
+/// - `old_path`: `PathBuf` - The path of the file before the rename
+/// - `new_path`: `PathBuf` - The path of the file after the rename
+/// - `size`: `Option<u64>` - The size of the file after the rename, in bytes
+/// - `modified`: `Option<i64>` - The modification time of the file after the rename,
+///   in nanoseconds since the Unix epoch
+///
+/// **NOTE:** `size` and `modified` are `None` when they could not be read
+///
+/// # Methods:
+/// - `capture` - Creates a new `RecordedRename` from the file at its new path
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RecordedRename {
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+    pub size: Option<u64>,
+    pub modified: Option<i64>,
+}
+impl RecordedRename {
+    /// Creates a new `RecordedRename`, reading the size and modification
+    /// time of the file at its new path
+    ///
+    /// # Arguments:
+    /// - `old_path`: `impl Into<PathBuf>` - The path of the file before the rename
+    /// - `new_path`: `impl Into<PathBuf>` - The path of the file after the rename
+    pub fn capture(old_path: impl Into<PathBuf>, new_path: impl Into<PathBuf>) -> Self {
+        let new_path = new_path.into();
+        let (size, modified) = file_stamp(&new_path);
+
+        Self {
+            old_path: old_path.into(),
+            new_path,
+            size,
+            modified,
+        }
+    }
+}
+
+/// Renames files back to the paths they had before a batch
+///
+/// **NOTE:** Every file must still be where the batch left it, with the same
+/// size and modification time, otherwise nothing is renamed. The renames are
+/// run with `execute`, so they never overwrite a file and are rolled back if
+/// one fails
+///
+/// # Arguments:
+/// - `renames`: `&[RecordedRename]` - The files of the batch, see `ExecutionReport::recorded`
+///
+/// # Example:
+/// ```
+/// use rs_rename::{undo_renames, ExecutionReport, RecordedRename};
+/// use rs_response::ResponseWithData;
+///
+/// fn rename_and_undo(report: &ExecutionReport) -> ResponseWithData<ExecutionReport> {
+///   let recorded: Vec<RecordedRename> = report.recorded();
+///
+///   undo_renames(&recorded)
+/// }
+/// ```
+pub fn undo_renames(renames: &[RecordedRename]) -> ResponseWithData<ExecutionReport> {
+    let message = "Unable to undo the renames";
+
+    let mut missing = Vec::new();
+    let mut changed = Vec::new();
+    for rename in renames.iter() {
+        if fs::symlink_metadata(&rename.new_path).is_err() {
+            missing.push(format!("'{}'", rename.new_path.display()));
+            continue;
+        }
+
+        let (size, modified) = file_stamp(&rename.new_path);
+        let size_changed = rename.size.is_some() && rename.size != size;
+        let modified_changed = rename.modified.is_some() && rename.modified != modified;
+        if size_changed || modified_changed {
+            changed.push(format!("'{}'", rename.new_path.display()));
+        }
+    }
+
+    if !missing.is_empty() {
+        return Err(create_error(
+            message,
+            format!(
+                "{} files are no longer where the batch left them: {}",
+                missing.len(),
+                missing.join(", ")
+            ),
+            ERR_SRC,
+        ));
+    }
+    if !changed.is_empty() {
+        return Err(create_error(
+            message,
+            format!(
+                "{} files have changed since the batch: {}",
+                changed.len(),
+                changed.join(", ")
+            ),
+            ERR_SRC,
+        ));
+    }
+
+    let plan = RenamePlan::new(
+        renames
+            .iter()
+            .map(|rename| {
+                let old_name = rename
+                    .old_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+
+                PlannedRename::new(rename.new_path.clone(), old_name)
+            })
+            .collect(),
+    );
+
+    execute(&plan, OnFailure::Rollback)
+}
+
+/// The size and modification time of a file, without following links
+fn file_stamp(path: &Path) -> (Option<u64>, Option<i64>) {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) => {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .and_then(|elapsed| i64::try_from(elapsed.as_nanos()).ok());
+
+            (Some(metadata.len()), modified)
+        }
+        Err(_) => (None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rs_response::OkDataResponse;
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("rs_rename-undo-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    #[test]
+    fn renames_files_back() {
+        let folder = temp_folder("back");
+        fs::write(folder.join("b.txt"), "content").unwrap();
+        let recorded = vec![RecordedRename::capture(
+            folder.join("a.txt"),
+            folder.join("b.txt"),
+        )];
+
+        let response = undo_renames(&recorded).unwrap();
+
+        assert!(matches!(response, OkDataResponse::INFOData(_)));
+        assert!(folder.join("a.txt").exists());
+        assert!(!folder.join("b.txt").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn refuses_files_that_changed() {
+        let folder = temp_folder("changed");
+        fs::write(folder.join("b.txt"), "content").unwrap();
+        let recorded = vec![RecordedRename::capture(
+            folder.join("a.txt"),
+            folder.join("b.txt"),
+        )];
+        fs::write(folder.join("b.txt"), "another file").unwrap();
+
+        let error = undo_renames(&recorded).unwrap_err();
+
+        assert!(error.cause.contains("have changed"));
+        assert!(folder.join("b.txt").exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn refuses_missing_files() {
+        let folder = temp_folder("missing");
+        let recorded = vec![RecordedRename::capture(
+            folder.join("a.txt"),
+            folder.join("b.txt"),
+        )];
+
+        let error = undo_renames(&recorded).unwrap_err();
+
+        assert!(error.cause.contains("no longer where"));
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
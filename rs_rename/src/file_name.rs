@@ -0,0 +1,42 @@
+/// Splits a file name into its *stem* and *extension*
+///
+/// **NOTE:** A leading dot does not start an extension, so hidden
+/// files such as `.gitignore` are treated as a stem without an extension
+///
+/// # Arguments:
+/// - `name`: `&str` - The file name to split
+///
+/// # Example:
+/// ```
+/// use rs_rename::split_name;
+///
+/// assert_eq!(split_name("photo.final.jpg"), ("photo.final", Some("jpg")));
+/// assert_eq!(split_name(".gitignore"), (".gitignore", None));
+/// assert_eq!(split_name("README"), ("README", None));
+/// ```
+pub fn split_name(name: &str) -> (&str, Option<&str>) {
+    match name.rfind('.') {
+        Some(0) | None => (name, None),
+        Some(idx) => (&name[..idx], Some(&name[idx + 1..])),
+    }
+}
+
+/// Joins a *stem* and an optional *extension* into a file name
+///
+/// # Arguments:
+/// - `stem`: `&str` - The file name without its extension
+/// - `extension`: `Option<&str>` - The extension, without the leading dot
+///
+/// # Example:
+/// ```
+/// use rs_rename::join_name;
+///
+/// assert_eq!(join_name("photo", Some("jpg")), "photo.jpg");
+/// assert_eq!(join_name("README", None), "README");
+/// ```
+pub fn join_name(stem: &str, extension: Option<&str>) -> String {
+    match extension {
+        Some(ext) if !ext.is_empty() => format!("{}.{}", stem, ext),
+        _ => stem.to_string(),
+    }
+}
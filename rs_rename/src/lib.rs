@@ -1 +1,21 @@
+mod conflicts;
+mod error_factory;
+mod executor;
+mod file_name;
+mod pipeline;
+mod plan;
+mod preview;
+mod rule;
+pub mod rules;
+pub mod template;
 
+pub use conflicts::{ensure_no_conflicts, find_conflicts, Conflict, ConflictKind};
+pub use executor::{
+    execute, execute_and_record, order_steps, undo_batch, undo_renames, ExecutionReport, OnFailure,
+    RecordedRename, RenameStep,
+};
+pub use file_name::{join_name, split_name};
+pub use pipeline::RulePipeline;
+pub use plan::{PlannedRename, RenamePlan};
+pub use preview::{preview, PreviewItem, PreviewStatus};
+pub use rule::{Rule, RuleContext};
@@ -0,0 +1,202 @@
+use crate::error_factory::create_error;
+use crate::plan::{PlannedRename, RenamePlan};
+use crate::rule::{Rule, RuleContext};
+use rs_response::{DataResponse, OkDataResponse, ResponseWithData};
+use std::path::{Path, PathBuf};
+
+const ERR_SRC: &str = "pipeline::RulePipeline";
+
+/// An ordered list of `Rule`s that every file name is run through in turn
+///
+/// # Methods:
+/// - `new` - Creates an empty `RulePipeline`
+/// - `push` - Appends a `Rule` to the end of the pipeline
+/// - `describe` - A summary of every rule in the pipeline, in order
+/// - `apply` - Runs a single file name through every rule
+/// - `apply_with_notes` - Runs a single file name through every rule, with the notes of the rules
+/// - `plan` - Runs a batch of files through every rule and creates a `RenamePlan`
+///
+/// # Example:
+/// ```
+/// use rs_rename::{Rule, RuleContext, RulePipeline};
+/// use rs_response::DataResponse;
+/// use std::path::PathBuf;
+///
+/// struct Lowercase;
+///
+/// impl Rule for Lowercase {
+///   fn describe(&self) -> String {
+///     String::from("Lowercase")
+///   }
+///
+///   fn apply(&self, name: &str, _ctx: &RuleContext) -> DataResponse<String> {
+///     Ok(name.to_lowercase())
+///   }
+/// }
+///
+/// let mut pipeline = RulePipeline::new();
+/// pipeline.push(Lowercase);
+///
+/// let sources = vec![PathBuf::from("photos/IMG_0001.JPG")];
+/// let plan = pipeline.plan(&sources).unwrap();
+/// ```
+#[derive(Default)]
+pub struct RulePipeline {
+    rules: Vec<Box<dyn Rule>>,
+}
+impl RulePipeline {
+    /// Creates an empty `RulePipeline`
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Appends a `Rule` to the end of the pipeline
+    ///
+    /// # Arguments:
+    /// - `rule`: `impl Rule + 'static` - The rule to run after the current ones
+    pub fn push(&mut self, rule: impl Rule + 'static) -> &mut Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// The number of rules in the pipeline
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Whether the pipeline contains no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// A summary of every rule in the pipeline, in order
+    pub fn describe(&self) -> Vec<String> {
+        self.rules.iter().map(|rule| rule.describe()).collect()
+    }
+
+    /// Prepares every rule for a new batch
+    ///
+    /// **NOTE:** `plan` calls this automatically. It only needs to be
+    /// called before using `apply` directly
+    ///
+    /// # Arguments:
+    /// - `sources`: `&[PathBuf]` - The source paths of the whole batch
+    pub fn prepare(&mut self, sources: &[PathBuf]) -> DataResponse<()> {
+        for rule in self.rules.iter_mut() {
+            rule.prepare(sources)?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs a single file name through every rule and returns the new name
+    ///
+    /// **NOTE:** The resulting name is validated to ensure it is a single,
+    /// non-empty path component
+    ///
+    /// # Arguments:
+    /// - `path`: `&Path` - The current path of the file
+    /// - `index`: `usize` - The position of the file in the batch
+    pub fn apply(&self, path: &Path, index: usize) -> DataResponse<String> {
+        self.run(path, index, false).map(|(name, _)| name)
+    }
+
+    /// Runs a single file name through every rule and returns the new name,
+    /// along with what the rules noted about their changes
+    ///
+    /// **NOTE:** The notes are in rule order, see `Rule::note`
+    ///
+    /// # Arguments:
+    /// - `path`: `&Path` - The current path of the file
+    /// - `index`: `usize` - The position of the file in the batch
+    pub fn apply_with_notes(
+        &self,
+        path: &Path,
+        index: usize,
+    ) -> DataResponse<(String, Vec<String>)> {
+        self.run(path, index, true)
+    }
+
+    fn run(
+        &self,
+        path: &Path,
+        index: usize,
+        with_notes: bool,
+    ) -> DataResponse<(String, Vec<String>)> {
+        let mut name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => {
+                return Err(create_error(
+                    "Unable to rename the file",
+                    format!("'{}' does not have a file name", path.display()),
+                    ERR_SRC,
+                ))
+            }
+        };
+
+        let ctx = RuleContext { path, index };
+        let mut notes = Vec::new();
+        for rule in self.rules.iter() {
+            let new_name = rule.apply(&name, &ctx)?;
+            if with_notes {
+                notes.extend(rule.note(&name, &ctx));
+            }
+            name = new_name;
+        }
+
+        validate_name(&name, path)?;
+
+        Ok((name, notes))
+    }
+
+    /// Runs a batch of files through every rule and creates a `RenamePlan`
+    ///
+    /// **NOTE:** The batch stops at the first file a rule fails on
+    ///
+    /// # Arguments:
+    /// - `sources`: `&[PathBuf]` - The current paths of the files to rename
+    pub fn plan(&mut self, sources: &[PathBuf]) -> ResponseWithData<RenamePlan> {
+        self.prepare(sources)?;
+
+        let mut items = Vec::with_capacity(sources.len());
+        for (index, source) in sources.iter().enumerate() {
+            let new_name = self.apply(source, index)?;
+            items.push(PlannedRename::new(source.clone(), new_name));
+        }
+
+        let plan = RenamePlan::new(items);
+        let message = format!(
+            "{} of {} file names would change",
+            plan.changed().count(),
+            plan.len()
+        );
+
+        Ok(OkDataResponse::new_info("Rename", message, plan))
+    }
+}
+
+fn validate_name(name: &str, path: &Path) -> DataResponse<()> {
+    let message = format!("Invalid file name for '{}'", path.display());
+
+    if name.is_empty() {
+        return Err(create_error(message, "The new file name is empty", ERR_SRC));
+    }
+
+    if name == "." || name == ".." {
+        return Err(create_error(
+            message,
+            format!("'{}' is not a valid file name", name),
+            ERR_SRC,
+        ));
+    }
+
+    if name.contains('/') || name.contains('\\') || name.contains('\0') {
+        return Err(create_error(
+            message,
+            format!("'{}' contains a path separator or null character", name),
+            ERR_SRC,
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+/// A single entry of a `RenamePlan`
+///
+/// # Properties:
+/// - `source`: `PathBuf` - The current path of the file
+/// - `new_name`: `String` - The proposed file name
+///
+/// # Methods:
+/// - `old_name` - The current file name
+/// - `target` - The path the file would be renamed to
+/// - `is_unchanged` - Whether the proposed file name matches the current one
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PlannedRename {
+    pub source: PathBuf,
+    pub new_name: String,
+}
+impl PlannedRename {
+    /// Creates a new `PlannedRename` item
+    ///
+    /// # Arguments:
+    /// - `source`: `impl Into<PathBuf>` - The current path of the file
+    /// - `new_name`: `impl Into<String>` - The proposed file name
+    pub fn new(source: impl Into<PathBuf>, new_name: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            new_name: new_name.into(),
+        }
+    }
+
+    /// The current file name
+    pub fn old_name(&self) -> String {
+        self.source
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    /// The path the file would be renamed to
+    pub fn target(&self) -> PathBuf {
+        self.source
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(&self.new_name)
+    }
+
+    /// Whether the proposed file name matches the current one
+    pub fn is_unchanged(&self) -> bool {
+        self.old_name() == self.new_name
+    }
+}
+
+/// The output of a `RulePipeline`, mapping every source path to its
+/// proposed new name
+///
+/// **NOTE:** Entries keep the order in which the sources were given
+///
+/// # Properties:
+/// - `items`: `Vec<PlannedRename>` - The proposed renames
+///
+/// # Methods:
+/// - `len` - The number of files in the plan
+/// - `is_empty` - Whether the plan contains no files
+/// - `changed` - An iterator over the entries whose name would change
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct RenamePlan {
+    pub items: Vec<PlannedRename>,
+}
+impl RenamePlan {
+    /// Creates a new `RenamePlan` item
+    pub fn new(items: Vec<PlannedRename>) -> Self {
+        Self { items }
+    }
+
+    /// The number of files in the plan
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the plan contains no files
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// An iterator over the entries whose name would change
+    pub fn changed(&self) -> impl Iterator<Item = &PlannedRename> {
+        self.items.iter().filter(|item| !item.is_unchanged())
+    }
+}
@@ -0,0 +1,247 @@
+use crate::conflicts::find_conflicts;
+use crate::error_factory::create_warning;
+use crate::pipeline::RulePipeline;
+use crate::plan::{PlannedRename, RenamePlan};
+use rs_fs::FileEntry;
+use rs_response::{OkDataResponse, OkResponse, ResponseVecWithData};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const ERR_SRC: &str = "preview::preview()";
+
+/// The outcome of a file in a `preview`
+///
+/// - `Unchanged`: The new name is the same as the old name
+/// - `Renamed`: The file would be renamed
+/// - `Invalid`: A rule failed or produced an invalid name
+/// - `Conflicting`: The new name could overwrite another file, see `find_conflicts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum PreviewStatus {
+    Unchanged,
+    Renamed,
+    Invalid,
+    Conflicting,
+}
+
+/// A single row of a `preview`
+///
+/// # Properties:
+/// - `source`: `PathBuf` - The current path of the file
+/// - `old_name`: `String` - The current file name
+/// - `new_name`: `String` - The proposed file name. Same as `old_name` when `Invalid`
+/// - `status`: `PreviewStatus` - The outcome of the file
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PreviewItem {
+    pub source: PathBuf,
+    pub old_name: String,
+    pub new_name: String,
+    pub status: PreviewStatus,
+}
+
+/// Runs scanned files through a `RulePipeline` without touching the disk
+///
+/// **NOTE:** Every file gets its own response. `Unchanged` and `Renamed`
+/// files are *Info* responses, while `Invalid` and `Conflicting` files are
+/// *Warning* responses with the reason in their `cause`. `Unchanged` and
+/// `Renamed` files are *Warning* responses too when a rule noted a change,
+/// such as a corrected extension, with the notes in their `cause`
+///
+/// **NOTE:** Only a rule that fails to prepare for the batch fails the whole preview
+///
+/// # Arguments:
+/// - `entries`: `&[FileEntry]` - The scanned files, in batch order
+/// - `pipeline`: `&mut RulePipeline` - The rules to run each file name through
+///
+/// # Example:
+/// ```
+/// use rs_fs::{scan, ScanOptions};
+/// use rs_rename::rules::{CaseOptions, CaseRule};
+/// use rs_rename::{preview, PreviewItem, RulePipeline};
+/// use rs_response::ResponseVecWithData;
+///
+/// fn preview_lowercase(folder: &str) -> ResponseVecWithData<PreviewItem> {
+///   let results = scan(&ScanOptions::new(folder))?;
+///
+///   let mut pipeline = RulePipeline::new();
+///   pipeline.push(CaseRule::new(CaseOptions::default()));
+///
+///   preview(&results.entries, &mut pipeline)
+/// }
+/// ```
+pub fn preview(
+    entries: &[FileEntry],
+    pipeline: &mut RulePipeline,
+) -> ResponseVecWithData<PreviewItem> {
+    let sources: Vec<PathBuf> = entries.iter().map(|entry| entry.path.clone()).collect();
+    pipeline.prepare(&sources)?;
+
+    let mut rows: Vec<(PreviewItem, Option<String>)> = sources
+        .iter()
+        .enumerate()
+        .map(|(index, source)| {
+            let old_name = file_name(source);
+
+            match pipeline.apply_with_notes(source, index) {
+                Ok((new_name, notes)) => {
+                    let status = match new_name == old_name {
+                        true => PreviewStatus::Unchanged,
+                        false => PreviewStatus::Renamed,
+                    };
+                    let item = PreviewItem {
+                        source: source.clone(),
+                        old_name,
+                        new_name,
+                        status,
+                    };
+                    let notes = match notes.is_empty() {
+                        true => None,
+                        false => Some(notes.join(". ")),
+                    };
+                    (item, notes)
+                }
+                Err(e) => {
+                    let item = PreviewItem {
+                        source: source.clone(),
+                        new_name: old_name.clone(),
+                        old_name,
+                        status: PreviewStatus::Invalid,
+                    };
+                    (item, Some(format!("{}: {}", e.message, e.cause)))
+                }
+            }
+        })
+        .collect();
+
+    flag_conflicts(&mut rows);
+
+    Ok(rows.into_iter().map(to_response).collect())
+}
+
+/// Marks files whose new names could overwrite another file
+fn flag_conflicts(rows: &mut [(PreviewItem, Option<String>)]) {
+    // Invalid files stay where they are, so they are kept in the plan as unchanged
+    let plan = RenamePlan::new(
+        rows.iter()
+            .map(|(item, _)| PlannedRename::new(item.source.clone(), item.new_name.clone()))
+            .collect(),
+    );
+
+    let sources: HashMap<&PathBuf, usize> = rows
+        .iter()
+        .enumerate()
+        .map(|(idx, (item, _))| (&item.source, idx))
+        .collect();
+    let flagged: Vec<(usize, String)> = find_conflicts(&plan)
+        .into_iter()
+        .map(|conflict| (sources[&conflict.source], conflict.reason()))
+        .collect();
+
+    for (idx, reason) in flagged {
+        let (item, current) = &mut rows[idx];
+        if item.status != PreviewStatus::Invalid {
+            item.status = PreviewStatus::Conflicting;
+            *current = match current.take() {
+                Some(notes) => Some(format!("{}. {}", reason, notes)),
+                None => Some(reason),
+            };
+        }
+    }
+}
+
+fn to_response((item, reason): (PreviewItem, Option<String>)) -> OkDataResponse<PreviewItem> {
+    let message = match item.status {
+        PreviewStatus::Unchanged => "Unchanged",
+        PreviewStatus::Renamed => "Renamed",
+        PreviewStatus::Invalid => "Invalid file name",
+        PreviewStatus::Conflicting => "Conflicting file name",
+    };
+
+    match reason {
+        None => OkResponse::new_info("Rename", message).add_data(item),
+        Some(reason) => create_warning(message, reason, ERR_SRC).add_data(item),
+    }
+}
+
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule::{Rule, RuleContext};
+    use rs_response::DataResponse;
+    use std::fs;
+
+    /// Renames the files listed in the map and keeps the others
+    struct MapNames(HashMap<&'static str, &'static str>);
+    impl Rule for MapNames {
+        fn describe(&self) -> String {
+            String::from("Map names")
+        }
+
+        fn apply(&self, name: &str, _ctx: &RuleContext) -> DataResponse<String> {
+            Ok(self.0.get(name).copied().unwrap_or(name).to_string())
+        }
+    }
+
+    #[test]
+    fn reports_each_status() {
+        let folder = std::env::temp_dir().join(format!("rs_rename-preview-{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+
+        let names = ["keep.txt", "old.txt", "bad.txt", "a.txt", "b.txt", "c.txt"];
+        let entries: Vec<FileEntry> = names
+            .iter()
+            .map(|name| {
+                let path = folder.join(name);
+                fs::write(&path, name).unwrap();
+                FileEntry::read(&path, false).unwrap()
+            })
+            .collect();
+        fs::write(folder.join("taken.txt"), "outside of the batch").unwrap();
+
+        let mut pipeline = RulePipeline::new();
+        pipeline.push(MapNames(HashMap::from([
+            ("old.txt", "new.txt"),
+            ("bad.txt", "b/d.txt"),
+            ("a.txt", "same.txt"),
+            ("b.txt", "same.txt"),
+            ("c.txt", "taken.txt"),
+        ])));
+
+        let rows: Vec<(PreviewItem, Option<String>)> = preview(&entries, &mut pipeline)
+            .unwrap()
+            .into_iter()
+            .map(|response| match response {
+                OkDataResponse::INFOData(info) => (info.data, None),
+                OkDataResponse::WARNData(warning) => (warning.data, Some(warning.cause)),
+            })
+            .collect();
+        fs::remove_dir_all(&folder).unwrap();
+
+        let statuses: Vec<(&str, PreviewStatus)> = rows
+            .iter()
+            .map(|(item, _)| (item.new_name.as_str(), item.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("keep.txt", PreviewStatus::Unchanged),
+                ("new.txt", PreviewStatus::Renamed),
+                ("bad.txt", PreviewStatus::Invalid),
+                ("same.txt", PreviewStatus::Conflicting),
+                ("same.txt", PreviewStatus::Conflicting),
+                ("taken.txt", PreviewStatus::Conflicting),
+            ]
+        );
+
+        assert_eq!(rows[0].1, None);
+        assert_eq!(rows[1].1, None);
+        assert!(rows[2].1.as_ref().unwrap().contains("path separator"));
+        assert!(rows[3].1.as_ref().unwrap().contains("b.txt"));
+        assert!(rows[5].1.as_ref().unwrap().contains("already exists"));
+    }
+}
@@ -0,0 +1,73 @@
+use rs_response::DataResponse;
+use std::path::{Path, PathBuf};
+
+/// Information about the file currently being renamed by a `Rule`
+///
+/// # Properties:
+/// - `path`: `&Path` - The original path of the file
+/// - `index`: `usize` - The position of the file in the batch
+pub struct RuleContext<'a> {
+    pub path: &'a Path,
+    pub index: usize,
+}
+
+/// A single step of a `RulePipeline`
+///
+/// Each rule receives the file name produced by the previous rule and
+/// returns the file name to hand to the next one
+///
+/// # Methods:
+/// - `describe` - A short summary of the rule to display to the user
+/// - `prepare` - Called once with the whole batch before any file is renamed
+/// - `apply` - Transforms a single file name
+/// - `note` - Explains a change `apply` makes, to show it in the preview
+///
+/// # Example:
+/// ```
+/// use rs_rename::{Rule, RuleContext};
+/// use rs_response::DataResponse;
+///
+/// struct AddPrefix(String);
+///
+/// impl Rule for AddPrefix {
+///   fn describe(&self) -> String {
+///     format!("Add the prefix '{}'", self.0)
+///   }
+///
+///   fn apply(&self, name: &str, _ctx: &RuleContext) -> DataResponse<String> {
+///     Ok(format!("{}{}", self.0, name))
+///   }
+/// }
+/// ```
+pub trait Rule: Send + Sync {
+    /// A short summary of the rule to display to the user
+    fn describe(&self) -> String;
+
+    /// Called once with the source paths of the whole batch, in batch
+    /// order, before any file name is passed to `apply`
+    ///
+    /// **NOTE:** The default implementation does nothing. Rules that
+    /// depend on the rest of the batch (such as counters) override it
+    fn prepare(&mut self, _sources: &[PathBuf]) -> DataResponse<()> {
+        Ok(())
+    }
+
+    /// Transforms a single file name
+    ///
+    /// # Arguments:
+    /// - `name`: `&str` - The file name produced by the previous rule
+    /// - `ctx`: `&RuleContext` - Information about the file being renamed
+    fn apply(&self, name: &str, ctx: &RuleContext) -> DataResponse<String>;
+
+    /// Explains a change `apply` makes to a file name, which the preview
+    /// shows as a warning, such as a corrected extension
+    ///
+    /// **NOTE:** The default implementation has nothing to explain
+    ///
+    /// # Arguments:
+    /// - `name`: `&str` - The file name given to `apply`
+    /// - `ctx`: `&RuleContext` - Information about the file being renamed
+    fn note(&self, _name: &str, _ctx: &RuleContext) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,14 @@
+mod case;
+pub use case::{CaseOptions, CaseRule, CaseStyle, ExtensionCase, DEFAULT_SMALL_WORDS};
+
+mod counter;
+pub use counter::{CounterOptions, CounterOrder, CounterPlacement, CounterReset, CounterRule};
+
+mod extension;
+pub use extension::{ExtensionOptions, ExtensionRule};
+
+mod regex_replace;
+pub use regex_replace::{MatchTarget, Occurrence, RegexOptions, RegexRule};
+
+mod template;
+pub use template::TemplateRule;
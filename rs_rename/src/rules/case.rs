@@ -0,0 +1,408 @@
+use crate::file_name::{join_name, split_name};
+use crate::rule::{Rule, RuleContext};
+use rs_response::DataResponse;
+
+/// Words that are kept lowercase by `CaseStyle::Title`, unless they are
+/// the first or last word of the name
+pub const DEFAULT_SMALL_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "or", "over", "per", "the", "to", "up", "via", "vs", "with",
+];
+
+/// The letter case to convert the file's stem to
+///
+/// | Style      | Result                 |
+/// | ---------- | ---------------------- |
+/// | `Lower`    | `the lord of the rings`|
+/// | `Upper`    | `THE LORD OF THE RINGS`|
+/// | `Title`    | `The Lord of the Rings`|
+/// | `Sentence` | `The lord of the rings`|
+/// | `Snake`    | `the_lord_of_the_rings`|
+/// | `Kebab`    | `the-lord-of-the-rings`|
+/// | `Camel`    | `theLordOfTheRings`    |
+/// | `Pascal`   | `TheLordOfTheRings`    |
+///
+/// **NOTE:** `Lower`, `Upper`, `Title` and `Sentence` keep the original
+/// separators between words. The other styles replace them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaseStyle {
+    #[default]
+    Lower,
+    Upper,
+    Title,
+    Sentence,
+    Snake,
+    Kebab,
+    Camel,
+    Pascal,
+}
+
+/// The letter case to convert the file's extension to
+///
+/// - `Keep`: Leave the extension as is
+/// - `Lower`: Convert to lowercase
+/// - `Upper`: Convert to UPPERCASE
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtensionCase {
+    #[default]
+    Keep,
+    Lower,
+    Upper,
+}
+
+/// Options for creating a `CaseRule`
+///
+/// # Properties:
+/// - `style`: `CaseStyle` - The letter case of the stem. Defaults to `Lower`
+/// - `extension`: `ExtensionCase` - The letter case of the extension. Defaults to `Keep`
+/// - `small_words`: `Vec<String>` - Words kept lowercase in `Title` case. Defaults to `DEFAULT_SMALL_WORDS`
+/// - `acronyms`: `Vec<String>` - Words written exactly as given in `Title` and `Sentence` case,
+///   matched ignoring letter case. Defaults to none
+#[derive(Debug, Clone)]
+pub struct CaseOptions {
+    pub style: CaseStyle,
+    pub extension: ExtensionCase,
+    pub small_words: Vec<String>,
+    pub acronyms: Vec<String>,
+}
+impl Default for CaseOptions {
+    fn default() -> Self {
+        Self {
+            style: CaseStyle::default(),
+            extension: ExtensionCase::default(),
+            small_words: DEFAULT_SMALL_WORDS.iter().map(|w| w.to_string()).collect(),
+            acronyms: Vec::new(),
+        }
+    }
+}
+
+/// A `Rule` that changes the letter case of the file name
+///
+/// Words are split on whitespace and punctuation, on changes between
+/// letters and digits, and on existing camelCase boundaries, so
+/// `USBDriveBackup2023` has the words `USB`, `Drive`, `Backup` and `2023`
+///
+/// # Methods:
+/// - `new` - Creates a new `CaseRule`
+///
+/// # Example:
+/// ```
+/// use rs_rename::rules::{CaseOptions, CaseRule, CaseStyle, ExtensionCase};
+/// use rs_rename::{Rule, RuleContext};
+/// use std::path::Path;
+///
+/// let rule = CaseRule::new(CaseOptions {
+///   style: CaseStyle::Title,
+///   extension: ExtensionCase::Lower,
+///   acronyms: vec![String::from("NASA")],
+///   ..Default::default()
+/// });
+///
+/// let ctx = RuleContext { path: Path::new("nasa_photo_of_the_moon.JPG"), index: 0 };
+/// let name = rule.apply("nasa_photo_of_the_moon.JPG", &ctx).unwrap();
+///
+/// assert_eq!(name, "NASA_Photo_of_the_Moon.jpg");
+/// ```
+pub struct CaseRule {
+    options: CaseOptions,
+}
+impl CaseRule {
+    /// Creates a new `CaseRule`
+    ///
+    /// # Arguments:
+    /// - `options`: `CaseOptions` - The style, extension case, small words and acronyms
+    pub fn new(options: CaseOptions) -> Self {
+        Self { options }
+    }
+
+    fn acronym(&self, word: &str) -> Option<&str> {
+        self.options
+            .acronyms
+            .iter()
+            .find(|acronym| acronym.to_lowercase() == word.to_lowercase())
+            .map(|acronym| acronym.as_str())
+    }
+
+    fn is_small_word(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        self.options
+            .small_words
+            .iter()
+            .any(|small| small.to_lowercase() == word)
+    }
+
+    fn convert_stem(&self, stem: &str) -> String {
+        // Keep the leading dots of hidden files such as `.bashrc`
+        let body = stem.trim_start_matches('.');
+        let dots = &stem[..stem.len() - body.len()];
+
+        let converted = match self.options.style {
+            CaseStyle::Lower => body.to_lowercase(),
+            CaseStyle::Upper => body.to_uppercase(),
+            CaseStyle::Title => self.recase_words(body, |idx, last, word| {
+                match idx != 0 && idx != last && self.is_small_word(word) {
+                    true => word.to_lowercase(),
+                    false => capitalize(word),
+                }
+            }),
+            CaseStyle::Sentence => self.recase_words(body, |idx, _, word| match idx {
+                0 => capitalize(word),
+                _ => word.to_lowercase(),
+            }),
+            CaseStyle::Snake => join_words(body, "_", |_, word| word.to_lowercase()),
+            CaseStyle::Kebab => join_words(body, "-", |_, word| word.to_lowercase()),
+            CaseStyle::Camel => join_words(body, "", |idx, word| match idx {
+                0 => word.to_lowercase(),
+                _ => capitalize(word),
+            }),
+            CaseStyle::Pascal => join_words(body, "", |_, word| capitalize(word)),
+        };
+
+        format!("{}{}", dots, converted)
+    }
+
+    /// Recases every word while keeping the separators between them.
+    /// Acronyms are always written as given
+    fn recase_words<F>(&self, text: &str, recase: F) -> String
+    where
+        F: Fn(usize, usize, &str) -> String,
+    {
+        let pieces = split_pieces(text);
+        let last = pieces.iter().filter(|piece| piece.is_word).count().max(1) - 1;
+
+        let mut result = String::with_capacity(text.len());
+        let mut idx = 0;
+        for piece in pieces {
+            if !piece.is_word {
+                result.push_str(piece.text);
+                continue;
+            }
+
+            match self.acronym(piece.text) {
+                Some(acronym) => result.push_str(acronym),
+                None => result.push_str(&recase(idx, last, piece.text)),
+            }
+            idx += 1;
+        }
+
+        result
+    }
+}
+impl Rule for CaseRule {
+    fn describe(&self) -> String {
+        format!("Change the letter case to {:?}", self.options.style)
+    }
+
+    fn apply(&self, name: &str, _ctx: &RuleContext) -> DataResponse<String> {
+        let (stem, ext) = split_name(name);
+
+        let ext = ext.map(|ext| match self.options.extension {
+            ExtensionCase::Keep => ext.to_string(),
+            ExtensionCase::Lower => ext.to_lowercase(),
+            ExtensionCase::Upper => ext.to_uppercase(),
+        });
+
+        Ok(join_name(&self.convert_stem(stem), ext.as_deref()))
+    }
+}
+
+/// Joins the words of `text` with `separator`, dropping the original separators.
+/// If `text` has no words, it is returned as is
+fn join_words<F>(text: &str, separator: &str, recase: F) -> String
+where
+    F: Fn(usize, &str) -> String,
+{
+    let words: Vec<String> = split_pieces(text)
+        .into_iter()
+        .filter(|piece| piece.is_word)
+        .enumerate()
+        .map(|(idx, piece)| recase(idx, piece.text))
+        .collect();
+
+    match words.is_empty() {
+        true => text.to_string(),
+        false => words.join(separator),
+    }
+}
+
+/// Uppercases the first character of a word and lowercases the rest
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.as_str().to_lowercase().chars())
+            .collect(),
+    }
+}
+
+/// A run of text that is either a single word or the separator between two words
+struct Piece<'a> {
+    text: &'a str,
+    is_word: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharKind {
+    Upper,
+    Lower,
+    Digit,
+    Mark,
+    Separator,
+}
+
+fn char_kind(c: char) -> CharKind {
+    if c.is_uppercase() {
+        CharKind::Upper
+    } else if c.is_alphabetic() {
+        // Letters without case, such as CJK, behave like lowercase letters
+        CharKind::Lower
+    } else if c.is_numeric() {
+        CharKind::Digit
+    } else if is_combining_mark(c) {
+        CharKind::Mark
+    } else {
+        CharKind::Separator
+    }
+}
+
+/// Whether `c` is in one of the Unicode blocks of combining diacritical marks
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F
+    )
+}
+
+/// Splits text into words and the separators between them
+///
+/// A new word starts:
+/// - after a separator
+/// - between a lowercase letter and an uppercase letter (`camel|Case`)
+/// - before the last uppercase letter of a run followed by a lowercase one (`USB|Drive`)
+/// - between letters and digits (`photo|2023`)
+///
+/// **NOTE:** Apostrophes between two letters are kept inside the word, so
+/// `DON'T` and `don't` are single words
+fn split_pieces(text: &str) -> Vec<Piece<'_>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let kind_at = |i: usize| chars.get(i).map(|&(_, c)| char_kind(c));
+    let byte_at = |i: usize| chars.get(i).map(|&(b, _)| b).unwrap_or(text.len());
+
+    let is_apostrophe = |i: usize| {
+        matches!(chars[i].1, '\'' | '\u{2019}')
+            && matches!(
+                kind_at(i.wrapping_sub(1)),
+                Some(CharKind::Upper | CharKind::Lower)
+            )
+            && matches!(kind_at(i + 1), Some(CharKind::Upper | CharKind::Lower))
+    };
+
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut in_word: Option<bool> = None;
+    let mut prev = CharKind::Separator;
+    let mut after_apostrophe = false;
+
+    for i in 0..chars.len() {
+        let mut kind = char_kind(chars[i].1);
+        let apostrophe = kind == CharKind::Separator && is_apostrophe(i);
+        if apostrophe {
+            // Neither the apostrophe nor the letter after it starts a new word
+            kind = prev;
+        }
+        if kind == CharKind::Mark {
+            // Marks belong to whatever they follow
+            kind = match prev {
+                CharKind::Separator => CharKind::Separator,
+                _ => prev,
+            };
+        }
+
+        let is_word = kind != CharKind::Separator;
+        let boundary = match (in_word, is_word) {
+            (None, _) => false,
+            (Some(was_word), now_word) if was_word != now_word => true,
+            (Some(false), false) => false,
+            _ if apostrophe || after_apostrophe => false,
+            _ => match (prev, kind) {
+                (CharKind::Lower, CharKind::Upper) => true,
+                (CharKind::Digit, CharKind::Upper | CharKind::Lower) => true,
+                (CharKind::Upper | CharKind::Lower, CharKind::Digit) => true,
+                (CharKind::Upper, CharKind::Upper) => {
+                    matches!(kind_at(i + 1), Some(CharKind::Lower))
+                }
+                _ => false,
+            },
+        };
+
+        if boundary {
+            pieces.push(Piece {
+                text: &text[byte_at(start)..byte_at(i)],
+                is_word: in_word.unwrap_or(false),
+            });
+            start = i;
+        }
+
+        in_word = Some(is_word);
+        prev = kind;
+        after_apostrophe = apostrophe;
+    }
+
+    if let Some(is_word) = in_word {
+        pieces.push(Piece {
+            text: &text[byte_at(start)..],
+            is_word,
+        });
+    }
+
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn convert(style: CaseStyle, name: &str) -> String {
+        let rule = CaseRule::new(CaseOptions {
+            style,
+            ..Default::default()
+        });
+        let ctx = RuleContext {
+            path: Path::new(name),
+            index: 0,
+        };
+        rule.apply(name, &ctx).unwrap()
+    }
+
+    #[test]
+    fn splits_camel_case_and_digits() {
+        assert_eq!(
+            convert(CaseStyle::Snake, "USBDriveBackup2023.zip"),
+            "usb_drive_backup_2023.zip"
+        );
+    }
+
+    #[test]
+    fn keeps_apostrophes_inside_words() {
+        assert_eq!(
+            convert(CaseStyle::Title, "DON'T STOP.mp3"),
+            "Don't Stop.mp3"
+        );
+        assert_eq!(
+            convert(CaseStyle::Title, "IT\u{2019}S A DOG'S LIFE"),
+            "It\u{2019}s a Dog's Life"
+        );
+        assert_eq!(
+            convert(CaseStyle::Kebab, "DON'Ts and dos"),
+            "don'ts-and-dos"
+        );
+    }
+
+    #[test]
+    fn keeps_leading_dots() {
+        assert_eq!(convert(CaseStyle::Upper, ".bashrc"), ".BASHRC");
+    }
+}
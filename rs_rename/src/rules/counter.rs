@@ -0,0 +1,546 @@
+use crate::error_factory::create_error;
+use crate::file_name::{join_name, split_name};
+use crate::rule::{Rule, RuleContext};
+use rs_response::DataResponse;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const ERR_SRC: &str = "rules::counter::CounterRule";
+
+/// Where the counter is inserted into the file's stem
+///
+/// - `Prefix`: Before the stem
+/// - `Suffix`: After the stem, before the extension
+/// - `AtIndex`: Before the character at the given index. Indexes past
+///   the end of the stem are treated as `Suffix`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CounterPlacement {
+    Prefix,
+    #[default]
+    Suffix,
+    AtIndex(usize),
+}
+
+/// When the counter starts over from its `start` value
+///
+/// - `Never`: The whole batch shares one counter
+/// - `PerFolder`: Each parent folder has its own counter
+/// - `PerExtension`: Each extension (ignoring letter case) has its own counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CounterReset {
+    #[default]
+    Never,
+    PerFolder,
+    PerExtension,
+}
+
+/// The order in which files are numbered
+///
+/// **NOTE:** Ties are always broken by the full path, so numbering the
+/// same batch twice gives the same result
+///
+/// - `Input`: The order of the batch as it was given
+/// - `Name`: File name, compared character by character
+/// - `NaturalName`: File name, comparing runs of digits by their value (`img2` before `img10`)
+/// - `Path`: Full path, compared character by character
+/// - `Modified`: Last modification time
+/// - `Size`: File size in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CounterOrder {
+    Input,
+    Name,
+    #[default]
+    NaturalName,
+    Path,
+    Modified,
+    Size,
+}
+
+/// Options for creating a `CounterRule`
+///
+/// # Properties:
+/// - `start`: `i64` - The first number of each counter. Defaults to `1`
+/// - `step`: `i64` - The amount added for each file. Defaults to `1`
+/// - `padding`: `usize` - The minimum number of digits, padded with zeros. Defaults to `0`
+/// - `separator`: `String` - Text placed between the counter and the stem. Defaults to `""`
+/// - `placement`: `CounterPlacement` - Where the counter is inserted. Defaults to `Suffix`
+/// - `reset`: `CounterReset` - When the counter starts over. Defaults to `Never`
+/// - `order`: `CounterOrder` - The order in which files are numbered. Defaults to `NaturalName`
+/// - `descending`: `bool` - Whether to reverse the order. Defaults to `false`
+#[derive(Debug, Clone)]
+pub struct CounterOptions {
+    pub start: i64,
+    pub step: i64,
+    pub padding: usize,
+    pub separator: String,
+    pub placement: CounterPlacement,
+    pub reset: CounterReset,
+    pub order: CounterOrder,
+    pub descending: bool,
+}
+impl Default for CounterOptions {
+    fn default() -> Self {
+        Self {
+            start: 1,
+            step: 1,
+            padding: 0,
+            separator: String::new(),
+            placement: CounterPlacement::default(),
+            reset: CounterReset::default(),
+            order: CounterOrder::default(),
+            descending: false,
+        }
+    }
+}
+
+/// A `Rule` that numbers every file of the batch
+///
+/// # Methods:
+/// - `new` - Creates a new `CounterRule`
+///
+/// # Example:
+/// ```
+/// use rs_rename::rules::{CounterOptions, CounterPlacement, CounterReset, CounterRule};
+/// use rs_rename::RulePipeline;
+/// use std::path::PathBuf;
+///
+/// let mut pipeline = RulePipeline::new();
+/// pipeline.push(CounterRule::new(CounterOptions {
+///   padding: 3,
+///   separator: String::from("_"),
+///   placement: CounterPlacement::Prefix,
+///   reset: CounterReset::PerFolder,
+///   ..Default::default()
+/// }));
+///
+/// let sources = vec![
+///   PathBuf::from("scans/b.png"),
+///   PathBuf::from("scans/a.png"),
+///   PathBuf::from("photos/a.jpg"),
+/// ];
+///
+/// // "scans/002_b.png", "scans/001_a.png" and "photos/001_a.jpg"
+/// let plan = pipeline.plan(&sources).unwrap();
+/// ```
+pub struct CounterRule {
+    options: CounterOptions,
+    numbers: Vec<i64>,
+}
+impl CounterRule {
+    /// Creates a new `CounterRule`
+    ///
+    /// # Arguments:
+    /// - `options`: `CounterOptions` - The start, step, padding, placement, reset and order of the counter
+    pub fn new(options: CounterOptions) -> Self {
+        Self {
+            options,
+            numbers: Vec::new(),
+        }
+    }
+
+    fn group_key(&self, path: &Path) -> String {
+        match self.options.reset {
+            CounterReset::Never => String::new(),
+            CounterReset::PerFolder => path
+                .parent()
+                .map(|parent| parent.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            CounterReset::PerExtension => path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn sort_order(&self, sources: &[PathBuf]) -> DataResponse<Vec<usize>> {
+        let mut order: Vec<usize> = (0..sources.len()).collect();
+
+        match self.options.order {
+            CounterOrder::Input => {}
+            CounterOrder::Name => order.sort_by(|&a, &b| {
+                file_name(&sources[a])
+                    .cmp(&file_name(&sources[b]))
+                    .then_with(|| sources[a].cmp(&sources[b]))
+            }),
+            CounterOrder::NaturalName => order.sort_by(|&a, &b| {
+                natural_cmp(&file_name(&sources[a]), &file_name(&sources[b]))
+                    .then_with(|| sources[a].cmp(&sources[b]))
+            }),
+            CounterOrder::Path => order.sort_by(|&a, &b| sources[a].cmp(&sources[b])),
+            CounterOrder::Modified => {
+                let times = sources
+                    .iter()
+                    .map(|path| read_metadata(path, |meta| meta.modified()))
+                    .collect::<DataResponse<Vec<SystemTime>>>()?;
+                order.sort_by(|&a, &b| {
+                    times[a]
+                        .cmp(&times[b])
+                        .then_with(|| sources[a].cmp(&sources[b]))
+                });
+            }
+            CounterOrder::Size => {
+                let sizes = sources
+                    .iter()
+                    .map(|path| read_metadata(path, |meta| Ok(meta.len())))
+                    .collect::<DataResponse<Vec<u64>>>()?;
+                order.sort_by(|&a, &b| {
+                    sizes[a]
+                        .cmp(&sizes[b])
+                        .then_with(|| sources[a].cmp(&sources[b]))
+                });
+            }
+        }
+
+        if self.options.descending {
+            order.reverse();
+        }
+
+        Ok(order)
+    }
+
+    fn insert(&self, stem: &str, counter: &str) -> String {
+        let separator = self.options.separator.as_str();
+
+        match self.options.placement {
+            CounterPlacement::Prefix => format!("{}{}{}", counter, separator, stem),
+            CounterPlacement::Suffix => format!("{}{}{}", stem, separator, counter),
+            CounterPlacement::AtIndex(index) => match stem.char_indices().nth(index) {
+                None => format!("{}{}{}", stem, separator, counter),
+                Some((byte_idx, _)) => format!(
+                    "{}{}{}{}",
+                    &stem[..byte_idx],
+                    counter,
+                    separator,
+                    &stem[byte_idx..]
+                ),
+            },
+        }
+    }
+}
+impl Rule for CounterRule {
+    fn describe(&self) -> String {
+        format!(
+            "Number files from {} in steps of {}",
+            self.options.start, self.options.step
+        )
+    }
+
+    fn prepare(&mut self, sources: &[PathBuf]) -> DataResponse<()> {
+        let order = self.sort_order(sources)?;
+
+        // The number last given in each group. The next one is only
+        // computed when another file of the group needs it, so a counter
+        // close to `i64::MAX` never fails after its last file
+        let mut last: HashMap<String, i64> = HashMap::new();
+        let mut numbers = vec![0; sources.len()];
+
+        for index in order {
+            let group = self.group_key(&sources[index]);
+            let number = match last.get(&group) {
+                None => self.options.start,
+                Some(previous) => previous.checked_add(self.options.step).ok_or_else(|| {
+                    create_error(
+                        "Unable to number the files",
+                        "The counter is too large",
+                        ERR_SRC,
+                    )
+                })?,
+            };
+
+            numbers[index] = number;
+            last.insert(group, number);
+        }
+
+        self.numbers = numbers;
+
+        Ok(())
+    }
+
+    fn apply(&self, name: &str, ctx: &RuleContext) -> DataResponse<String> {
+        let number = self.numbers.get(ctx.index).ok_or_else(|| {
+            create_error(
+                "Unable to number the files",
+                format!(
+                    "DEVELOPER ERROR: No counter was prepared for '{}'",
+                    ctx.path.display()
+                ),
+                ERR_SRC,
+            )
+        })?;
+
+        let counter = format!("{:0width$}", number, width = self.options.padding);
+        let (stem, ext) = split_name(name);
+
+        Ok(join_name(&self.insert(stem, &counter), ext))
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn read_metadata<T>(
+    path: &Path,
+    read: impl FnOnce(fs::Metadata) -> std::io::Result<T>,
+) -> DataResponse<T> {
+    fs::metadata(path).and_then(read).map_err(|e| {
+        create_error(
+            format!("Unable to sort '{}' for numbering", path.display()),
+            e.to_string(),
+            ERR_SRC,
+        )
+    })
+}
+
+/// Compares two strings, treating runs of ASCII digits as numbers
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_digits(&mut a);
+                let y = take_digits(&mut b);
+                let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(&y));
+
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase()).then(x.cmp(&y));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(c);
+    }
+
+    let trimmed = digits.trim_start_matches('0');
+    match trimmed.is_empty() {
+        true => String::from("0"),
+        false => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// The file names the counter gives to `sources`, in batch order
+    fn number(options: CounterOptions, sources: &[PathBuf]) -> DataResponse<Vec<String>> {
+        let mut rule = CounterRule::new(options);
+        rule.prepare(sources)?;
+
+        sources
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                let ctx = RuleContext { path, index };
+                rule.apply(&file_name(path), &ctx)
+            })
+            .collect()
+    }
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    fn by_order(order: CounterOrder, descending: bool) -> CounterOptions {
+        CounterOptions {
+            separator: String::from("_"),
+            order,
+            descending,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn numbers_in_name_order() {
+        let sources = paths(&["b/img10.png", "a/img2.png", "c/IMG1.png"]);
+
+        assert_eq!(
+            number(by_order(CounterOrder::Input, false), &sources).unwrap(),
+            ["img10_1.png", "img2_2.png", "IMG1_3.png"]
+        );
+        assert_eq!(
+            number(by_order(CounterOrder::Name, false), &sources).unwrap(),
+            ["img10_2.png", "img2_3.png", "IMG1_1.png"]
+        );
+        assert_eq!(
+            number(by_order(CounterOrder::NaturalName, false), &sources).unwrap(),
+            ["img10_3.png", "img2_2.png", "IMG1_1.png"]
+        );
+        assert_eq!(
+            number(by_order(CounterOrder::NaturalName, true), &sources).unwrap(),
+            ["img10_1.png", "img2_2.png", "IMG1_3.png"]
+        );
+        assert_eq!(
+            number(by_order(CounterOrder::Path, false), &sources).unwrap(),
+            ["img10_2.png", "img2_1.png", "IMG1_3.png"]
+        );
+    }
+
+    #[test]
+    fn breaks_ties_by_path() {
+        // The same name in two folders, given in both orders
+        let sources = paths(&[
+            "b/photo.jpg",
+            "a/photo.jpg",
+            "c/photo01.jpg",
+            "c/photo1.jpg",
+        ]);
+        let reversed: Vec<PathBuf> = sources.iter().rev().cloned().collect();
+
+        let numbers = number(by_order(CounterOrder::NaturalName, false), &sources).unwrap();
+        assert_eq!(
+            numbers,
+            [
+                "photo_2.jpg",
+                "photo_1.jpg",
+                "photo01_3.jpg",
+                "photo1_4.jpg"
+            ]
+        );
+
+        let mut numbers_reversed =
+            number(by_order(CounterOrder::NaturalName, false), &reversed).unwrap();
+        numbers_reversed.reverse();
+        assert_eq!(numbers, numbers_reversed);
+    }
+
+    #[test]
+    fn numbers_in_size_and_modified_order() {
+        let folder = std::env::temp_dir().join(format!("rs_rename-counter-{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+
+        let now = SystemTime::now();
+        let sources: Vec<PathBuf> = [("a.txt", 3, 1), ("b.txt", 1, 3), ("c.txt", 2, 2)]
+            .iter()
+            .map(|(name, size, age)| {
+                let path = folder.join(name);
+                let file = fs::File::create(&path).unwrap();
+                file.set_len(*size).unwrap();
+                file.set_modified(now - Duration::from_secs(60 * age))
+                    .unwrap();
+                path
+            })
+            .collect();
+
+        let by_size = number(by_order(CounterOrder::Size, false), &sources);
+        let by_modified = number(by_order(CounterOrder::Modified, false), &sources);
+        fs::remove_dir_all(&folder).unwrap();
+
+        assert_eq!(by_size.unwrap(), ["a_3.txt", "b_1.txt", "c_2.txt"]);
+        assert_eq!(by_modified.unwrap(), ["a_3.txt", "b_1.txt", "c_2.txt"]);
+
+        // A file that does not exist cannot be sorted
+        assert!(number(by_order(CounterOrder::Size, false), &sources).is_err());
+    }
+
+    #[test]
+    fn resets_per_group() {
+        let sources = paths(&["a/x.jpg", "b/y.JPG", "a/z.png", "b/w.jpg"]);
+
+        let per_folder = CounterOptions {
+            reset: CounterReset::PerFolder,
+            order: CounterOrder::Input,
+            padding: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            number(per_folder, &sources).unwrap(),
+            ["x01.jpg", "y01.JPG", "z02.png", "w02.jpg"]
+        );
+
+        let per_extension = CounterOptions {
+            reset: CounterReset::PerExtension,
+            order: CounterOrder::Input,
+            start: 10,
+            step: -5,
+            ..Default::default()
+        };
+        assert_eq!(
+            number(per_extension, &sources).unwrap(),
+            ["x10.jpg", "y5.JPG", "z10.png", "w0.jpg"]
+        );
+    }
+
+    #[test]
+    fn places_the_counter() {
+        let sources = paths(&["abc.txt"]);
+        let at = |placement| CounterOptions {
+            placement,
+            separator: String::from("-"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            number(at(CounterPlacement::Prefix), &sources).unwrap(),
+            ["1-abc.txt"]
+        );
+        assert_eq!(
+            number(at(CounterPlacement::AtIndex(1)), &sources).unwrap(),
+            ["a1-bc.txt"]
+        );
+        // Past the end of the stem, the counter becomes a suffix
+        assert_eq!(
+            number(at(CounterPlacement::AtIndex(3)), &sources).unwrap(),
+            ["abc-1.txt"]
+        );
+        assert_eq!(
+            number(at(CounterPlacement::AtIndex(99)), &sources).unwrap(),
+            ["abc-1.txt"]
+        );
+    }
+
+    #[test]
+    fn only_fails_when_a_number_does_not_fit() {
+        let near_max = |step| CounterOptions {
+            start: i64::MAX - 1,
+            step,
+            order: CounterOrder::Input,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            number(near_max(1), &paths(&["a", "b"])).unwrap(),
+            [format!("a{}", i64::MAX - 1), format!("b{}", i64::MAX)]
+        );
+        assert_eq!(
+            number(near_max(i64::MAX), &paths(&["a"])).unwrap(),
+            [format!("a{}", i64::MAX - 1)]
+        );
+        assert!(number(near_max(2), &paths(&["a", "b"])).is_err());
+    }
+
+    #[test]
+    fn compares_names_naturally() {
+        assert_eq!(natural_cmp("img2", "img10"), Ordering::Less);
+        assert_eq!(natural_cmp("img10", "img9"), Ordering::Greater);
+        assert_eq!(natural_cmp("img007", "img7"), Ordering::Equal);
+        assert_eq!(natural_cmp("img007", "img8"), Ordering::Less);
+        assert_eq!(natural_cmp("Img1", "img1"), Ordering::Less);
+        assert_eq!(natural_cmp("img", "img1"), Ordering::Less);
+        assert_eq!(natural_cmp("a99", "b1"), Ordering::Less);
+    }
+}
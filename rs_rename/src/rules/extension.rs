@@ -0,0 +1,285 @@
+use crate::error_factory::create_error;
+use crate::file_name::{join_name, split_name};
+use crate::rule::{Rule, RuleContext};
+use rs_fs::{is_known_extension, sniff, FileType};
+use rs_response::DataResponse;
+use std::path::PathBuf;
+
+const ERR_SRC: &str = "rules::extension::ExtensionRule";
+
+/// Options for creating an `ExtensionRule`
+///
+/// # Properties:
+/// - `add_missing`: `bool` - Whether to add an extension to files without one. Defaults to `true`
+/// - `replace_wrong`: `bool` - Whether to replace extensions that do not match the content. Defaults to `true`
+#[derive(Debug, Clone)]
+pub struct ExtensionOptions {
+    pub add_missing: bool,
+    pub replace_wrong: bool,
+}
+impl Default for ExtensionOptions {
+    fn default() -> Self {
+        Self {
+            add_missing: true,
+            replace_wrong: true,
+        }
+    }
+}
+
+/// A `Rule` that fixes wrong or missing extensions from the real type of
+/// each file, found with `rs_fs::sniff`
+///
+/// **NOTE:** Files of an unknown type, such as text files, are left as they
+/// are. Only an extension of another detected type is replaced, so `.key` or
+/// `.srw` files are kept even though they are zip archives or TIFF images.
+/// Files detected as a type other formats are based on, such as a zip
+/// archive, only get an extension when they have none (see
+/// `FileType::is_generic`)
+///
+/// **NOTE:** When the current extension does not look like an extension at
+/// all, e.g. `report.final`, the right one is added after it instead of
+/// replacing it. An extension in uppercase stays in uppercase
+///
+/// **NOTE:** Every correction is explained with `Rule::note`, so it appears
+/// as a warning in the preview
+///
+/// # Methods:
+/// - `new` - Creates a new `ExtensionRule`
+///
+/// # Example:
+/// ```
+/// use rs_rename::rules::{ExtensionOptions, ExtensionRule};
+/// use rs_rename::RulePipeline;
+/// use std::path::PathBuf;
+///
+/// let mut pipeline = RulePipeline::new();
+/// pipeline.push(ExtensionRule::new(ExtensionOptions {
+///   add_missing: false,
+///   ..Default::default()
+/// }));
+///
+/// let sources = vec![PathBuf::from("downloads/photo.jpg")];
+///
+/// // "downloads/photo.png" when the file is a PNG image, or an error when it
+/// // cannot be read
+/// let plan = pipeline.plan(&sources);
+/// ```
+pub struct ExtensionRule {
+    options: ExtensionOptions,
+    /// The type of each file of the batch, or why it could not be read
+    types: Vec<Result<Option<FileType>, String>>,
+}
+impl ExtensionRule {
+    /// Creates a new `ExtensionRule`
+    ///
+    /// # Arguments:
+    /// - `options`: `ExtensionOptions` - Which extensions to fix
+    pub fn new(options: ExtensionOptions) -> Self {
+        Self {
+            options,
+            types: Vec::new(),
+        }
+    }
+
+    /// Finds the corrected file name along with its note, or `None` when the
+    /// name is kept
+    fn fix(&self, name: &str, ctx: &RuleContext) -> DataResponse<Option<(String, String)>> {
+        let file_type = match self.types.get(ctx.index) {
+            Some(Ok(file_type)) => file_type,
+            Some(Err(cause)) => {
+                return Err(create_error(
+                    format!("Unable to detect the type of '{}'", ctx.path.display()),
+                    cause.clone(),
+                    ERR_SRC,
+                ))
+            }
+            None => {
+                return Err(create_error(
+                    "Unable to detect the file types",
+                    format!(
+                        "DEVELOPER ERROR: No file type was prepared for '{}'",
+                        ctx.path.display()
+                    ),
+                    ERR_SRC,
+                ))
+            }
+        };
+
+        let file_type = match file_type {
+            Some(file_type) => file_type,
+            None => return Ok(None),
+        };
+
+        let (stem, ext) = split_name(name);
+        let fixed = match ext {
+            Some(ext) if file_type.has_extension(ext) => None,
+            Some(ext) if is_known_extension(ext) => {
+                match self.options.replace_wrong && !file_type.is_generic() {
+                    true => {
+                        let new_ext = same_case(file_type.extension(), ext);
+                        let note = format!(
+                            "'.{}' was changed to '.{}', as the file is of type '{}'",
+                            ext, new_ext, file_type.name
+                        );
+                        Some((join_name(stem, Some(&new_ext)), note))
+                    }
+                    false => None,
+                }
+            }
+            // The extension of a format that is not detected, such as `.key`
+            Some(ext) if looks_like_extension(ext) => None,
+            _ => match self.options.add_missing {
+                true => {
+                    let new_ext = file_type.extension();
+                    let note = format!(
+                        "'.{}' was added, as the file is of type '{}'",
+                        new_ext, file_type.name
+                    );
+                    Some((join_name(name, Some(new_ext)), note))
+                }
+                false => None,
+            },
+        };
+
+        Ok(fixed)
+    }
+}
+impl Rule for ExtensionRule {
+    fn describe(&self) -> String {
+        match (self.options.add_missing, self.options.replace_wrong) {
+            (true, true) => String::from("Fix wrong or missing extensions from the file content"),
+            (true, false) => String::from("Add missing extensions from the file content"),
+            (false, true) => String::from("Fix wrong extensions from the file content"),
+            (false, false) => String::from("Check extensions against the file content"),
+        }
+    }
+
+    fn prepare(&mut self, sources: &[PathBuf]) -> DataResponse<()> {
+        // Files that cannot be read fail when they are renamed, not the batch
+        self.types = sources
+            .iter()
+            .map(|source| sniff(source).map_err(|e| e.cause))
+            .collect();
+
+        Ok(())
+    }
+
+    fn apply(&self, name: &str, ctx: &RuleContext) -> DataResponse<String> {
+        Ok(match self.fix(name, ctx)? {
+            Some((fixed, _)) => fixed,
+            None => name.to_string(),
+        })
+    }
+
+    fn note(&self, name: &str, ctx: &RuleContext) -> Option<String> {
+        self.fix(name, ctx).ok()?.map(|(_, note)| note)
+    }
+}
+
+/// Whether the text after the last dot is meant as an extension, rather than
+/// part of the name like in `report.final` or `v1.2`
+fn looks_like_extension(ext: &str) -> bool {
+    (1..=4).contains(&ext.len())
+        && ext.chars().all(|c| c.is_ascii_alphanumeric())
+        && ext.chars().any(|c| c.is_ascii_alphabetic())
+}
+
+/// Writes `ext` in uppercase when the extension it replaces was
+fn same_case(ext: &str, old: &str) -> String {
+    match old.chars().any(|c| c.is_ascii_lowercase()) {
+        true => ext.to_string(),
+        false => ext.to_uppercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+    const ZIP: &[u8] = b"PK\x05\x06\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+    const TIFF: &[u8] = b"II*\0\x08\0\0\0\0\0";
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!(
+            "rs_rename-extension-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    /// The new name of every file, with the notes of the changed ones
+    fn fix_names(name: &str, files: &[(&str, &[u8])]) -> Vec<(String, Option<String>)> {
+        let folder = temp_folder(name);
+        let sources: Vec<PathBuf> = files
+            .iter()
+            .map(|(name, content)| {
+                let path = folder.join(name);
+                fs::write(&path, content).unwrap();
+                path
+            })
+            .collect();
+
+        let mut rule = ExtensionRule::new(ExtensionOptions::default());
+        rule.prepare(&sources).unwrap();
+
+        let names = files
+            .iter()
+            .zip(sources.iter())
+            .enumerate()
+            .map(|(index, ((name, _), path))| {
+                let ctx = RuleContext { path, index };
+                (rule.apply(name, &ctx).unwrap(), rule.note(name, &ctx))
+            })
+            .collect();
+        fs::remove_dir_all(&folder).unwrap();
+        names
+    }
+
+    #[test]
+    fn replaces_extensions_of_other_types() {
+        let names = fix_names("replace", &[("photo.jpg", PNG), ("SCAN.JPG", TIFF)]);
+
+        assert_eq!(names[0].0, "photo.png");
+        assert_eq!(
+            names[0].1.as_deref(),
+            Some("'.jpg' was changed to '.png', as the file is of type 'PNG image'")
+        );
+        // TIFF is generic, so a known extension of another type is kept too
+        assert_eq!(names[1], (String::from("SCAN.JPG"), None));
+    }
+
+    #[test]
+    fn keeps_extensions_of_formats_based_on_generic_types() {
+        let names = fix_names(
+            "generic",
+            &[("deck.key", ZIP), ("shot.srw", TIFF), ("book.epub", ZIP)],
+        );
+
+        assert_eq!(names[0], (String::from("deck.key"), None));
+        assert_eq!(names[1], (String::from("shot.srw"), None));
+        assert_eq!(names[2], (String::from("book.epub"), None));
+    }
+
+    #[test]
+    fn adds_missing_extensions() {
+        let names = fix_names(
+            "missing",
+            &[
+                ("photo", PNG),
+                ("backup", ZIP),
+                ("report.final", PNG),
+                ("notes.txt", b"plain text"),
+            ],
+        );
+
+        assert_eq!(names[0].0, "photo.png");
+        assert_eq!(names[1].0, "backup.zip");
+        assert_eq!(names[2].0, "report.final.png");
+        assert_eq!(names[3], (String::from("notes.txt"), None));
+    }
+}
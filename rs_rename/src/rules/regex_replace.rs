@@ -0,0 +1,280 @@
+use crate::error_factory::create_error;
+use crate::file_name::{join_name, split_name};
+use crate::rule::{Rule, RuleContext};
+use regex::{Regex, RegexBuilder};
+use rs_response::DataResponse;
+
+const ERR_SRC: &str = "rules::regex_replace::RegexRule";
+
+/// The part of the file name a rule operates on
+///
+/// - `Stem`: The file name without its extension
+/// - `Extension`: The extension, without the leading dot
+/// - `FullName`: The whole file name, including the extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchTarget {
+    #[default]
+    Stem,
+    Extension,
+    FullName,
+}
+impl MatchTarget {
+    /// Runs `transform` on the targeted part of `name` and returns the
+    /// rebuilt file name
+    ///
+    /// **NOTE:** If the result of transforming an `Extension` is empty,
+    /// the extension is dropped
+    pub(crate) fn map<F>(&self, name: &str, transform: F) -> DataResponse<String>
+    where
+        F: FnOnce(&str) -> DataResponse<String>,
+    {
+        match self {
+            Self::FullName => transform(name),
+            Self::Stem => {
+                let (stem, ext) = split_name(name);
+                Ok(join_name(&transform(stem)?, ext))
+            }
+            Self::Extension => match split_name(name) {
+                (stem, Some(ext)) => Ok(join_name(stem, Some(&transform(ext)?))),
+                (_, None) => Ok(name.to_string()),
+            },
+        }
+    }
+}
+
+/// Which matches of the pattern are replaced
+///
+/// - `First`: Only the first match
+/// - `All`: Every match
+/// - `Nth`: Only the *n*-th match, counting from `1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Occurrence {
+    First,
+    #[default]
+    All,
+    Nth(usize),
+}
+
+/// Options for creating a `RegexRule`
+///
+/// # Properties:
+/// - `target`: `MatchTarget` - The part of the file name to match against. Defaults to `Stem`
+/// - `occurrence`: `Occurrence` - Which matches to replace. Defaults to `All`
+/// - `case_insensitive`: `bool` - Whether to ignore letter case when matching. Defaults to `false`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RegexOptions {
+    pub target: MatchTarget,
+    pub occurrence: Occurrence,
+    pub case_insensitive: bool,
+}
+
+/// A `Rule` that finds a regular expression and replaces it
+///
+/// The replacement can refer to numbered (`$1`, `${1}`) and named
+/// (`$name`, `${name}`) capture groups. Use `$$` for a literal `$`
+///
+/// # Methods:
+/// - `new` - Compiles the pattern and creates a `RegexRule`
+///
+/// # Example:
+/// ```
+/// use rs_rename::rules::{MatchTarget, Occurrence, RegexOptions, RegexRule};
+/// use rs_rename::RulePipeline;
+/// use rs_response::DataResponse;
+///
+/// fn swap_date(pipeline: &mut RulePipeline) -> DataResponse<()> {
+///   let rule = RegexRule::new(
+///     r"(?P<day>\d{2})-(?P<month>\d{2})-(?P<year>\d{4})",
+///     "${year}-${month}-${day}",
+///     RegexOptions {
+///       target: MatchTarget::Stem,
+///       occurrence: Occurrence::All,
+///       case_insensitive: false,
+///     },
+///   )?;
+///
+///   pipeline.push(rule);
+///
+///   Ok(())
+/// }
+/// ```
+pub struct RegexRule {
+    regex: Regex,
+    replacement: String,
+    options: RegexOptions,
+}
+impl RegexRule {
+    /// Compiles the pattern and creates a `RegexRule`
+    ///
+    /// **NOTE:** An invalid pattern returns an error with the message
+    /// of the regular expression compiler as its `cause`
+    ///
+    /// # Arguments:
+    /// - `pattern`: `&str` - The regular expression to find
+    /// - `replacement`: `impl Into<String>` - The text to replace each match with
+    /// - `options`: `RegexOptions` - Which part of the name to match and which matches to replace
+    pub fn new(
+        pattern: &str,
+        replacement: impl Into<String>,
+        options: RegexOptions,
+    ) -> DataResponse<Self> {
+        if options.occurrence == Occurrence::Nth(0) {
+            return Err(create_error(
+                "Invalid regular expression rule",
+                "The occurrence to replace is counted from 1, but found 0",
+                ERR_SRC,
+            ));
+        }
+
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(options.case_insensitive)
+            .build()
+            .map_err(|e| {
+                create_error(
+                    format!("Invalid regular expression '{}'", pattern),
+                    e.to_string(),
+                    ERR_SRC,
+                )
+            })?;
+
+        Ok(Self {
+            regex,
+            replacement: replacement.into(),
+            options,
+        })
+    }
+
+    fn replace(&self, text: &str) -> String {
+        let replacement = self.replacement.as_str();
+
+        match self.options.occurrence {
+            Occurrence::All => self.regex.replace_all(text, replacement).to_string(),
+            Occurrence::First => self.regex.replace(text, replacement).to_string(),
+            Occurrence::Nth(n) => match self.regex.captures_iter(text).nth(n - 1) {
+                None => text.to_string(),
+                Some(caps) => {
+                    let found = caps.get(0).expect("group 0 is always present");
+                    let mut result = String::from(&text[..found.start()]);
+                    caps.expand(replacement, &mut result);
+                    result.push_str(&text[found.end()..]);
+                    result
+                }
+            },
+        }
+    }
+}
+impl Rule for RegexRule {
+    fn describe(&self) -> String {
+        format!(
+            "Replace '{}' with '{}'",
+            self.regex.as_str(),
+            self.replacement
+        )
+    }
+
+    fn apply(&self, name: &str, _ctx: &RuleContext) -> DataResponse<String> {
+        self.options.target.map(name, |text| Ok(self.replace(text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn rename(pattern: &str, replacement: &str, options: RegexOptions, name: &str) -> String {
+        let rule = match RegexRule::new(pattern, replacement, options) {
+            Ok(rule) => rule,
+            Err(e) => panic!("{}: {}", e.message, e.cause),
+        };
+        let ctx = RuleContext {
+            path: Path::new(name),
+            index: 0,
+        };
+        rule.apply(name, &ctx).unwrap()
+    }
+
+    #[test]
+    fn replaces_capture_groups() {
+        let options = RegexOptions::default();
+
+        assert_eq!(
+            rename(r"(\w+)-(\w+)", "$2-$1", options, "left-right.txt"),
+            "right-left.txt"
+        );
+        assert_eq!(
+            rename(
+                r"(?P<day>\d{2})-(?P<month>\d{2})-(?P<year>\d{4})",
+                "${year}-${month}-${day}",
+                options,
+                "scan 31-12-2023.pdf"
+            ),
+            "scan 2023-12-31.pdf"
+        );
+        assert_eq!(rename(r"\d+", "$$", options, "a1b22.txt"), "a$b$.txt");
+    }
+
+    #[test]
+    fn replaces_the_chosen_occurrence() {
+        let options = |occurrence| RegexOptions {
+            occurrence,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            rename("a", "_", options(Occurrence::All), "banana"),
+            "b_n_n_"
+        );
+        assert_eq!(
+            rename("a", "_", options(Occurrence::First), "banana"),
+            "b_nana"
+        );
+        assert_eq!(
+            rename("(a)", "[$1]", options(Occurrence::Nth(2)), "banana"),
+            "ban[a]na"
+        );
+        assert_eq!(
+            rename("a", "_", options(Occurrence::Nth(4)), "banana"),
+            "banana"
+        );
+    }
+
+    #[test]
+    fn matches_the_chosen_target() {
+        let options = |target| RegexOptions {
+            target,
+            case_insensitive: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            rename("JPEG", "jpg", options(MatchTarget::Stem), "jpeg.jpeg"),
+            "jpg.jpeg"
+        );
+        assert_eq!(
+            rename("JPEG", "jpg", options(MatchTarget::Extension), "jpeg.jpeg"),
+            "jpeg.jpg"
+        );
+        assert_eq!(
+            rename(r"\.", "_", options(MatchTarget::FullName), "a.b.c"),
+            "a_b_c"
+        );
+    }
+
+    #[test]
+    fn refuses_invalid_patterns() {
+        let error = match RegexRule::new("(unclosed", "", RegexOptions::default()) {
+            Ok(_) => panic!("an invalid pattern was compiled"),
+            Err(e) => e,
+        };
+        assert_eq!(error.category, "Rename");
+        assert_eq!(error.message, "Invalid regular expression '(unclosed'");
+        assert!(error.cause.contains("unclosed group"), "{}", error.cause);
+
+        let options = RegexOptions {
+            occurrence: Occurrence::Nth(0),
+            ..Default::default()
+        };
+        assert!(RegexRule::new("a", "b", options).is_err());
+    }
+}
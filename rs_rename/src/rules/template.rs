@@ -0,0 +1,65 @@
+use crate::rule::{Rule, RuleContext};
+use crate::template::{Template, TokenContext, TokenRegistry};
+use rs_response::DataResponse;
+use std::path::PathBuf;
+
+/// A `Rule` that replaces the whole file name with a rendered `Template`
+///
+/// **NOTE:** Trailing dots are removed from the result, so `{stem}.{ext}`
+/// gives `README` rather than `README.` for a file without an extension
+///
+/// # Methods:
+/// - `new` - Parses the template and creates a `TemplateRule`
+///
+/// # Example:
+/// ```
+/// use rs_rename::rules::TemplateRule;
+/// use rs_rename::template::TokenRegistry;
+/// use rs_rename::RulePipeline;
+/// use rs_response::DataResponse;
+///
+/// fn add_template(pipeline: &mut RulePipeline) -> DataResponse<()> {
+///   let rule = TemplateRule::new(
+///     "{stem}_{counter:03}_{mtime:%Y-%m-%d}.{ext|lower}",
+///     TokenRegistry::new(),
+///   )?;
+///
+///   pipeline.push(rule);
+///
+///   Ok(())
+/// }
+/// ```
+pub struct TemplateRule {
+    template: Template,
+}
+impl TemplateRule {
+    /// Parses the template and creates a `TemplateRule`
+    ///
+    /// # Arguments:
+    /// - `source`: `&str` - The template text
+    /// - `registry`: `TokenRegistry` - The tokens the template may use
+    pub fn new(source: &str, registry: TokenRegistry) -> DataResponse<Self> {
+        Ok(Self {
+            template: Template::parse(source, registry)?,
+        })
+    }
+}
+impl Rule for TemplateRule {
+    fn describe(&self) -> String {
+        format!("Rename to '{}'", self.template.source())
+    }
+
+    fn prepare(&mut self, sources: &[PathBuf]) -> DataResponse<()> {
+        self.template.prepare(sources)
+    }
+
+    fn apply(&self, name: &str, ctx: &RuleContext) -> DataResponse<String> {
+        let rendered = self.template.render(&TokenContext {
+            name,
+            path: ctx.path,
+            index: ctx.index,
+        })?;
+
+        Ok(rendered.trim_end_matches('.').to_string())
+    }
+}
@@ -0,0 +1,247 @@
+mod audio;
+mod builtin;
+mod document;
+mod exif;
+mod filter;
+mod hash;
+mod image;
+mod media;
+mod parser;
+mod shift;
+mod token;
+
+pub use audio::AudioTokens;
+pub use builtin::FileTokens;
+pub use document::DocumentTokens;
+pub use exif::ExifTokens;
+pub use filter::Filter;
+pub use hash::HashTokens;
+pub use image::ImageTokens;
+pub use media::MediaTokens;
+pub use shift::{ClockShifts, TimeShift};
+pub use token::{TokenContext, TokenProvider, TokenRegistry};
+
+use crate::error_factory::create_error;
+use parser::{Segment, Span};
+use rs_response::DataResponse;
+use std::path::PathBuf;
+
+const ERR_SRC: &str = "template::Template";
+
+/// A parsed file name template, such as `{stem}_{counter:03}.{ext|lower}`
+///
+/// # Syntax:
+/// - Text outside of braces is copied as is. Use `{{` and `}}` for literal braces
+/// - `{token}` inserts the value of a token
+/// - `{token:argument}` passes an argument to the token, such as a padding or a date format
+/// - `{token|filter|filter:argument}` runs the value through one or more filters
+/// - A `\` inside an argument escapes the next character, such as `\|` or `\}`
+///
+/// # Methods:
+/// - `parse` - Parses and validates a template against a `TokenRegistry`
+/// - `source` - The original template text
+/// - `prepare` - Prepares every token provider for a new batch
+/// - `render` - Renders the template for a single file
+///
+/// # Example:
+/// ```
+/// use rs_rename::template::{Template, TokenContext, TokenRegistry};
+/// use std::path::Path;
+///
+/// let template = Template::parse("{stem|upper}_{counter:03}.{ext}", TokenRegistry::new()).unwrap();
+///
+/// let ctx = TokenContext {
+///   name: "holiday.jpg",
+///   path: Path::new("photos/holiday.jpg"),
+///   index: 6,
+/// };
+///
+/// assert_eq!(template.render(&ctx).unwrap(), "HOLIDAY_007.jpg");
+///
+/// let err = Template::parse("{stem|shout}", TokenRegistry::new()).err().unwrap();
+/// assert_eq!(err.cause, "Unknown filter 'shout' at columns 7-11: 'shout'");
+/// ```
+pub struct Template {
+    source: String,
+    segments: Vec<Segment>,
+    registry: TokenRegistry,
+}
+impl Template {
+    /// Parses and validates a template against a `TokenRegistry`
+    ///
+    /// **NOTE:** Errors point at the column of the mistake in their `cause`
+    ///
+    /// # Arguments:
+    /// - `source`: `&str` - The template text
+    /// - `registry`: `TokenRegistry` - The tokens the template may use
+    pub fn parse(source: &str, mut registry: TokenRegistry) -> DataResponse<Self> {
+        let segments = parser::parse(source, &registry)
+            .map_err(|(message, span)| parse_error(source, &message, span))?;
+
+        let tokens: Vec<&str> = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                Segment::Token(token) => Some(token.name.as_str()),
+                Segment::Literal(_) => None,
+            })
+            .collect();
+        registry.select(&tokens);
+
+        Ok(Self {
+            source: source.to_string(),
+            segments,
+            registry,
+        })
+    }
+
+    /// The original template text
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Prepares every token provider for a new batch
+    ///
+    /// # Arguments:
+    /// - `sources`: `&[PathBuf]` - The source paths of the whole batch
+    pub fn prepare(&mut self, sources: &[PathBuf]) -> DataResponse<()> {
+        self.registry.prepare(sources)
+    }
+
+    /// Renders the template for a single file
+    ///
+    /// **NOTE:** Tokens without a value are rendered as empty text,
+    /// unless a `default` filter provides one
+    ///
+    /// # Arguments:
+    /// - `ctx`: `&TokenContext` - The file to render the template for
+    pub fn render(&self, ctx: &TokenContext) -> DataResponse<String> {
+        let mut result = String::new();
+
+        for segment in self.segments.iter() {
+            match segment {
+                Segment::Literal(text) => result.push_str(text),
+                Segment::Token(token) => {
+                    let provider = self.registry.find(&token.name).ok_or_else(|| {
+                        create_error(
+                            "Unable to render the template",
+                            format!("DEVELOPER ERROR: Unknown token '{}'", token.name),
+                            ERR_SRC,
+                        )
+                    })?;
+
+                    let mut value =
+                        provider.resolve(&token.name, token.argument.as_deref(), ctx)?;
+                    for filter in token.filters.iter() {
+                        value = filter.apply(value);
+                    }
+
+                    result.push_str(&value.unwrap_or_default());
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+fn parse_error(source: &str, message: &str, span: Span) -> rs_response::ErrorRepsonse {
+    let snippet: String = source
+        .chars()
+        .skip(span.start)
+        .take(span.end - span.start)
+        .collect();
+
+    let columns = match span.end - span.start {
+        0 | 1 => format!("column {}", span.start + 1),
+        _ => format!("columns {}-{}", span.start + 1, span.end),
+    };
+
+    create_error(
+        format!("Invalid template '{}'", source),
+        format!("{} at {}: '{}'", message, columns, snippet),
+        ERR_SRC,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn render(source: &str, name: &str) -> String {
+        let template = match Template::parse(source, TokenRegistry::new()) {
+            Ok(template) => template,
+            Err(e) => panic!("{}: {}", e.message, e.cause),
+        };
+        let ctx = TokenContext {
+            name,
+            path: Path::new(name),
+            index: 0,
+        };
+        template.render(&ctx).unwrap()
+    }
+
+    fn parse_error(source: &str) -> String {
+        match Template::parse(source, TokenRegistry::new()) {
+            Ok(_) => panic!("'{}' was parsed", source),
+            Err(e) => e.cause,
+        }
+    }
+
+    #[test]
+    fn renders_tokens_and_literals() {
+        assert_eq!(render("{stem}_{counter:03}.{ext}", "a.txt"), "a_001.txt");
+        assert_eq!(render("{{{name}}}", "a.txt"), "{a.txt}");
+        assert_eq!(render("{ext|default:none}", "README"), "none");
+        assert_eq!(render(r"{ext|default:a\|b\}}", "README"), "a|b}");
+    }
+
+    #[test]
+    fn applies_each_filter() {
+        assert_eq!(render("{stem|upper}", "Mixed Case"), "MIXED CASE");
+        assert_eq!(render("{stem|lower}", "Mixed Case"), "mixed case");
+        assert_eq!(render("{stem|trim}", "  padded  "), "padded");
+        assert_eq!(render("{stem|default:x}", ".txt"), ".txt");
+        assert_eq!(render("{ext|default}", "README"), "");
+        assert_eq!(render("{stem|slice:0:3}", "abcdef"), "abc");
+        assert_eq!(render("{stem|slice:2}", "abcdef"), "cdef");
+        assert_eq!(render("{stem|slice:-2}", "abcdef"), "ef");
+        assert_eq!(render("{stem|slice:1:-1}", "abcdef"), "bcde");
+        assert_eq!(render("{stem|slice:4:2}", "abcdef"), "");
+        assert_eq!(render("{stem|slice:0:99}", "héllo"), "héllo");
+        assert_eq!(render("{stem|trim|slice:0:1|upper}", " ab "), "A");
+    }
+
+    #[test]
+    fn points_at_the_mistake() {
+        assert_eq!(
+            parse_error("a{stem"),
+            "Unclosed '{', expected '}' at columns 2-6: '{stem'"
+        );
+        assert_eq!(
+            parse_error("{stem|shout}"),
+            "Unknown filter 'shout' at columns 7-11: 'shout'"
+        );
+        assert_eq!(
+            parse_error("{stem|slice:a:3}"),
+            "'a' is not a valid slice index at columns 13-15: 'a:3'"
+        );
+        assert_eq!(
+            parse_error("{stem|upper:x}"),
+            "The 'upper' filter does not take an argument at column 13: 'x'"
+        );
+        assert_eq!(
+            parse_error("{nope}"),
+            "Unknown token 'nope' at columns 2-5: 'nope'"
+        );
+        assert_eq!(
+            parse_error("{counter:x}"),
+            "'x' is not a valid counter padding at column 10: 'x'"
+        );
+        assert_eq!(
+            parse_error("a}b"),
+            "Unmatched '}', use '}}' for a literal brace at column 2: '}'"
+        );
+        assert_eq!(parse_error("{}"), "Missing token name at columns 1-2: '{}'");
+    }
+}
@@ -0,0 +1,166 @@
+use super::builtin::safe_text;
+use super::token::{MetadataCache, TokenContext, TokenProvider};
+use crate::error_factory::create_error;
+use rs_fs::{read_audio_tags, AudioTags};
+use rs_response::DataResponse;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const ERR_SRC: &str = "template::audio::AudioTokens";
+
+const TOKENS: [&str; 7] = [
+    "audio.title",
+    "audio.artist",
+    "audio.album",
+    "audio.track",
+    "audio.disc",
+    "audio.year",
+    "audio.genre",
+];
+
+/// The tags of audio files, available in every `TokenRegistry::new`
+///
+/// | Token          | Argument           | Value                     |
+/// | -------------- | ------------------ | ------------------------- |
+/// | `audio.title`  |                    | The title of the track    |
+/// | `audio.artist` |                    | The performing artist     |
+/// | `audio.album`  |                    | The album title           |
+/// | `audio.track`  | Padding, e.g. `02` | The track number          |
+/// | `audio.disc`   | Padding, e.g. `02` | The disc number           |
+/// | `audio.year`   |                    | The release year          |
+/// | `audio.genre`  |                    | The genre                 |
+///
+/// **NOTE:** Tags are read from ID3v1/ID3v2 (MP3), Vorbis comments (FLAC,
+/// Ogg) and MP4 atoms (M4A). A `/` or `\` in a tag, such as in `AC/DC`, is
+/// written as `-`, as file names cannot contain them
+///
+/// **NOTE:** A missing tag falls back to the default set for its token with
+/// `set_default`, and has no value otherwise. The `default` filter can still
+/// provide one, e.g. `{audio.album|default:Singles}`
+///
+/// **NOTE:** The tags of every file are read once per batch
+///
+/// # Methods:
+/// - `new` - Creates a new `AudioTokens` without defaults
+/// - `set_default` - Sets the value of a token for files without that tag
+///
+/// # Example:
+/// ```
+/// use rs_rename::template::{AudioTokens, FileTokens, TokenRegistry};
+/// use rs_response::DataResponse;
+///
+/// fn music_registry() -> DataResponse<TokenRegistry> {
+///   let mut audio = AudioTokens::new();
+///   audio
+///     .set_default("audio.artist", "Unknown Artist")?
+///     .set_default("audio.track", "00")?;
+///
+///   let mut registry = TokenRegistry::empty();
+///   registry.register(FileTokens::new()).register(audio);
+///
+///   Ok(registry)
+/// }
+/// ```
+pub struct AudioTokens {
+    defaults: HashMap<&'static str, String>,
+    cache: MetadataCache<AudioTags>,
+}
+impl Default for AudioTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl AudioTokens {
+    /// Creates a new `AudioTokens` without defaults
+    pub fn new() -> Self {
+        Self {
+            defaults: HashMap::new(),
+            cache: MetadataCache::new(),
+        }
+    }
+
+    /// Sets the value of a token for files without that tag
+    ///
+    /// **NOTE:** The default is used as is, without padding
+    ///
+    /// # Arguments:
+    /// - `token`: `&str` - The token, such as `audio.artist`
+    /// - `value`: `impl Into<String>` - The value to use when the tag is missing
+    pub fn set_default(
+        &mut self,
+        token: &str,
+        value: impl Into<String>,
+    ) -> DataResponse<&mut Self> {
+        let token = TOKENS
+            .into_iter()
+            .find(|known| *known == token)
+            .ok_or_else(|| {
+                create_error(
+                    format!("Cannot set a default for '{}'", token),
+                    format!(
+                        "It is not an audio token. Use one of: {}",
+                        TOKENS.join(", ")
+                    ),
+                    ERR_SRC,
+                )
+            })?;
+
+        self.defaults.insert(token, value.into());
+        Ok(self)
+    }
+
+    fn read(&self, ctx: &TokenContext) -> DataResponse<Option<AudioTags>> {
+        self.cache.read(ctx.path, read_audio_tags)
+    }
+}
+impl TokenProvider for AudioTokens {
+    fn tokens(&self) -> Vec<&'static str> {
+        TOKENS.to_vec()
+    }
+
+    fn validate(&self, token: &str, argument: Option<&str>) -> Result<(), String> {
+        match (token, argument) {
+            (_, None) => Ok(()),
+            ("audio.track", Some(padding)) | ("audio.disc", Some(padding)) => {
+                match padding.parse::<usize>() {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(format!("'{}' is not a valid padding", padding)),
+                }
+            }
+            (_, Some(_)) => Err(format!("The '{}' token does not take an argument", token)),
+        }
+    }
+
+    fn prepare(&mut self, _sources: &[PathBuf]) -> DataResponse<()> {
+        self.cache.clear();
+
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        token: &str,
+        argument: Option<&str>,
+        ctx: &TokenContext,
+    ) -> DataResponse<Option<String>> {
+        let tags = self.read(ctx)?.unwrap_or_default();
+
+        let pad = |number: u32| {
+            let width = argument.and_then(|arg| arg.parse().ok()).unwrap_or(0);
+            format!("{:0width$}", number, width = width)
+        };
+
+        let value = match token {
+            "audio.title" => tags.title.map(safe_text),
+            "audio.artist" => tags.artist.map(safe_text),
+            "audio.album" => tags.album.map(safe_text),
+            "audio.track" => tags.track.map(pad),
+            "audio.disc" => tags.disc.map(pad),
+            "audio.year" => tags.year.map(|year| year.to_string()),
+            "audio.genre" => tags.genre.map(safe_text),
+            _ => None,
+        };
+
+        Ok(value.or_else(|| self.defaults.get(token).cloned()))
+    }
+}
@@ -0,0 +1,308 @@
+use super::shift::ClockShifts;
+use super::token::{TokenContext, TokenProvider};
+use crate::error_factory::create_error;
+use crate::file_name::split_name;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use rs_response::DataResponse;
+use std::fmt::Write;
+use std::fs;
+use std::time::SystemTime;
+
+const ERR_SRC: &str = "template::builtin::FileTokens";
+pub(crate) const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The built-in tokens, available in every `TokenRegistry::new`
+///
+/// | Token      | Argument            | Value                                          |
+/// | ---------- | ------------------- | ---------------------------------------------- |
+/// | `name`     |                     | The current file name                          |
+/// | `stem`     |                     | The current file name without its extension    |
+/// | `ext`      |                     | The current extension, without the leading dot |
+/// | `parent`   |                     | The name of the parent folder                  |
+/// | `counter`  | Padding, e.g. `03`  | The position in the batch, starting at `1`     |
+/// | `size`     |                     | The file size in bytes                         |
+/// | `mtime`    | Format, e.g. `%Y`   | The last modification time                     |
+/// | `created`  | Format, e.g. `%Y`   | The creation time                              |
+///
+/// **NOTE:** Dates use `strftime` formats and default to `%Y-%m-%d`
+///
+/// **NOTE:** `mtime` and `created` can be corrected for a selection of
+/// files, see `with_shifts`
+///
+/// # Methods:
+/// - `new` - Creates a new `FileTokens`
+/// - `with_shifts` - Creates a new `FileTokens` that corrects `mtime` and `created`
+pub struct FileTokens {
+    shifts: ClockShifts,
+}
+impl Default for FileTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl FileTokens {
+    /// Creates a new `FileTokens`
+    pub fn new() -> Self {
+        Self::with_shifts(ClockShifts::new())
+    }
+
+    /// Creates a new `FileTokens` that corrects `mtime` and `created`
+    ///
+    /// **NOTE:** Only the shifts set for files are used, as these tokens
+    /// do not know the camera model of a file
+    ///
+    /// # Arguments:
+    /// - `shifts`: `ClockShifts` - The clock corrections, by file
+    pub fn with_shifts(shifts: ClockShifts) -> Self {
+        Self { shifts }
+    }
+
+    fn shift(&self, ctx: &TokenContext, time: SystemTime) -> Option<SystemTime> {
+        match self.shifts.find(ctx.path, None) {
+            Some(shift) => shift.apply_to_time(time),
+            None => Some(time),
+        }
+    }
+}
+impl TokenProvider for FileTokens {
+    fn tokens(&self) -> Vec<&'static str> {
+        vec![
+            "name", "stem", "ext", "parent", "counter", "size", "mtime", "created",
+        ]
+    }
+
+    fn validate(&self, token: &str, argument: Option<&str>) -> Result<(), String> {
+        if matches!(token, "mtime" | "created") {
+            self.shifts.validate_by_file(token)?;
+        }
+
+        match (token, argument) {
+            (_, None) => Ok(()),
+            ("counter", Some(padding)) => match padding.parse::<usize>() {
+                Ok(_) => Ok(()),
+                Err(_) => Err(format!("'{}' is not a valid counter padding", padding)),
+            },
+            ("mtime", Some(format)) | ("created", Some(format)) => validate_date_format(format),
+            (_, Some(_)) => Err(format!("The '{}' token does not take an argument", token)),
+        }
+    }
+
+    fn resolve(
+        &self,
+        token: &str,
+        argument: Option<&str>,
+        ctx: &TokenContext,
+    ) -> DataResponse<Option<String>> {
+        let value = match token {
+            "name" => Some(ctx.name.to_string()),
+            "stem" => Some(split_name(ctx.name).0.to_string()),
+            "ext" => split_name(ctx.name).1.map(String::from),
+            "parent" => ctx
+                .path
+                .parent()
+                .and_then(|parent| parent.file_name())
+                .map(|name| name.to_string_lossy().to_string()),
+            "counter" => {
+                let width = argument.and_then(|arg| arg.parse().ok()).unwrap_or(0);
+                Some(format!("{:0width$}", ctx.index + 1, width = width))
+            }
+            "size" => Some(read_metadata(ctx, |meta| Ok(meta.len()))?.to_string()),
+            "mtime" => self
+                .shift(ctx, read_metadata(ctx, |meta| meta.modified())?)
+                .map(|time| format_date(time, argument)),
+            "created" => self
+                .shift(ctx, read_metadata(ctx, |meta| meta.created())?)
+                .map(|time| format_date(time, argument)),
+            _ => None,
+        };
+
+        Ok(value)
+    }
+}
+
+/// Checks that a `strftime` format only contains valid specifiers
+pub(crate) fn validate_date_format(format: &str) -> Result<(), String> {
+    match StrftimeItems::new(format).any(|item| item == Item::Error) {
+        true => Err(format!("'{}' is not a valid date format", format)),
+        false => Ok(()),
+    }
+}
+
+/// Checks that a `strftime` format can be used on dates without a timezone,
+/// such as the ones read from EXIF or document metadata
+///
+/// **NOTE:** Timezone specifiers such as `%z` and `%Z` are refused
+pub(crate) fn validate_naive_date_format(format: &str) -> Result<(), String> {
+    validate_date_format(format)?;
+
+    let sample = NaiveDate::from_ymd_opt(2000, 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .unwrap_or_default();
+    let mut formatted = String::new();
+    match write!(formatted, "{}", sample.format(format)) {
+        Ok(()) => Ok(()),
+        Err(_) => Err(format!(
+            "'{}' cannot be used on this date, as it has no timezone",
+            format
+        )),
+    }
+}
+
+/// Formats a date without a timezone with a `strftime` format
+///
+/// **NOTE:** The format should be checked with `validate_naive_date_format`.
+/// A format that still cannot be used is reported as an error
+pub(crate) fn format_naive_date(date: NaiveDateTime, format: Option<&str>) -> DataResponse<String> {
+    let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+
+    let mut formatted = String::new();
+    write!(formatted, "{}", date.format(format)).map_err(|_| {
+        create_error(
+            format!("Unable to format the date {}", date),
+            format!("'{}' cannot be used on a date without a timezone", format),
+            ERR_SRC,
+        )
+    })?;
+
+    Ok(formatted)
+}
+
+/// Formats a time in the local timezone with a `strftime` format
+pub(crate) fn format_date(time: SystemTime, format: Option<&str>) -> String {
+    let time: DateTime<Local> = time.into();
+    time.format(format.unwrap_or(DEFAULT_DATE_FORMAT))
+        .to_string()
+}
+
+/// Makes a metadata value safe to use in a file name, such as `AC/DC`
+///
+/// **NOTE:** Path separators are replaced with `-`, and control characters,
+/// such as the null padding of EXIF values, are removed
+pub(crate) fn safe_text(value: String) -> String {
+    value
+        .replace(['/', '\\'], "-")
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn read_metadata<T>(
+    ctx: &TokenContext,
+    read: impl FnOnce(fs::Metadata) -> std::io::Result<T>,
+) -> DataResponse<T> {
+    fs::metadata(ctx.path).and_then(read).map_err(|e| {
+        create_error(
+            format!("Unable to read the metadata of '{}'", ctx.path.display()),
+            e.to_string(),
+            ERR_SRC,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::TimeShift;
+
+    fn sample_date() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 4, 1)
+            .and_then(|date| date.and_hms_opt(11, 30, 0))
+            .unwrap()
+    }
+
+    #[test]
+    fn formats_naive_dates() {
+        assert_eq!(
+            format_naive_date(sample_date(), None).unwrap(),
+            "2023-04-01"
+        );
+        assert_eq!(
+            format_naive_date(sample_date(), Some("%Y%m%d-%H%M")).unwrap(),
+            "20230401-1130"
+        );
+    }
+
+    #[test]
+    fn refuses_timezones_on_naive_dates() {
+        for format in ["%Y-%z", "%:z", "%Z", "%+"] {
+            assert!(validate_naive_date_format(format).is_err(), "{}", format);
+            assert!(format_naive_date(sample_date(), Some(format)).is_err());
+        }
+        assert!(validate_naive_date_format("%Y-%m-%d %H.%M").is_ok());
+    }
+
+    #[test]
+    fn makes_text_safe_for_file_names() {
+        assert_eq!(safe_text(String::from("AC/DC")), "AC-DC");
+        assert_eq!(safe_text(String::from("a\\b")), "a-b");
+        assert_eq!(safe_text(String::from("Canon\0\0 ")), "Canon");
+        assert_eq!(safe_text(String::from("one\ttwo")), "onetwo");
+    }
+
+    #[test]
+    fn shifts_file_times_of_selected_files() {
+        use std::path::PathBuf;
+        use std::time::Duration;
+
+        let folder =
+            std::env::temp_dir().join(format!("rs_rename-builtin-shift-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_680_000_000);
+        let paths: Vec<PathBuf> = ["a.jpg", "b.jpg"]
+            .iter()
+            .map(|name| folder.join(name))
+            .collect();
+        for path in paths.iter() {
+            fs::File::create(path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+
+        let mut shifts = ClockShifts::new();
+        shifts.for_files([paths[0].clone()], TimeShift::parse("+1d 2h").unwrap());
+        let tokens = FileTokens::with_shifts(shifts);
+        let mtime = |path: &PathBuf| {
+            let ctx = TokenContext {
+                name: "a.jpg",
+                path,
+                index: 0,
+            };
+            tokens
+                .resolve("mtime", Some("%Y-%m-%d %H:%M"), &ctx)
+                .unwrap()
+        };
+        let shifted = mtime(&paths[0]);
+        let unchanged = mtime(&paths[1]);
+        fs::remove_dir_all(&folder).unwrap();
+
+        let expected = modified + Duration::from_secs(26 * 3600);
+        assert_eq!(shifted, Some(format_date(expected, Some("%Y-%m-%d %H:%M"))));
+        assert_eq!(
+            unchanged,
+            Some(format_date(modified, Some("%Y-%m-%d %H:%M")))
+        );
+    }
+
+    #[test]
+    fn refuses_camera_model_shifts_on_file_times() {
+        let mut shifts = ClockShifts::new();
+        shifts.for_model("X-T4", TimeShift::parse("+2h").unwrap());
+        let tokens = FileTokens::with_shifts(shifts);
+
+        assert!(tokens.validate("mtime", None).is_err());
+        assert!(tokens.validate("created", Some("%Y")).is_err());
+        assert!(tokens.validate("counter", Some("03")).is_ok());
+        assert!(FileTokens::new().validate("mtime", Some("%Y")).is_ok());
+    }
+
+    #[test]
+    fn refuses_invalid_specifiers() {
+        assert!(validate_date_format("%Y-%Q").is_err());
+        assert!(validate_date_format("%Y-%m").is_ok());
+    }
+}
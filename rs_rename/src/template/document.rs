@@ -0,0 +1,217 @@
+use super::builtin::{format_naive_date, safe_text, validate_naive_date_format};
+use super::shift::ClockShifts;
+use super::token::{MetadataCache, TokenContext, TokenProvider};
+use chrono::NaiveDateTime;
+use rs_fs::{read_document_info, DocumentInfo};
+use rs_response::DataResponse;
+use std::path::PathBuf;
+
+/// The metadata of PDF files, EPUB books and office documents, available in
+/// every `TokenRegistry::new`
+///
+/// | Token            | Argument          | Value                                  |
+/// | ---------------- | ----------------- | -------------------------------------- |
+/// | `doc.title`      |                   | The title of the document              |
+/// | `doc.author`     |                   | The author, or the creator of a book   |
+/// | `doc.subject`    |                   | The subject or description             |
+/// | `doc.language`   |                   | The language code, e.g. `en`           |
+/// | `doc.series`     |                   | The series a book belongs to           |
+/// | `doc.modifiedby` |                   | The last person who modified the file  |
+/// | `doc.created`    | Format, e.g. `%Y` | When the document was created          |
+/// | `doc.modified`   | Format, e.g. `%Y` | When the document was last modified    |
+///
+/// **NOTE:** PDF files provide the title, author, subject and both dates,
+/// from their information dictionary or their XMP metadata. EPUB books
+/// provide the title, author, subject, language and series, from their OPF
+/// package. Office Open XML files (.docx, .xlsx, .pptx) and OpenDocument
+/// files (.odt, .ods) provide the title, author, subject, language, last
+/// modifier and both dates. A `/` or `\` in a value is written as `-`, as
+/// file names cannot contain them
+///
+/// **NOTE:** Other files, and encrypted PDF files, have no value for these
+/// tokens. Use the `default` filter to provide one
+///
+/// **NOTE:** `doc.created` and `doc.modified` can be corrected for a
+/// selection of files, see `with_shifts`
+///
+/// **NOTE:** The metadata of every file is read once per batch
+///
+/// # Methods:
+/// - `new` - Creates a new `DocumentTokens` with an empty cache
+/// - `with_shifts` - Creates a new `DocumentTokens` that corrects `doc.created` and `doc.modified`
+///
+/// # Example:
+/// ```
+/// use rs_rename::template::{Template, TokenRegistry};
+///
+/// let template = Template::parse(
+///   "{doc.author|default:Unknown} - {doc.title|default:Untitled}.{ext}",
+///   TokenRegistry::new(),
+/// );
+///
+/// assert!(template.is_ok());
+/// ```
+pub struct DocumentTokens {
+    shifts: ClockShifts,
+    cache: MetadataCache<DocumentInfo>,
+}
+impl Default for DocumentTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl DocumentTokens {
+    /// Creates a new `DocumentTokens` with an empty cache
+    pub fn new() -> Self {
+        Self::with_shifts(ClockShifts::new())
+    }
+
+    /// Creates a new `DocumentTokens` that corrects `doc.created` and `doc.modified`
+    ///
+    /// **NOTE:** Only the shifts set for files are used, as documents have
+    /// no camera model
+    ///
+    /// # Arguments:
+    /// - `shifts`: `ClockShifts` - The clock corrections, by file
+    pub fn with_shifts(shifts: ClockShifts) -> Self {
+        Self {
+            shifts,
+            cache: MetadataCache::new(),
+        }
+    }
+
+    fn read(&self, ctx: &TokenContext) -> DataResponse<Option<DocumentInfo>> {
+        self.cache.read(ctx.path, read_document_info)
+    }
+
+    fn shift(&self, ctx: &TokenContext, date: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self.shifts.find(ctx.path, None) {
+            Some(shift) => shift.apply(date),
+            None => Some(date),
+        }
+    }
+}
+impl TokenProvider for DocumentTokens {
+    fn tokens(&self) -> Vec<&'static str> {
+        vec![
+            "doc.title",
+            "doc.author",
+            "doc.subject",
+            "doc.language",
+            "doc.series",
+            "doc.modifiedby",
+            "doc.created",
+            "doc.modified",
+        ]
+    }
+
+    fn validate(&self, token: &str, argument: Option<&str>) -> Result<(), String> {
+        if matches!(token, "doc.created" | "doc.modified") {
+            self.shifts.validate_by_file(token)?;
+        }
+
+        match (token, argument) {
+            (_, None) => Ok(()),
+            ("doc.created" | "doc.modified", Some(format)) => validate_naive_date_format(format),
+            (_, Some(_)) => Err(format!("The '{}' token does not take an argument", token)),
+        }
+    }
+
+    fn prepare(&mut self, _sources: &[PathBuf]) -> DataResponse<()> {
+        self.cache.clear();
+
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        token: &str,
+        argument: Option<&str>,
+        ctx: &TokenContext,
+    ) -> DataResponse<Option<String>> {
+        let info = match self.read(ctx)? {
+            Some(info) => info,
+            None => return Ok(None),
+        };
+
+        let value = match token {
+            "doc.title" => info.title.map(safe_text),
+            "doc.author" => info.author.map(safe_text),
+            "doc.subject" => info.subject.map(safe_text),
+            "doc.language" => info.language.map(safe_text),
+            "doc.series" => info.series.map(safe_text),
+            "doc.modifiedby" => info.modified_by.map(safe_text),
+            "doc.created" => info
+                .created
+                .and_then(|date| self.shift(ctx, date))
+                .map(|date| format_naive_date(date, argument))
+                .transpose()?,
+            "doc.modified" => info
+                .modified
+                .and_then(|date| self.shift(ctx, date))
+                .map(|date| format_naive_date(date, argument))
+                .transpose()?,
+            _ => None,
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::TimeShift;
+    use chrono::NaiveDate;
+    use std::path::Path;
+
+    #[test]
+    fn validates_date_formats() {
+        let tokens = DocumentTokens::new();
+
+        assert!(tokens.validate("doc.created", Some("%Y-%m-%d")).is_ok());
+        assert!(tokens.validate("doc.created", Some("%Y-%z")).is_err());
+        assert!(tokens.validate("doc.created", Some("%:z")).is_err());
+        assert!(tokens.validate("doc.modified", Some("%Y%Z")).is_err());
+        assert!(tokens.validate("doc.title", Some("%Y")).is_err());
+    }
+
+    #[test]
+    fn shifts_dates_of_selected_files() {
+        let path = Path::new("report.pdf");
+        let created = NaiveDate::from_ymd_opt(2023, 4, 1)
+            .and_then(|date| date.and_hms_opt(23, 30, 0))
+            .unwrap();
+        let mut shifts = ClockShifts::new();
+        shifts.for_files([path.to_path_buf()], TimeShift::parse("+1h").unwrap());
+
+        let tokens = DocumentTokens::with_shifts(shifts);
+        let info = DocumentInfo {
+            created: Some(created),
+            modified: Some(created),
+            ..Default::default()
+        };
+        tokens.cache.read(path, |_| Ok(Some(info))).unwrap();
+
+        let ctx = TokenContext {
+            name: "report.pdf",
+            path,
+            index: 0,
+        };
+        let resolve = |token| tokens.resolve(token, Some("%Y-%m-%d %H.%M"), &ctx).unwrap();
+
+        assert_eq!(resolve("doc.created").as_deref(), Some("2023-04-02 00.30"));
+        assert_eq!(resolve("doc.modified").as_deref(), Some("2023-04-02 00.30"));
+    }
+
+    #[test]
+    fn refuses_camera_model_shifts() {
+        let mut shifts = ClockShifts::new();
+        shifts.for_model("X-T4", TimeShift::parse("+2h").unwrap());
+        let tokens = DocumentTokens::with_shifts(shifts);
+
+        assert!(tokens.validate("doc.created", None).is_err());
+        assert!(tokens.validate("doc.modified", Some("%Y")).is_err());
+        assert!(tokens.validate("doc.title", None).is_ok());
+    }
+}
@@ -0,0 +1,203 @@
+use super::builtin::{format_naive_date, safe_text, validate_naive_date_format};
+use super::shift::ClockShifts;
+use super::token::{MetadataCache, TokenContext, TokenProvider};
+use rs_fs::{read_exif, ExifData};
+use rs_response::DataResponse;
+use std::path::PathBuf;
+
+/// The EXIF tokens of photos, available in every `TokenRegistry::new`
+///
+/// | Token              | Argument          | Value                                         |
+/// | ------------------ | ----------------- | --------------------------------------------- |
+/// | `exif.date`        | Format, e.g. `%Y` | When the photo was taken                      |
+/// | `exif.make`        |                   | The camera maker                              |
+/// | `exif.model`       |                   | The camera model                              |
+/// | `exif.lens`        |                   | The lens model                                |
+/// | `exif.iso`         |                   | The ISO speed, e.g. `400`                     |
+/// | `exif.exposure`    |                   | The exposure time in seconds, e.g. `1-250`    |
+/// | `exif.aperture`    |                   | The f-number, e.g. `2.8`                      |
+/// | `exif.focal`       |                   | The focal length in millimeters, e.g. `50`    |
+/// | `exif.orientation` |                   | The EXIF orientation, from `1` to `8`         |
+///
+/// **NOTE:** Dates use `strftime` formats and default to `%Y-%m-%d`. Exposure
+/// times under a second are written as `1-250` rather than `1/250`, as file
+/// names cannot contain a `/`
+///
+/// **NOTE:** Files without EXIF metadata have no value for these tokens. Use
+/// the `default` filter to provide one, e.g. `{exif.model|default:unknown}`
+///
+/// **NOTE:** `exif.date` can be corrected for cameras with a wrong clock or
+/// timezone, see `with_shifts`
+///
+/// **NOTE:** The metadata of every file is read once per batch
+///
+/// # Methods:
+/// - `new` - Creates a new `ExifTokens` with an empty cache
+/// - `with_shifts` - Creates a new `ExifTokens` that corrects `exif.date`
+pub struct ExifTokens {
+    shifts: ClockShifts,
+    cache: MetadataCache<ExifData>,
+}
+impl Default for ExifTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl ExifTokens {
+    /// Creates a new `ExifTokens` with an empty cache
+    pub fn new() -> Self {
+        Self::with_shifts(ClockShifts::new())
+    }
+
+    /// Creates a new `ExifTokens` that corrects `exif.date`
+    ///
+    /// # Arguments:
+    /// - `shifts`: `ClockShifts` - The clock corrections, by camera model and by file
+    pub fn with_shifts(shifts: ClockShifts) -> Self {
+        Self {
+            shifts,
+            cache: MetadataCache::new(),
+        }
+    }
+
+    fn read(&self, ctx: &TokenContext) -> DataResponse<Option<ExifData>> {
+        self.cache.read(ctx.path, read_exif)
+    }
+}
+impl TokenProvider for ExifTokens {
+    fn tokens(&self) -> Vec<&'static str> {
+        vec![
+            "exif.date",
+            "exif.make",
+            "exif.model",
+            "exif.lens",
+            "exif.iso",
+            "exif.exposure",
+            "exif.aperture",
+            "exif.focal",
+            "exif.orientation",
+        ]
+    }
+
+    fn validate(&self, token: &str, argument: Option<&str>) -> Result<(), String> {
+        match (token, argument) {
+            (_, None) => Ok(()),
+            ("exif.date", Some(format)) => validate_naive_date_format(format),
+            (_, Some(_)) => Err(format!("The '{}' token does not take an argument", token)),
+        }
+    }
+
+    fn prepare(&mut self, _sources: &[PathBuf]) -> DataResponse<()> {
+        self.cache.clear();
+
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        token: &str,
+        argument: Option<&str>,
+        ctx: &TokenContext,
+    ) -> DataResponse<Option<String>> {
+        let exif = match self.read(ctx)? {
+            Some(exif) => exif,
+            None => return Ok(None),
+        };
+
+        let value = match token {
+            "exif.date" => exif
+                .date_taken
+                .and_then(
+                    |date| match self.shifts.find(ctx.path, exif.model.as_deref()) {
+                        Some(shift) => shift.apply(date),
+                        None => Some(date),
+                    },
+                )
+                .map(|date| format_naive_date(date, argument))
+                .transpose()?,
+            "exif.make" => exif.make.map(safe_text),
+            "exif.model" => exif.model.map(safe_text),
+            "exif.lens" => exif.lens.map(safe_text),
+            "exif.iso" => exif.iso.map(|iso| iso.to_string()),
+            "exif.exposure" => exif.exposure_time.map(format_exposure),
+            "exif.aperture" => exif.f_number.map(format_number),
+            "exif.focal" => exif.focal_length.map(format_number),
+            "exif.orientation" => exif.orientation.map(|orientation| orientation.to_string()),
+            _ => None,
+        };
+
+        Ok(value)
+    }
+}
+
+/// Writes an exposure time as `1-250` under a second, and in seconds otherwise
+fn format_exposure(seconds: f64) -> String {
+    match seconds > 0.0 && seconds < 1.0 {
+        true => format!("1-{}", (1.0 / seconds).round()),
+        false => format_number(seconds),
+    }
+}
+
+/// Writes a number with at most one decimal, without trailing zeros
+fn format_number(value: f64) -> String {
+    let value = format!("{:.1}", value);
+
+    match value.strip_suffix(".0") {
+        Some(value) => value.to_string(),
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn validates_date_formats() {
+        let tokens = ExifTokens::new();
+
+        assert!(tokens
+            .validate("exif.date", Some("%Y-%m-%d_%H%M%S"))
+            .is_ok());
+        assert!(tokens.validate("exif.date", Some("%Y-%z")).is_err());
+        assert!(tokens.validate("exif.date", Some("%Z")).is_err());
+        assert!(tokens.validate("exif.model", Some("%Y")).is_err());
+    }
+
+    #[test]
+    fn makes_names_safe_for_file_names() {
+        let tokens = ExifTokens::new();
+        let path = Path::new("photo.jpg");
+        let exif = ExifData {
+            make: Some(String::from("Canon\0\0\0")),
+            model: Some(String::from("Canon EOS 250D")),
+            lens: Some(String::from("EF-S18-55mm f/3.5-5.6 IS STM")),
+            ..Default::default()
+        };
+        tokens.cache.read(path, |_| Ok(Some(exif))).unwrap();
+
+        let ctx = TokenContext {
+            name: "photo.jpg",
+            path,
+            index: 0,
+        };
+        let resolve = |token| tokens.resolve(token, None, &ctx).unwrap();
+
+        assert_eq!(
+            resolve("exif.lens").as_deref(),
+            Some("EF-S18-55mm f-3.5-5.6 IS STM")
+        );
+        assert_eq!(resolve("exif.make").as_deref(), Some("Canon"));
+        assert_eq!(resolve("exif.model").as_deref(), Some("Canon EOS 250D"));
+        assert_eq!(resolve("exif.iso"), None);
+    }
+
+    #[test]
+    fn formats_exposures_and_numbers() {
+        assert_eq!(format_exposure(0.004), "1-250");
+        assert_eq!(format_exposure(2.5), "2.5");
+        assert_eq!(format_number(50.0), "50");
+        assert_eq!(format_number(2.8), "2.8");
+    }
+}
@@ -0,0 +1,100 @@
+/// A transformation applied to a token's value, written as `{token|filter}`
+///
+/// - `Upper`: `upper` - Converts to UPPERCASE
+/// - `Lower`: `lower` - Converts to lowercase
+/// - `Trim`: `trim` - Removes leading and trailing whitespace
+/// - `Default`: `default:text` - Uses `text` when the token has no value or is empty
+/// - `Slice`: `slice:start:end` - Keeps the characters from `start` up to, but not
+///   including, `end`. Negative values count from the end and `end` may be left out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Upper,
+    Lower,
+    Trim,
+    Default(String),
+    Slice(i64, Option<i64>),
+}
+impl Filter {
+    /// Parses a filter from its name and optional argument
+    pub(crate) fn parse(name: &str, argument: Option<&str>) -> Result<Self, FilterError> {
+        let no_argument = |filter: Self| match argument {
+            None => Ok(filter),
+            Some(_) => Err(FilterError::Argument(format!(
+                "The '{}' filter does not take an argument",
+                name
+            ))),
+        };
+
+        match name {
+            "upper" => no_argument(Self::Upper),
+            "lower" => no_argument(Self::Lower),
+            "trim" => no_argument(Self::Trim),
+            "default" => Ok(Self::Default(argument.unwrap_or_default().to_string())),
+            "slice" => {
+                let argument = argument.ok_or_else(|| {
+                    FilterError::Name(String::from(
+                        "The 'slice' filter requires a start, such as 'slice:0:3'",
+                    ))
+                })?;
+
+                let parse_index = |value: &str| {
+                    value.trim().parse::<i64>().map_err(|_| {
+                        FilterError::Argument(format!("'{}' is not a valid slice index", value))
+                    })
+                };
+
+                match argument.split_once(':') {
+                    None => Ok(Self::Slice(parse_index(argument)?, None)),
+                    Some((start, "")) => Ok(Self::Slice(parse_index(start)?, None)),
+                    Some((start, end)) => {
+                        Ok(Self::Slice(parse_index(start)?, Some(parse_index(end)?)))
+                    }
+                }
+            }
+            _ => Err(FilterError::Name(format!("Unknown filter '{}'", name))),
+        }
+    }
+
+    /// Applies the filter to a token's value
+    pub(crate) fn apply(&self, value: Option<String>) -> Option<String> {
+        match self {
+            Self::Default(fallback) => match value {
+                Some(value) if !value.is_empty() => Some(value),
+                _ => Some(fallback.clone()),
+            },
+            _ => value.map(|value| match self {
+                Self::Upper => value.to_uppercase(),
+                Self::Lower => value.to_lowercase(),
+                Self::Trim => value.trim().to_string(),
+                Self::Slice(start, end) => slice(&value, *start, *end),
+                Self::Default(_) => value,
+            }),
+        }
+    }
+}
+
+/// Which part of a filter was invalid, so the error can point at it
+pub(crate) enum FilterError {
+    Name(String),
+    Argument(String),
+}
+
+fn slice(value: &str, start: i64, end: Option<i64>) -> String {
+    let len = value.chars().count() as i64;
+    let clamp = |idx: i64| match idx < 0 {
+        true => (len + idx).max(0),
+        false => idx.min(len),
+    };
+
+    let start = clamp(start);
+    let end = end.map(clamp).unwrap_or(len);
+    if end <= start {
+        return String::new();
+    }
+
+    value
+        .chars()
+        .skip(start as usize)
+        .take((end - start) as usize)
+        .collect()
+}
@@ -0,0 +1,248 @@
+use super::token::{TokenContext, TokenProvider};
+use crate::error_factory::create_error;
+use rs_fs::{HashAlgorithm, HashCache, HashProgress};
+use rs_response::{DataResponse, OkDataResponse};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const ERR_SRC: &str = "template::hash::HashTokens";
+
+/// The digests of file contents, available in every `TokenRegistry::new`
+///
+/// | Token         | Argument          | Value                                  |
+/// | ------------- | ----------------- | -------------------------------------- |
+/// | `hash.crc32`  | Length, e.g. `4`  | The CRC-32 of the content, 8 digits    |
+/// | `hash.md5`    | Length, e.g. `8`  | The MD5 of the content, 32 digits      |
+/// | `hash.sha1`   | Length, e.g. `8`  | The SHA-1 of the content, 40 digits    |
+/// | `hash.sha256` | Length, e.g. `8`  | The SHA-256 of the content, 64 digits  |
+/// | `hash.blake3` | Length, e.g. `8`  | The BLAKE3 of the content, 64 digits   |
+///
+/// **NOTE:** Digests are written in lowercase hexadecimal. The argument keeps
+/// only their first digits, e.g. `{hash.sha256:8}`
+///
+/// **NOTE:** When a batch is prepared, every file is hashed on a pool of
+/// worker threads with the algorithms the template uses, reporting progress
+/// to the callback set with `set_progress`. Digests are cached by path, size
+/// and modification time, so unchanged files are only hashed once
+///
+/// **NOTE:** A file that could not be hashed while preparing the batch fails
+/// when its token is resolved, with the reason it could not be read
+///
+/// **NOTE:** `new` creates a cache for this `HashTokens` alone. Keep a
+/// `HashCache` in the application and use `with_cache`, so files are not
+/// hashed again when a template changes
+///
+/// # Methods:
+/// - `new` - Creates a new `HashTokens` with an empty cache
+/// - `with_cache` - Creates a new `HashTokens` with the given cache
+/// - `set_progress` - Sets the callback told about the progress of a batch
+///
+/// # Example:
+/// ```
+/// use rs_fs::HashCache;
+/// use rs_rename::template::{HashTokens, Template, TokenRegistry};
+/// use rs_response::DataResponse;
+/// use std::sync::Arc;
+///
+/// fn dedupe_template(cache: &Arc<HashCache>) -> DataResponse<Template> {
+///   let mut hashes = HashTokens::with_cache(cache.clone());
+///   hashes.set_progress(|progress| {
+///     println!("Hashed {} of {} files", progress.done, progress.total);
+///   });
+///
+///   Template::parse("{stem}_{hash.sha256:8}.{ext}", TokenRegistry::with_hashes(hashes))
+/// }
+/// ```
+pub struct HashTokens {
+    cache: Arc<HashCache>,
+    selected: Vec<HashAlgorithm>,
+    on_progress: Option<Box<dyn Fn(HashProgress) + Send + Sync>>,
+    /// Why each file of the batch that could not be hashed failed
+    failures: HashMap<PathBuf, (String, String)>,
+}
+impl Default for HashTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl HashTokens {
+    /// Creates a new `HashTokens` with an empty cache
+    pub fn new() -> Self {
+        Self::with_cache(Arc::new(HashCache::new()))
+    }
+
+    /// Creates a new `HashTokens` with the given cache, such as one kept by
+    /// the application
+    ///
+    /// # Arguments:
+    /// - `cache`: `Arc<HashCache>` - The cache to read and store digests in
+    pub fn with_cache(cache: Arc<HashCache>) -> Self {
+        Self {
+            cache,
+            selected: Vec::new(),
+            on_progress: None,
+            failures: HashMap::new(),
+        }
+    }
+
+    /// Sets the callback told about the progress of a batch
+    ///
+    /// **NOTE:** The callback is called from the worker threads
+    ///
+    /// # Arguments:
+    /// - `on_progress`: `impl Fn(HashProgress) + Send + Sync + 'static` - Called after every file
+    pub fn set_progress(
+        &mut self,
+        on_progress: impl Fn(HashProgress) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_progress = Some(Box::new(on_progress));
+        self
+    }
+}
+impl TokenProvider for HashTokens {
+    fn tokens(&self) -> Vec<&'static str> {
+        vec![
+            "hash.crc32",
+            "hash.md5",
+            "hash.sha1",
+            "hash.sha256",
+            "hash.blake3",
+        ]
+    }
+
+    fn validate(&self, _token: &str, argument: Option<&str>) -> Result<(), String> {
+        match argument.map(|length| (length, length.parse::<usize>())) {
+            None | Some((_, Ok(1..))) => Ok(()),
+            Some((length, _)) => Err(format!("'{}' is not a valid hash length", length)),
+        }
+    }
+
+    fn select(&mut self, tokens: &[&str]) {
+        self.selected = Vec::new();
+
+        for algorithm in tokens.iter().filter_map(|token| algorithm(token)) {
+            if !self.selected.contains(&algorithm) {
+                self.selected.push(algorithm);
+            }
+        }
+    }
+
+    fn prepare(&mut self, sources: &[PathBuf]) -> DataResponse<()> {
+        self.failures.clear();
+        if self.selected.is_empty() {
+            return Ok(());
+        }
+
+        let warnings = self.cache.hash_batch(sources, &self.selected, |progress| {
+            if let Some(on_progress) = self.on_progress.as_ref() {
+                on_progress(progress);
+            }
+        });
+
+        // Files that cannot be hashed fail when their token is resolved
+        for warning in warnings {
+            if let OkDataResponse::WARNData(warning) = warning {
+                self.failures
+                    .insert(warning.data, (warning.message, warning.cause));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        token: &str,
+        argument: Option<&str>,
+        ctx: &TokenContext,
+    ) -> DataResponse<Option<String>> {
+        let algorithm = match algorithm(token) {
+            Some(algorithm) => algorithm,
+            None => return Ok(None),
+        };
+
+        if let Some((message, cause)) = self.failures.get(ctx.path) {
+            return Err(create_error(message.clone(), cause.clone(), ERR_SRC));
+        }
+
+        let mut digest = self.cache.hash(ctx.path, algorithm)?;
+        if let Some(length) = argument.and_then(|length| length.parse().ok()) {
+            digest.truncate(length);
+        }
+
+        Ok(Some(digest))
+    }
+}
+
+fn algorithm(token: &str) -> Option<HashAlgorithm> {
+    match token {
+        "hash.crc32" => Some(HashAlgorithm::Crc32),
+        "hash.md5" => Some(HashAlgorithm::Md5),
+        "hash.sha1" => Some(HashAlgorithm::Sha1),
+        "hash.sha256" => Some(HashAlgorithm::Sha256),
+        "hash.blake3" => Some(HashAlgorithm::Blake3),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::{Template, TokenRegistry};
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder =
+            std::env::temp_dir().join(format!("rs_rename-hash-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    #[test]
+    fn hashes_the_batch_with_progress() {
+        let folder = temp_folder("batch");
+        let sources = vec![folder.join("a.txt"), folder.join("missing.txt")];
+        fs::write(&sources[0], "abc").unwrap();
+
+        let cache = Arc::new(HashCache::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut hashes = HashTokens::with_cache(cache.clone());
+        let counter = calls.clone();
+        hashes.set_progress(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let mut template = Template::parse(
+            "{hash.crc32}_{hash.md5:6}",
+            TokenRegistry::with_hashes(hashes),
+        )
+        .unwrap();
+        template.prepare(&sources).unwrap();
+
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        assert_eq!(cache.len(), 1);
+
+        let ctx = |index: usize| TokenContext {
+            name: "",
+            path: &sources[index],
+            index,
+        };
+        assert_eq!(template.render(&ctx(0)).unwrap(), "352441c2_900150");
+
+        let error = template.render(&ctx(1)).unwrap_err();
+        assert!(error.message.contains("Unable to hash"));
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn validates_lengths() {
+        let tokens = HashTokens::new();
+
+        assert!(tokens.validate("hash.sha1", Some("8")).is_ok());
+        assert!(tokens.validate("hash.sha1", Some("0")).is_err());
+        assert!(tokens.validate("hash.sha1", Some("x")).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use super::token::{MetadataCache, TokenContext, TokenProvider};
+use rs_fs::{probe_image, ImageInfo};
+use rs_response::DataResponse;
+use std::path::PathBuf;
+
+/// The size and orientation of images, available in every `TokenRegistry::new`
+///
+/// | Token             | Argument | Value                                 |
+/// | ----------------- | -------- | ------------------------------------- |
+/// | `img.w`           |          | The width in pixels                   |
+/// | `img.h`           |          | The height in pixels                  |
+/// | `img.orientation` |          | The EXIF orientation, from `1` to `8` |
+///
+/// **NOTE:** Only the header of PNG, JPEG, GIF, WebP, BMP and TIFF images is
+/// read, so these tokens stay fast on large batches. Other files have no
+/// value for these tokens. Use the `default` filter to provide one
+///
+/// **NOTE:** The width and height are the stored ones, even for photos
+/// displayed rotated by their orientation
+///
+/// **NOTE:** The header of every file is read once per batch
+///
+/// # Methods:
+/// - `new` - Creates a new `ImageTokens` with an empty cache
+pub struct ImageTokens {
+    cache: MetadataCache<ImageInfo>,
+}
+impl Default for ImageTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl ImageTokens {
+    /// Creates a new `ImageTokens` with an empty cache
+    pub fn new() -> Self {
+        Self {
+            cache: MetadataCache::new(),
+        }
+    }
+
+    fn read(&self, ctx: &TokenContext) -> DataResponse<Option<ImageInfo>> {
+        self.cache.read(ctx.path, probe_image)
+    }
+}
+impl TokenProvider for ImageTokens {
+    fn tokens(&self) -> Vec<&'static str> {
+        vec!["img.w", "img.h", "img.orientation"]
+    }
+
+    fn prepare(&mut self, _sources: &[PathBuf]) -> DataResponse<()> {
+        self.cache.clear();
+
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        token: &str,
+        _argument: Option<&str>,
+        ctx: &TokenContext,
+    ) -> DataResponse<Option<String>> {
+        let info = match self.read(ctx)? {
+            Some(info) => info,
+            None => return Ok(None),
+        };
+
+        let value = match token {
+            "img.w" => Some(info.width.to_string()),
+            "img.h" => Some(info.height.to_string()),
+            "img.orientation" => info.orientation.map(|orientation| orientation.to_string()),
+            _ => None,
+        };
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,232 @@
+use super::token::{MetadataCache, TokenContext, TokenProvider};
+use rs_fs::{read_media_info, MediaInfo};
+use rs_response::DataResponse;
+use std::path::PathBuf;
+
+/// The default format of `media.duration`, such as `03m25s`
+const DEFAULT_DURATION_FORMAT: &str = "%Mm%Ss";
+
+/// The technical properties of audio and video files, available in every
+/// `TokenRegistry::new`
+///
+/// | Token              | Argument             | Value                                      |
+/// | ------------------ | -------------------- | ------------------------------------------ |
+/// | `media.duration`   | Format, e.g. `%M-%S` | The duration, e.g. `00m42s`                |
+/// | `media.bitrate`    |                      | The average bitrate in kbit/s, e.g. `320`  |
+/// | `media.samplerate` |                      | The audio sample rate in Hz, e.g. `44100`  |
+/// | `media.channels`   |                      | The number of audio channels, e.g. `2`     |
+/// | `media.width`      |                      | The video width in pixels                  |
+/// | `media.height`     |                      | The video height in pixels                 |
+/// | `media.resolution` |                      | The video size, e.g. `1920x1080`           |
+/// | `media.fps`        |                      | The video frame rate, e.g. `25` or `29.97` |
+///
+/// **NOTE:** Audio properties are read from WAV, FLAC and MP3 files, and
+/// video properties from MP4 and MOV files. Other files have no value for
+/// these tokens. Use the `default` filter to provide one
+///
+/// **NOTE:** Durations are truncated to the second, as media players show
+/// them. Their format supports `%H` (hours), `%M` (minutes), `%S` (seconds),
+/// `%s` (total seconds) and `%%`. Without `%H`, the hours are counted in the
+/// minutes
+///
+/// **NOTE:** The properties of every file are read once per batch
+///
+/// # Methods:
+/// - `new` - Creates a new `MediaTokens` with an empty cache
+///
+/// # Example:
+/// ```
+/// use rs_rename::template::{Template, TokenRegistry};
+///
+/// let template = Template::parse(
+///   "clip_{media.resolution}_{media.duration}.{ext}",
+///   TokenRegistry::new(),
+/// );
+///
+/// assert!(template.is_ok());
+/// ```
+pub struct MediaTokens {
+    cache: MetadataCache<MediaInfo>,
+}
+impl Default for MediaTokens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl MediaTokens {
+    /// Creates a new `MediaTokens` with an empty cache
+    pub fn new() -> Self {
+        Self {
+            cache: MetadataCache::new(),
+        }
+    }
+
+    fn read(&self, ctx: &TokenContext) -> DataResponse<Option<MediaInfo>> {
+        self.cache.read(ctx.path, read_media_info)
+    }
+}
+impl TokenProvider for MediaTokens {
+    fn tokens(&self) -> Vec<&'static str> {
+        vec![
+            "media.duration",
+            "media.bitrate",
+            "media.samplerate",
+            "media.channels",
+            "media.width",
+            "media.height",
+            "media.resolution",
+            "media.fps",
+        ]
+    }
+
+    fn validate(&self, token: &str, argument: Option<&str>) -> Result<(), String> {
+        match (token, argument) {
+            (_, None) => Ok(()),
+            ("media.duration", Some(format)) => validate_duration_format(format),
+            (_, Some(_)) => Err(format!("The '{}' token does not take an argument", token)),
+        }
+    }
+
+    fn prepare(&mut self, _sources: &[PathBuf]) -> DataResponse<()> {
+        self.cache.clear();
+
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        token: &str,
+        argument: Option<&str>,
+        ctx: &TokenContext,
+    ) -> DataResponse<Option<String>> {
+        let info = match self.read(ctx)? {
+            Some(info) => info,
+            None => return Ok(None),
+        };
+
+        let value = match token {
+            "media.duration" => info.duration.map(|duration| {
+                format_duration(duration, argument.unwrap_or(DEFAULT_DURATION_FORMAT))
+            }),
+            "media.bitrate" => info.bitrate.map(|bitrate| bitrate.to_string()),
+            "media.samplerate" => info.sample_rate.map(|rate| rate.to_string()),
+            "media.channels" => info.channels.map(|channels| channels.to_string()),
+            "media.width" => info.width.map(|width| width.to_string()),
+            "media.height" => info.height.map(|height| height.to_string()),
+            "media.resolution" => match (info.width, info.height) {
+                (Some(width), Some(height)) => Some(format!("{}x{}", width, height)),
+                _ => None,
+            },
+            "media.fps" => info.frame_rate.map(format_frame_rate),
+            _ => None,
+        };
+
+        Ok(value)
+    }
+}
+
+fn validate_duration_format(format: &str) -> Result<(), String> {
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+
+        match chars.next() {
+            Some('H') | Some('M') | Some('S') | Some('s') | Some('%') => {}
+            Some(other) => {
+                return Err(format!(
+                    "'%{}' is not a duration specifier. Use %H, %M, %S, %s or %%",
+                    other
+                ))
+            }
+            None => return Err(String::from("The duration format ends with a lone '%'")),
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a duration with `%H`, `%M`, `%S` and `%s` specifiers
+fn format_duration(seconds: f64, format: &str) -> String {
+    let total = seconds.max(0.0).floor() as u64;
+    let hours = match format.contains("%H") {
+        true => total / 3600,
+        false => 0,
+    };
+    let minutes = (total - hours * 3600) / 60;
+
+    let mut formatted = String::new();
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            formatted.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('H') => formatted.push_str(&format!("{:02}", hours)),
+            Some('M') => formatted.push_str(&format!("{:02}", minutes)),
+            Some('S') => formatted.push_str(&format!("{:02}", total % 60)),
+            Some('s') => formatted.push_str(&total.to_string()),
+            Some(other) => formatted.push(other),
+            None => {}
+        }
+    }
+
+    formatted
+}
+
+/// Writes a frame rate with at most two decimals, without trailing zeros
+fn format_frame_rate(fps: f64) -> String {
+    let fps = format!("{:.2}", fps);
+
+    fps.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(0.0, DEFAULT_DURATION_FORMAT), "00m00s");
+        assert_eq!(format_duration(205.9, DEFAULT_DURATION_FORMAT), "03m25s");
+        assert_eq!(format_duration(-3.0, "%s"), "0");
+        assert_eq!(format_duration(100.0, "100%%_%s"), "100%_100");
+    }
+
+    #[test]
+    fn counts_hours_in_minutes_without_them() {
+        let duration = 2.0 * 3600.0 + 5.0 * 60.0 + 7.0;
+
+        assert_eq!(format_duration(duration, "%H-%M-%S"), "02-05-07");
+        assert_eq!(format_duration(duration, "%Mm%Ss"), "125m07s");
+        assert_eq!(format_duration(duration, "%s"), "7507");
+    }
+
+    #[test]
+    fn validates_duration_formats() {
+        assert!(validate_duration_format("%H.%M.%S %s %%").is_ok());
+        assert_eq!(
+            validate_duration_format("%M-%d").unwrap_err(),
+            "'%d' is not a duration specifier. Use %H, %M, %S, %s or %%"
+        );
+        assert!(validate_duration_format("%M%").is_err());
+        assert!(MediaTokens::new()
+            .validate("media.duration", Some("%Y"))
+            .is_err());
+        assert!(MediaTokens::new()
+            .validate("media.bitrate", Some("%M"))
+            .is_err());
+    }
+
+    #[test]
+    fn formats_frame_rates() {
+        assert_eq!(format_frame_rate(25.0), "25");
+        assert_eq!(format_frame_rate(29.97002997), "29.97");
+        assert_eq!(format_frame_rate(23.5), "23.5");
+    }
+}
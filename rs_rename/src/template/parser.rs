@@ -0,0 +1,222 @@
+use super::filter::{Filter, FilterError};
+use super::token::TokenRegistry;
+
+/// A range of characters in the template, counted from `0`, `end` excluded
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+impl Span {
+    fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+type ParseResult<T> = Result<T, (String, Span)>;
+
+pub enum Segment {
+    Literal(String),
+    Token(TokenExpr),
+}
+
+pub struct TokenExpr {
+    pub name: String,
+    pub argument: Option<String>,
+    pub filters: Vec<Filter>,
+}
+
+/// Parses a template into its literal and token segments
+///
+/// **NOTE:** Token names and arguments are validated against the registry
+pub fn parse(source: &str, registry: &TokenRegistry) -> ParseResult<Vec<Segment>> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        pos: 0,
+        registry,
+    };
+
+    parser.parse()
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    registry: &'a TokenRegistry,
+}
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_next(&self) -> Option<char> {
+        self.chars.get(self.pos + 1).copied()
+    }
+
+    fn parse(&mut self) -> ParseResult<Vec<Segment>> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+
+        while let Some(c) = self.peek() {
+            match (c, self.peek_next()) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    literal.push(c);
+                    self.pos += 2;
+                }
+                ('{', _) => {
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Token(self.parse_token()?));
+                }
+                ('}', _) => {
+                    return Err((
+                        String::from("Unmatched '}', use '}}' for a literal brace"),
+                        Span::new(self.pos, self.pos + 1),
+                    ))
+                }
+                _ => {
+                    literal.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(segments)
+    }
+
+    fn parse_token(&mut self) -> ParseResult<TokenExpr> {
+        let open = self.pos;
+        self.pos += 1;
+
+        let (name, name_span) = self.read_name(open)?;
+        if name.is_empty() {
+            return Err((
+                String::from("Missing token name"),
+                Span::new(open, self.pos + 1),
+            ));
+        }
+
+        let provider = self
+            .registry
+            .find(&name)
+            .ok_or_else(|| (format!("Unknown token '{}'", name), name_span))?;
+
+        let mut argument = None;
+        let mut argument_span = name_span;
+        if self.peek() == Some(':') {
+            self.pos += 1;
+            let (text, span) = self.read_argument(open)?;
+            argument = Some(text);
+            argument_span = span;
+        }
+
+        provider
+            .validate(&name, argument.as_deref())
+            .map_err(|message| (message, argument_span))?;
+
+        let mut filters = Vec::new();
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            filters.push(self.parse_filter(open)?);
+        }
+
+        match self.peek() {
+            Some('}') => self.pos += 1,
+            _ => return Err(self.unclosed(open)),
+        }
+
+        Ok(TokenExpr {
+            name,
+            argument,
+            filters,
+        })
+    }
+
+    fn parse_filter(&mut self, open: usize) -> ParseResult<Filter> {
+        let (name, name_span) = self.read_name(open)?;
+        if name.is_empty() {
+            return Err((
+                String::from("Missing filter name"),
+                Span::new(self.pos - 1, self.pos),
+            ));
+        }
+
+        let mut argument = None;
+        let mut argument_span = name_span;
+        if self.peek() == Some(':') {
+            self.pos += 1;
+            let (text, span) = self.read_argument(open)?;
+            argument = Some(text);
+            argument_span = span;
+        }
+
+        Filter::parse(&name, argument.as_deref()).map_err(|e| match e {
+            FilterError::Name(message) => (message, name_span),
+            FilterError::Argument(message) => (message, argument_span),
+        })
+    }
+
+    /// Reads a token or filter name, up to the next `:`, `|` or `}`
+    fn read_name(&mut self, open: usize) -> ParseResult<(String, Span)> {
+        let start = self.pos;
+
+        while let Some(c) = self.peek() {
+            match c {
+                ':' | '|' | '}' => break,
+                c if c.is_alphanumeric() || c == '_' || c == '.' => self.pos += 1,
+                '{' => return Err(self.unclosed(open)),
+                c => {
+                    return Err((
+                        format!("Unexpected character '{}' in name", c),
+                        Span::new(self.pos, self.pos + 1),
+                    ))
+                }
+            }
+        }
+
+        if self.peek().is_none() {
+            return Err(self.unclosed(open));
+        }
+
+        let name = self.chars[start..self.pos].iter().collect();
+        Ok((name, Span::new(start, self.pos)))
+    }
+
+    /// Reads an argument, up to the next unescaped `|` or `}`
+    fn read_argument(&mut self, open: usize) -> ParseResult<(String, Span)> {
+        let start = self.pos;
+        let mut text = String::new();
+
+        loop {
+            match self.peek() {
+                None => return Err(self.unclosed(open)),
+                Some('|') | Some('}') => break,
+                Some('\\') => match self.peek_next() {
+                    None => return Err(self.unclosed(open)),
+                    Some(escaped) => {
+                        text.push(escaped);
+                        self.pos += 2;
+                    }
+                },
+                Some(c) => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        Ok((text, Span::new(start, self.pos)))
+    }
+
+    fn unclosed(&self, open: usize) -> (String, Span) {
+        (
+            String::from("Unclosed '{', expected '}'"),
+            Span::new(open, self.chars.len()),
+        )
+    }
+}
@@ -0,0 +1,349 @@
+use crate::error_factory::create_error;
+use chrono::{Duration, NaiveDateTime};
+use rs_response::DataResponse;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const ERR_SRC: &str = "template::shift::TimeShift";
+
+/// The largest shift, in days, either way
+const MAX_SHIFT_DAYS: i64 = 10_000;
+
+/// An offset to correct a camera clock, such as `+2h` or `-1d 3m`
+///
+/// # Syntax:
+/// - An optional `+` or `-` sign, which applies to the whole shift
+/// - One or more amounts followed by a unit: `d` (days), `h` (hours),
+///   `m` (minutes) or `s` (seconds), optionally separated by spaces
+/// - At most 10000 days either way
+///
+/// # Methods:
+/// - `parse` - Parses a shift such as `+2h` or `-1d 3m`
+/// - `seconds` - The shift in seconds
+/// - `apply` - Shifts a date
+/// - `apply_to_time` - Shifts a file system time
+///
+/// # Example:
+/// ```
+/// use rs_rename::template::TimeShift;
+///
+/// let shift = TimeShift::parse("-1d 3m").unwrap();
+///
+/// assert_eq!(shift.seconds(), -(24 * 3600 + 3 * 60));
+/// assert_eq!(shift.to_string(), "-1d 3m");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeShift {
+    seconds: i64,
+}
+impl TimeShift {
+    /// Parses a shift such as `+2h` or `-1d 3m`
+    ///
+    /// **NOTE:** The typographic minus sign `−` is accepted as well
+    ///
+    /// # Arguments:
+    /// - `shift`: `&str` - The shift to parse
+    pub fn parse(shift: &str) -> DataResponse<Self> {
+        let invalid =
+            |cause: String| create_error(format!("Invalid time shift '{}'", shift), cause, ERR_SRC);
+
+        let trimmed = shift.trim();
+        let (sign, rest) = match trimmed.chars().next() {
+            Some('+') => (1, &trimmed[1..]),
+            Some('-') => (-1, &trimmed[1..]),
+            Some('−') => (-1, &trimmed['−'.len_utf8()..]),
+            _ => (1, trimmed),
+        };
+
+        let mut seconds: i64 = 0;
+        let mut amount = String::new();
+        let mut parts = 0;
+
+        for c in rest.chars() {
+            match c {
+                '0'..='9' => amount.push(c),
+                ' ' if amount.is_empty() => continue,
+                'd' | 'h' | 'm' | 's' if !amount.is_empty() => {
+                    let unit = match c {
+                        'd' => 86_400,
+                        'h' => 3_600,
+                        'm' => 60,
+                        _ => 1,
+                    };
+
+                    seconds = amount
+                        .parse::<i64>()
+                        .ok()
+                        .and_then(|amount| amount.checked_mul(unit))
+                        .and_then(|part| seconds.checked_add(part))
+                        .ok_or_else(|| invalid(String::from("The shift is too large")))?;
+
+                    amount.clear();
+                    parts += 1;
+                }
+                _ if !amount.is_empty() => {
+                    return Err(invalid(format!(
+                        "'{}' must be followed by a unit: d, h, m or s",
+                        amount
+                    )))
+                }
+                _ => {
+                    return Err(invalid(format!(
+                        "Unexpected '{}'. Use amounts with a unit, such as +2h or -1d 3m",
+                        c
+                    )))
+                }
+            }
+        }
+
+        if !amount.is_empty() {
+            return Err(invalid(format!(
+                "'{}' must be followed by a unit: d, h, m or s",
+                amount
+            )));
+        }
+
+        if parts == 0 {
+            return Err(invalid(String::from(
+                "The shift is empty. Use amounts with a unit, such as +2h or -1d 3m",
+            )));
+        }
+
+        if seconds > MAX_SHIFT_DAYS * 86_400 {
+            return Err(invalid(format!(
+                "The shift is too large. It can be at most {} days",
+                MAX_SHIFT_DAYS
+            )));
+        }
+
+        Ok(Self {
+            seconds: sign * seconds,
+        })
+    }
+
+    /// The shift in seconds
+    pub fn seconds(&self) -> i64 {
+        self.seconds
+    }
+
+    /// Shifts a date
+    ///
+    /// **NOTE:** Returns `None` if the result is out of range
+    pub fn apply(&self, date: NaiveDateTime) -> Option<NaiveDateTime> {
+        date.checked_add_signed(Duration::try_seconds(self.seconds)?)
+    }
+
+    /// Shifts a file system time
+    ///
+    /// **NOTE:** Returns `None` if the result is out of range
+    pub fn apply_to_time(&self, time: SystemTime) -> Option<SystemTime> {
+        let offset = std::time::Duration::from_secs(self.seconds.unsigned_abs());
+
+        match self.seconds < 0 {
+            true => time.checked_sub(offset),
+            false => time.checked_add(offset),
+        }
+    }
+}
+impl fmt::Display for TimeShift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.seconds.unsigned_abs();
+        let mut parts = Vec::new();
+
+        for (unit, size) in [("d", 86_400), ("h", 3_600), ("m", 60), ("s", 1)] {
+            if rest >= size {
+                parts.push(format!("{}{}", rest / size, unit));
+                rest %= size;
+            }
+        }
+
+        if parts.is_empty() {
+            parts.push(String::from("0s"));
+        }
+
+        let sign = if self.seconds < 0 { "-" } else { "+" };
+        write!(f, "{}{}", sign, parts.join(" "))
+    }
+}
+
+/// The clock corrections of a batch, by camera model and by selected files
+///
+/// **NOTE:** A shift set for a file takes precedence over the shift of its
+/// camera model. Camera models are matched ignoring case and surrounding
+/// spaces
+///
+/// **NOTE:** Only `exif.date` knows the camera model of a file. The other
+/// date tokens (`mtime`, `created`, `doc.created` and `doc.modified`) are
+/// shifted by file, and refuse `ClockShifts` with camera model shifts
+///
+/// # Methods:
+/// - `new` - Creates `ClockShifts` without any correction
+/// - `for_model` - Shifts the dates of every photo taken with a camera model
+/// - `for_files` - Shifts the dates of a selection of files
+/// - `is_empty` - Whether no correction is set
+/// - `find` - The shift for a file and camera model, if any
+///
+/// # Example:
+/// ```
+/// use rs_rename::template::{ClockShifts, ExifTokens, FileTokens, TimeShift, TokenRegistry};
+/// use rs_response::DataResponse;
+///
+/// fn trip_registry() -> DataResponse<TokenRegistry> {
+///   let mut shifts = ClockShifts::new();
+///   shifts.for_model("X-T4", TimeShift::parse("+2h")?);
+///   shifts.for_model("EOS R6", TimeShift::parse("-1d 3m")?);
+///
+///   let mut registry = TokenRegistry::empty();
+///   registry
+///     .register(FileTokens::new())
+///     .register(ExifTokens::with_shifts(shifts));
+///
+///   Ok(registry)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ClockShifts {
+    models: HashMap<String, TimeShift>,
+    files: HashMap<PathBuf, TimeShift>,
+}
+impl ClockShifts {
+    /// Creates `ClockShifts` without any correction
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shifts the dates of every photo taken with a camera model
+    ///
+    /// # Arguments:
+    /// - `model`: `&str` - The camera model, as recorded in the EXIF metadata
+    /// - `shift`: `TimeShift` - The correction to apply
+    pub fn for_model(&mut self, model: &str, shift: TimeShift) -> &mut Self {
+        self.models.insert(model_key(model), shift);
+        self
+    }
+
+    /// Shifts the dates of a selection of files
+    ///
+    /// # Arguments:
+    /// - `paths`: `impl IntoIterator<Item = PathBuf>` - The original paths of the files
+    /// - `shift`: `TimeShift` - The correction to apply
+    pub fn for_files(
+        &mut self,
+        paths: impl IntoIterator<Item = PathBuf>,
+        shift: TimeShift,
+    ) -> &mut Self {
+        for path in paths {
+            self.files.insert(path, shift);
+        }
+        self
+    }
+
+    /// Whether no correction is set
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty() && self.files.is_empty()
+    }
+
+    /// The shift for a file and camera model, if any
+    ///
+    /// # Arguments:
+    /// - `path`: `&Path` - The original path of the file
+    /// - `model`: `Option<&str>` - The camera model of the file
+    pub fn find(&self, path: &Path, model: Option<&str>) -> Option<TimeShift> {
+        self.files
+            .get(path)
+            .copied()
+            .or_else(|| model.and_then(|model| self.models.get(&model_key(model)).copied()))
+    }
+
+    /// Refuses camera model shifts for a date token that does not know the
+    /// camera model of a file, as they would never be applied
+    pub(crate) fn validate_by_file(&self, token: &str) -> Result<(), String> {
+        match self.models.is_empty() {
+            true => Ok(()),
+            false => Err(format!(
+                "'{}' cannot be shifted by camera model, only 'exif.date' can. Shift a selection of files instead",
+                token
+            )),
+        }
+    }
+}
+
+fn model_key(model: &str) -> String {
+    model.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn date() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 4, 1)
+            .and_then(|date| date.and_hms_opt(11, 30, 0))
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_and_applies_shifts() {
+        let shift = TimeShift::parse("+1d 2h").unwrap();
+
+        assert_eq!(shift.seconds(), 26 * 3600);
+        assert_eq!(
+            shift.apply(date()).unwrap().to_string(),
+            "2023-04-02 13:30:00"
+        );
+        assert_eq!(TimeShift::parse("−90m").unwrap().to_string(), "-1h 30m");
+    }
+
+    #[test]
+    fn refuses_malformed_shifts() {
+        for shift in ["", "+", "2", "2x", "h2", "1d 2"] {
+            assert!(TimeShift::parse(shift).is_err(), "{}", shift);
+        }
+    }
+
+    #[test]
+    fn refuses_shifts_that_are_too_large() {
+        assert!(TimeShift::parse("+9999999999999999s").is_err());
+        assert!(TimeShift::parse("-10001d").is_err());
+        assert!(TimeShift::parse("+99999999999999999999d").is_err());
+        assert!(TimeShift::parse("+10000d").is_ok());
+    }
+
+    #[test]
+    fn never_panics_when_applied() {
+        let shift = TimeShift { seconds: i64::MAX };
+
+        assert_eq!(shift.apply(date()), None);
+        assert_eq!(shift.apply_to_time(SystemTime::now()), None);
+    }
+
+    #[test]
+    fn applies_shifts_to_file_times() {
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(100_000);
+
+        assert_eq!(
+            TimeShift::parse("+1m").unwrap().apply_to_time(time),
+            Some(time + std::time::Duration::from_secs(60))
+        );
+        assert_eq!(
+            TimeShift::parse("-1d").unwrap().apply_to_time(time),
+            Some(time - std::time::Duration::from_secs(86_400))
+        );
+    }
+
+    #[test]
+    fn refuses_model_shifts_for_dates_shifted_by_file() {
+        let mut shifts = ClockShifts::new();
+        shifts.for_files([PathBuf::from("a.jpg")], TimeShift::parse("+1h").unwrap());
+        assert!(shifts.validate_by_file("mtime").is_ok());
+
+        shifts.for_model("X-T4", TimeShift::parse("+2h").unwrap());
+        assert!(shifts
+            .validate_by_file("mtime")
+            .unwrap_err()
+            .contains("only 'exif.date' can"));
+    }
+}
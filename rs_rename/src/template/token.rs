@@ -0,0 +1,310 @@
+use super::audio::AudioTokens;
+use super::builtin::FileTokens;
+use super::document::DocumentTokens;
+use super::exif::ExifTokens;
+use super::hash::HashTokens;
+use super::image::ImageTokens;
+use super::media::MediaTokens;
+use rs_response::DataResponse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Information about the file a template is rendered for
+///
+/// # Properties:
+/// - `name`: `&str` - The file name produced by the previous rules
+/// - `path`: `&Path` - The original path of the file
+/// - `index`: `usize` - The position of the file in the batch
+pub struct TokenContext<'a> {
+    pub name: &'a str,
+    pub path: &'a Path,
+    pub index: usize,
+}
+
+/// A source of template tokens, such as file system or EXIF metadata
+///
+/// # Methods:
+/// - `tokens` - The token names this provider resolves
+/// - `validate` - Checks the argument of a token when the template is parsed
+/// - `select` - Called once the template is parsed, with the tokens it uses
+/// - `prepare` - Called once with the whole batch before any template is rendered
+/// - `resolve` - Gets the value of a token for a single file
+///
+/// # Example:
+/// ```
+/// use rs_rename::template::{Template, TokenContext, TokenProvider, TokenRegistry};
+/// use rs_response::DataResponse;
+///
+/// struct Project;
+///
+/// impl TokenProvider for Project {
+///   fn tokens(&self) -> Vec<&'static str> {
+///     vec!["project"]
+///   }
+///
+///   fn resolve(
+///     &self,
+///     _token: &str,
+///     _argument: Option<&str>,
+///     _ctx: &TokenContext,
+///   ) -> DataResponse<Option<String>> {
+///     Ok(Some(String::from("apollo")))
+///   }
+/// }
+///
+/// let mut registry = TokenRegistry::new();
+/// registry.register(Project);
+///
+/// let template = Template::parse("{project}_{name}", registry).unwrap();
+/// ```
+pub trait TokenProvider: Send + Sync {
+    /// The token names this provider resolves, such as `"stem"` or `"exif.iso"`
+    fn tokens(&self) -> Vec<&'static str>;
+
+    /// Checks the argument of a token when the template is parsed
+    ///
+    /// **NOTE:** The returned message is shown to the user along with the
+    /// column of the argument. The default implementation rejects any argument
+    fn validate(&self, token: &str, argument: Option<&str>) -> Result<(), String> {
+        match argument {
+            None => Ok(()),
+            Some(_) => Err(format!("The '{}' token does not take an argument", token)),
+        }
+    }
+
+    /// Called once the template is parsed, with the tokens of this provider
+    /// it uses, so `prepare` only does the work those tokens need
+    ///
+    /// **NOTE:** The default implementation does nothing
+    fn select(&mut self, _tokens: &[&str]) {}
+
+    /// Called once with the source paths of the whole batch before any
+    /// template is rendered
+    ///
+    /// **NOTE:** The default implementation does nothing
+    fn prepare(&mut self, _sources: &[PathBuf]) -> DataResponse<()> {
+        Ok(())
+    }
+
+    /// Gets the value of a token for a single file
+    ///
+    /// **NOTE:** `None` means the file has no value for this token
+    ///
+    /// # Arguments:
+    /// - `token`: `&str` - The name of the token
+    /// - `argument`: `Option<&str>` - The argument given in the template, already validated
+    /// - `ctx`: `&TokenContext` - The file to get the value for
+    fn resolve(
+        &self,
+        token: &str,
+        argument: Option<&str>,
+        ctx: &TokenContext,
+    ) -> DataResponse<Option<String>>;
+}
+
+/// The set of `TokenProvider`s a template may use
+///
+/// # Methods:
+/// - `new` - Creates a `TokenRegistry` with every built-in provider
+/// - `with_hashes` - Creates a `TokenRegistry` with every built-in provider, using the given `HashTokens`
+/// - `empty` - Creates a `TokenRegistry` without any providers
+/// - `register` - Adds a `TokenProvider`
+/// - `tokens` - Every registered token name
+pub struct TokenRegistry {
+    providers: Vec<Box<dyn TokenProvider>>,
+}
+impl Default for TokenRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl TokenRegistry {
+    /// Creates a `TokenRegistry` with every built-in provider
+    ///
+    /// **NOTE:** Those are `FileTokens`, `ExifTokens`, `ImageTokens`,
+    /// `AudioTokens`, `MediaTokens`, `DocumentTokens` and `HashTokens`
+    pub fn new() -> Self {
+        Self::with_hashes(HashTokens::new())
+    }
+
+    /// Creates a `TokenRegistry` with every built-in provider, using the given
+    /// `HashTokens`, such as one with a shared cache and a progress callback
+    ///
+    /// # Arguments:
+    /// - `hashes`: `HashTokens` - The provider of the `hash.*` tokens
+    pub fn with_hashes(hashes: HashTokens) -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register(FileTokens::new())
+            .register(ExifTokens::new())
+            .register(ImageTokens::new())
+            .register(AudioTokens::new())
+            .register(MediaTokens::new())
+            .register(DocumentTokens::new())
+            .register(hashes);
+        registry
+    }
+
+    /// Creates a `TokenRegistry` without any providers
+    pub fn empty() -> Self {
+        Self {
+            providers: Vec::new(),
+        }
+    }
+
+    /// Adds a `TokenProvider`
+    ///
+    /// **NOTE:** If two providers resolve the same token, the one
+    /// registered first is used
+    ///
+    /// # Arguments:
+    /// - `provider`: `impl TokenProvider + 'static` - The provider to add
+    pub fn register(&mut self, provider: impl TokenProvider + 'static) -> &mut Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Every registered token name
+    pub fn tokens(&self) -> Vec<&'static str> {
+        self.providers
+            .iter()
+            .flat_map(|provider| provider.tokens())
+            .collect()
+    }
+
+    pub(crate) fn find(&self, token: &str) -> Option<&dyn TokenProvider> {
+        self.providers
+            .iter()
+            .find(|provider| provider.tokens().contains(&token))
+            .map(|provider| provider.as_ref())
+    }
+
+    pub(crate) fn select(&mut self, tokens: &[&str]) {
+        let mut selected: Vec<Vec<&str>> = vec![Vec::new(); self.providers.len()];
+
+        for token in tokens.iter() {
+            if let Some(index) = self
+                .providers
+                .iter()
+                .position(|provider| provider.tokens().contains(token))
+            {
+                selected[index].push(token);
+            }
+        }
+
+        for (provider, tokens) in self.providers.iter_mut().zip(selected) {
+            provider.select(&tokens);
+        }
+    }
+
+    pub(crate) fn prepare(&mut self, sources: &[PathBuf]) -> DataResponse<()> {
+        for provider in self.providers.iter_mut() {
+            provider.prepare(sources)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The metadata a `TokenProvider` read for the files of a batch, so every
+/// file is only read once
+///
+/// **NOTE:** Files without metadata are cached too, as `None`
+///
+/// # Methods:
+/// - `new` - Creates an empty `MetadataCache`
+/// - `read` - Gets the metadata of a file, reading it on the first call
+/// - `clear` - Forgets every file, called by `prepare` for each new batch
+pub(crate) struct MetadataCache<T> {
+    entries: Mutex<HashMap<PathBuf, Option<T>>>,
+}
+impl<T> Default for MetadataCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> MetadataCache<T> {
+    /// Creates an empty `MetadataCache`
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forgets every file, called by `prepare` for each new batch
+    pub(crate) fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+impl<T: Clone> MetadataCache<T> {
+    /// Gets the metadata of a file, reading it on the first call
+    ///
+    /// **NOTE:** Errors are not cached, so the file is read again on the next call
+    ///
+    /// # Arguments:
+    /// - `path`: `&Path` - The file to get the metadata of
+    /// - `read`: `impl FnOnce(&Path) -> DataResponse<Option<T>>` - Reads the metadata of the file
+    pub(crate) fn read(
+        &self,
+        path: &Path,
+        read: impl FnOnce(&Path) -> DataResponse<Option<T>>,
+    ) -> DataResponse<Option<T>> {
+        if let Some(value) = self
+            .entries
+            .lock()
+            .ok()
+            .and_then(|entries| entries.get(path).cloned())
+        {
+            return Ok(value);
+        }
+
+        let value = read(path)?;
+
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(path.to_path_buf(), value.clone());
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_factory::create_error;
+    use std::cell::Cell;
+
+    #[test]
+    fn reads_every_file_once_per_batch() {
+        let cache = MetadataCache::new();
+        let reads = Cell::new(0);
+        let read = |path: &Path| {
+            reads.set(reads.get() + 1);
+            Ok(path.extension().map(|extension| extension.len()))
+        };
+
+        assert_eq!(cache.read(Path::new("a.jpg"), read).unwrap(), Some(3));
+        assert_eq!(cache.read(Path::new("a.jpg"), read).unwrap(), Some(3));
+        assert_eq!(cache.read(Path::new("b"), read).unwrap(), None);
+        assert_eq!(cache.read(Path::new("b"), read).unwrap(), None);
+        assert_eq!(reads.get(), 2);
+
+        cache.clear();
+        assert_eq!(cache.read(Path::new("a.jpg"), read).unwrap(), Some(3));
+        assert_eq!(reads.get(), 3);
+    }
+
+    #[test]
+    fn does_not_cache_errors() {
+        let cache = MetadataCache::new();
+        let path = Path::new("a.jpg");
+
+        let failed = cache.read(path, |_| Err(create_error("Unable to read", "", "test")));
+
+        assert!(failed.is_err());
+        assert_eq!(cache.read(path, |_| Ok(Some(1))).unwrap(), Some(1));
+    }
+}
@@ -1,20 +1,46 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use rs_db::{DbLocation, DbManager};
+use tauri::State;
+
 // Learn more about Tauri commands at https://tauri.app/v1/guides/features/command
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Upgrades every database to its latest schema, stopping at the first failure
+fn upgrade_databases(db_manager: &DbManager) -> rs_response::ResponseVec {
+    Ok(vec![
+        rs_db::settings_db::initialize_settings_db(db_manager)?,
+        rs_db::history_db::initialize_history_db(db_manager)?,
+    ])
+}
+
+#[tauri::command]
+fn initialize_databases(db_manager: State<DbManager>) -> rs_response::ResponseVec {
+    upgrade_databases(&db_manager)
+}
+
 fn main() {
-    // match rs_db::DB::Settings.connect() {
-    //     Ok(_db) => println!("Connected to Settings db"),
-    //     Err(errs) => eprintln!("Error connecting to settings db:\n{:#?}", errs),
-    // }
+    let db_manager = match DbLocation::from_args(std::env::args().skip(1))
+        .and_then(DbManager::with_location)
+    {
+        Ok(db_manager) => db_manager,
+        Err(err) => {
+            eprintln!("Error opening the databases:\n{:#?}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(err) = upgrade_databases(&db_manager) {
+        eprintln!("Error initializing the databases:\n{:#?}", err);
+    }
 
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![greet,])
+        .manage(db_manager)
+        .invoke_handler(tauri::generate_handler![greet, initialize_databases])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }